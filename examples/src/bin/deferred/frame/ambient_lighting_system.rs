@@ -172,6 +172,37 @@ impl AmbientLightingSystem {
         color_input: Arc<ImageView>,
         ambient_color: [f32; 3],
     ) -> Arc<SecondaryAutoCommandBuffer> {
+        let mut builder = AutoCommandBufferBuilder::secondary(
+            self.command_buffer_allocator.as_ref(),
+            self.gfx_queue.queue_family_index(),
+            CommandBufferUsage::MultipleSubmit,
+            CommandBufferInheritanceInfo {
+                render_pass: Some(self.subpass.clone().into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        self.record(
+            &mut builder,
+            viewport_dimensions,
+            color_input,
+            ambient_color,
+        );
+        builder.build().unwrap()
+    }
+
+    /// Records the ambient lighting commands of `draw` into `builder` instead of a new secondary
+    /// command buffer. [`LightingPass::ambient_light`] uses this when the frame records its
+    /// subpasses inline.
+    ///
+    /// [`LightingPass::ambient_light`]: super::LightingPass::ambient_light
+    pub fn record<L>(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<L>,
+        viewport_dimensions: [u32; 2],
+        color_input: Arc<ImageView>,
+        ambient_color: [f32; 3],
+    ) {
         let push_constants = fs::PushConstants {
             color: [ambient_color[0], ambient_color[1], ambient_color[2], 1.0],
         };
@@ -191,16 +222,6 @@ impl AmbientLightingSystem {
             depth_range: 0.0..=1.0,
         };
 
-        let mut builder = AutoCommandBufferBuilder::secondary(
-            self.command_buffer_allocator.as_ref(),
-            self.gfx_queue.queue_family_index(),
-            CommandBufferUsage::MultipleSubmit,
-            CommandBufferInheritanceInfo {
-                render_pass: Some(self.subpass.clone().into()),
-                ..Default::default()
-            },
-        )
-        .unwrap();
         builder
             .set_viewport(0, [viewport].into_iter().collect())
             .unwrap()
@@ -219,7 +240,6 @@ impl AmbientLightingSystem {
             .unwrap()
             .draw(self.vertex_buffer.len() as u32, 1, 0, 0)
             .unwrap();
-        builder.build().unwrap()
     }
 }
 
@@ -182,6 +182,38 @@ impl DirectionalLightingSystem {
         direction: Vector3<f32>,
         color: [f32; 3],
     ) -> Arc<SecondaryAutoCommandBuffer> {
+        let mut builder = AutoCommandBufferBuilder::secondary(
+            self.command_buffer_allocator.as_ref(),
+            self.gfx_queue.queue_family_index(),
+            CommandBufferUsage::MultipleSubmit,
+            CommandBufferInheritanceInfo {
+                render_pass: Some(self.subpass.clone().into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        self.record(
+            &mut builder,
+            viewport_dimensions,
+            color_input,
+            normals_input,
+            direction,
+            color,
+        );
+        builder.build().unwrap()
+    }
+
+    /// Like `draw`, but records the directional lighting commands into `builder`, which is the
+    /// primary command buffer of the frame when subpasses are recorded inline.
+    pub fn record<L>(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<L>,
+        viewport_dimensions: [u32; 2],
+        color_input: Arc<ImageView>,
+        normals_input: Arc<ImageView>,
+        direction: Vector3<f32>,
+        color: [f32; 3],
+    ) {
         let push_constants = fs::PushConstants {
             color: [color[0], color[1], color[2], 1.0],
             direction: direction.extend(0.0).into(),
@@ -205,16 +237,6 @@ impl DirectionalLightingSystem {
             depth_range: 0.0..=1.0,
         };
 
-        let mut builder = AutoCommandBufferBuilder::secondary(
-            self.command_buffer_allocator.as_ref(),
-            self.gfx_queue.queue_family_index(),
-            CommandBufferUsage::MultipleSubmit,
-            CommandBufferInheritanceInfo {
-                render_pass: Some(self.subpass.clone().into()),
-                ..Default::default()
-            },
-        )
-        .unwrap();
         builder
             .set_viewport(0, [viewport].into_iter().collect())
             .unwrap()
@@ -233,7 +255,6 @@ impl DirectionalLightingSystem {
             .unwrap()
             .draw(self.vertex_buffer.len() as u32, 1, 0, 0)
             .unwrap();
-        builder.build().unwrap()
     }
 }
 
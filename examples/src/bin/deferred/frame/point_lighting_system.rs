@@ -193,6 +193,44 @@ impl PointLightingSystem {
         position: Vector3<f32>,
         color: [f32; 3],
     ) -> Arc<SecondaryAutoCommandBuffer> {
+        let mut builder = AutoCommandBufferBuilder::secondary(
+            self.command_buffer_allocator.as_ref(),
+            self.gfx_queue.queue_family_index(),
+            CommandBufferUsage::MultipleSubmit,
+            CommandBufferInheritanceInfo {
+                render_pass: Some(self.subpass.clone().into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        self.record(
+            &mut builder,
+            viewport_dimensions,
+            color_input,
+            normals_input,
+            depth_input,
+            screen_to_world,
+            position,
+            color,
+        );
+        builder.build().unwrap()
+    }
+
+    /// Records the draw call for a single point light into an existing command buffer. The
+    /// parameters are the same as those of `draw`, which wraps these commands in a secondary
+    /// command buffer.
+    #[allow(clippy::too_many_arguments)]
+    pub fn record<L>(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<L>,
+        viewport_dimensions: [u32; 2],
+        color_input: Arc<ImageView>,
+        normals_input: Arc<ImageView>,
+        depth_input: Arc<ImageView>,
+        screen_to_world: Matrix4<f32>,
+        position: Vector3<f32>,
+        color: [f32; 3],
+    ) {
         let push_constants = fs::PushConstants {
             screen_to_world: screen_to_world.into(),
             color: [color[0], color[1], color[2], 1.0],
@@ -218,16 +256,6 @@ impl PointLightingSystem {
             depth_range: 0.0..=1.0,
         };
 
-        let mut builder = AutoCommandBufferBuilder::secondary(
-            self.command_buffer_allocator.as_ref(),
-            self.gfx_queue.queue_family_index(),
-            CommandBufferUsage::MultipleSubmit,
-            CommandBufferInheritanceInfo {
-                render_pass: Some(self.subpass.clone().into()),
-                ..Default::default()
-            },
-        )
-        .unwrap();
         builder
             .set_viewport(0, [viewport].into_iter().collect())
             .unwrap()
@@ -246,7 +274,6 @@ impl PointLightingSystem {
             .unwrap()
             .draw(self.vertex_buffer.len() as u32, 1, 0, 0)
             .unwrap();
-        builder.build().unwrap()
     }
 }
 
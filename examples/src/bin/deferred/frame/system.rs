@@ -58,6 +58,32 @@ pub struct FrameSystem {
     directional_lighting_system: DirectionalLightingSystem,
     // Will allow us to add a spot light source to a scene during the second subpass.
     point_lighting_system: PointLightingSystem,
+
+    // How the commands of each subpass are recorded. See `FrameSystemCreateInfo::contents`.
+    contents: SubpassContents,
+}
+
+/// Parameters to create a new `FrameSystem`.
+#[derive(Clone, Copy, Debug)]
+pub struct FrameSystemCreateInfo {
+    /// How the commands of the subpasses are recorded.
+    ///
+    /// With `SubpassContents::SecondaryCommandBuffers`, the drawing and lighting systems build
+    /// secondary command buffers that are then executed in the primary command buffer of the
+    /// frame. With `SubpassContents::Inline`, all commands are recorded directly in the primary
+    /// command buffer, which is simpler but doesn't allow recording the subpasses in parallel.
+    ///
+    /// The default value is `SubpassContents::SecondaryCommandBuffers`.
+    pub contents: SubpassContents,
+}
+
+impl Default for FrameSystemCreateInfo {
+    #[inline]
+    fn default() -> Self {
+        FrameSystemCreateInfo {
+            contents: SubpassContents::SecondaryCommandBuffers,
+        }
+    }
 }
 
 impl FrameSystem {
@@ -69,12 +95,16 @@ impl FrameSystem {
     /// - `final_output_format` is the format of the image that will later be passed to the
     ///   `frame()` method. We need to know that in advance. If that format ever changes, we have
     ///   to create a new `FrameSystem`.
+    /// - `create_info` chooses how the commands of each subpass are recorded.
     pub fn new(
         gfx_queue: Arc<Queue>,
         final_output_format: Format,
         memory_allocator: Arc<StandardMemoryAllocator>,
         command_buffer_allocator: Arc<StandardCommandBufferAllocator>,
+        create_info: FrameSystemCreateInfo,
     ) -> FrameSystem {
+        let FrameSystemCreateInfo { contents } = create_info;
+
         // Creating the render pass.
         //
         // The render pass has two subpasses. In the first subpass, we draw all the objects of the
@@ -242,6 +272,7 @@ impl FrameSystem {
             ambient_lighting_system,
             directional_lighting_system,
             point_lighting_system,
+            contents,
         }
     }
 
@@ -365,7 +396,7 @@ impl FrameSystem {
                     ..RenderPassBeginInfo::framebuffer(framebuffer.clone())
                 },
                 SubpassBeginInfo {
-                    contents: SubpassContents::SecondaryCommandBuffers,
+                    contents: self.contents,
                     ..Default::default()
                 },
             )
@@ -435,7 +466,7 @@ impl<'a> Frame<'a> {
                     .next_subpass(
                         Default::default(),
                         SubpassBeginInfo {
-                            contents: SubpassContents::SecondaryCommandBuffers,
+                            contents: self.system.contents,
                             ..Default::default()
                         },
                     )
@@ -495,16 +526,30 @@ pub struct DrawPass<'f, 's: 'f> {
 }
 
 impl<'f, 's: 'f> DrawPass<'f, 's> {
+    /// Returns how the commands of this pass must be recorded. This is the value of
+    /// `FrameSystemCreateInfo::contents`.
+    pub fn contents(&self) -> SubpassContents {
+        self.frame.system.contents
+    }
+
     /// Appends a command that executes a secondary command buffer that performs drawing.
+    ///
+    /// Can only be used if `contents` is `SubpassContents::SecondaryCommandBuffers`.
     pub fn execute(&mut self, command_buffer: Arc<dyn SecondaryCommandBufferAbstract>) {
-        self.frame
-            .command_buffer_builder
-            .as_mut()
-            .unwrap()
+        self.command_buffer_builder()
             .execute_commands(command_buffer)
             .unwrap();
     }
 
+    /// Returns the primary command buffer builder, to record drawing commands into it directly.
+    ///
+    /// Can only be used if `contents` is `SubpassContents::Inline`.
+    pub fn command_buffer_builder(
+        &mut self,
+    ) -> &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer> {
+        self.frame.command_buffer_builder.as_mut().unwrap()
+    }
+
     /// Returns the dimensions in pixels of the viewport.
     pub fn viewport_dimensions(&self) -> [u32; 2] {
         self.frame.framebuffer.extent()
@@ -527,17 +572,21 @@ impl<'f, 's: 'f> LightingPass<'f, 's> {
     ///
     /// All the objects will be colored with an intensity of `color`.
     pub fn ambient_light(&mut self, color: [f32; 3]) {
-        let command_buffer = self.frame.system.ambient_lighting_system.draw(
-            self.frame.framebuffer.extent(),
-            self.frame.system.diffuse_buffer.clone(),
-            color,
-        );
-        self.frame
-            .command_buffer_builder
-            .as_mut()
-            .unwrap()
-            .execute_commands(command_buffer)
-            .unwrap();
+        let frame = &mut *self.frame;
+        let builder = frame.command_buffer_builder.as_mut().unwrap();
+        let system = &frame.system.ambient_lighting_system;
+        let viewport_dimensions = frame.framebuffer.extent();
+        let color_input = frame.system.diffuse_buffer.clone();
+
+        match frame.system.contents {
+            SubpassContents::Inline => {
+                system.record(builder, viewport_dimensions, color_input, color);
+            }
+            _ => {
+                let command_buffer = system.draw(viewport_dimensions, color_input, color);
+                builder.execute_commands(command_buffer).unwrap();
+            }
+        }
     }
 
     /// Applies an directional lighting to the scene.
@@ -545,19 +594,35 @@ impl<'f, 's: 'f> LightingPass<'f, 's> {
     /// All the objects will be colored with an intensity varying between `[0, 0, 0]` and `color`,
     /// depending on the dot product of their normal and `direction`.
     pub fn directional_light(&mut self, direction: Vector3<f32>, color: [f32; 3]) {
-        let command_buffer = self.frame.system.directional_lighting_system.draw(
-            self.frame.framebuffer.extent(),
-            self.frame.system.diffuse_buffer.clone(),
-            self.frame.system.normals_buffer.clone(),
-            direction,
-            color,
-        );
-        self.frame
-            .command_buffer_builder
-            .as_mut()
-            .unwrap()
-            .execute_commands(command_buffer)
-            .unwrap();
+        let frame = &mut *self.frame;
+        let builder = frame.command_buffer_builder.as_mut().unwrap();
+        let system = &frame.system.directional_lighting_system;
+        let viewport_dimensions = frame.framebuffer.extent();
+        let color_input = frame.system.diffuse_buffer.clone();
+        let normals_input = frame.system.normals_buffer.clone();
+
+        match frame.system.contents {
+            SubpassContents::Inline => {
+                system.record(
+                    builder,
+                    viewport_dimensions,
+                    color_input,
+                    normals_input,
+                    direction,
+                    color,
+                );
+            }
+            _ => {
+                let command_buffer = system.draw(
+                    viewport_dimensions,
+                    color_input,
+                    normals_input,
+                    direction,
+                    color,
+                );
+                builder.execute_commands(command_buffer).unwrap();
+            }
+        }
     }
 
     /// Applies a spot lighting to the scene.
@@ -566,23 +631,40 @@ impl<'f, 's: 'f> LightingPass<'f, 's> {
     /// depending on their distance with `position`. Objects that aren't facing `position` won't
     /// receive any light.
     pub fn point_light(&mut self, position: Vector3<f32>, color: [f32; 3]) {
-        let command_buffer = {
-            self.frame.system.point_lighting_system.draw(
-                self.frame.framebuffer.extent(),
-                self.frame.system.diffuse_buffer.clone(),
-                self.frame.system.normals_buffer.clone(),
-                self.frame.system.depth_buffer.clone(),
-                self.frame.world_to_framebuffer.invert().unwrap(),
-                position,
-                color,
-            )
-        };
-
-        self.frame
-            .command_buffer_builder
-            .as_mut()
-            .unwrap()
-            .execute_commands(command_buffer)
-            .unwrap();
+        let frame = &mut *self.frame;
+        let builder = frame.command_buffer_builder.as_mut().unwrap();
+        let system = &frame.system.point_lighting_system;
+        let viewport_dimensions = frame.framebuffer.extent();
+        let color_input = frame.system.diffuse_buffer.clone();
+        let normals_input = frame.system.normals_buffer.clone();
+        let depth_input = frame.system.depth_buffer.clone();
+        let screen_to_world = frame.world_to_framebuffer.invert().unwrap();
+
+        match frame.system.contents {
+            SubpassContents::Inline => {
+                system.record(
+                    builder,
+                    viewport_dimensions,
+                    color_input,
+                    normals_input,
+                    depth_input,
+                    screen_to_world,
+                    position,
+                    color,
+                );
+            }
+            _ => {
+                let command_buffer = system.draw(
+                    viewport_dimensions,
+                    color_input,
+                    normals_input,
+                    depth_input,
+                    screen_to_world,
+                    position,
+                    color,
+                );
+                builder.execute_commands(command_buffer).unwrap();
+            }
+        }
     }
 }
@@ -24,16 +24,20 @@
 // This technique allows you to apply tons of light sources to a scene, which would be too
 // expensive otherwise. It has some drawbacks, which are the fact that transparent objects must be
 // drawn after the lighting, and that the whole process consumes more memory.
+//
+// By default each subpass is recorded into secondary command buffers. Pass `--inline` to record
+// all commands directly into the primary command buffer instead.
 
 use crate::{
-    frame::{FrameSystem, Pass},
+    frame::{FrameSystem, FrameSystemCreateInfo, Pass},
     triangle_draw_system::TriangleDrawSystem,
 };
 use cgmath::{Matrix4, SquareMatrix, Vector3};
 use std::sync::Arc;
 use vulkano::{
-    command_buffer::allocator::{
-        StandardCommandBufferAllocator, StandardCommandBufferAllocatorCreateInfo,
+    command_buffer::{
+        allocator::{StandardCommandBufferAllocator, StandardCommandBufferAllocatorCreateInfo},
+        SubpassContents,
     },
     device::{
         physical::PhysicalDeviceType, Device, DeviceCreateInfo, DeviceExtensions, QueueCreateInfo,
@@ -170,11 +174,17 @@ fn main() {
     ));
 
     // Here is the basic initialization for the deferred system.
+    let contents = if std::env::args().any(|arg| arg == "--inline") {
+        SubpassContents::Inline
+    } else {
+        SubpassContents::SecondaryCommandBuffers
+    };
     let mut frame_system = FrameSystem::new(
         queue.clone(),
         swapchain.image_format(),
         memory_allocator.clone(),
         command_buffer_allocator.clone(),
+        FrameSystemCreateInfo { contents },
     );
     let triangle_draw_system = TriangleDrawSystem::new(
         queue.clone(),
@@ -249,8 +259,15 @@ fn main() {
             while let Some(pass) = frame.next_pass() {
                 match pass {
                     Pass::Deferred(mut draw_pass) => {
-                        let cb = triangle_draw_system.draw(draw_pass.viewport_dimensions());
-                        draw_pass.execute(cb);
+                        let viewport_dimensions = draw_pass.viewport_dimensions();
+
+                        if draw_pass.contents() == SubpassContents::Inline {
+                            triangle_draw_system
+                                .record(draw_pass.command_buffer_builder(), viewport_dimensions);
+                        } else {
+                            let cb = triangle_draw_system.draw(viewport_dimensions);
+                            draw_pass.execute(cb);
+                        }
                     }
                     Pass::Lighting(mut lighting) => {
                         lighting.ambient_light([0.1, 0.1, 0.1]);
@@ -290,3 +307,134 @@ fn main() {
         _ => (),
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        frame::{FrameSystem, FrameSystemCreateInfo, Pass},
+        triangle_draw_system::TriangleDrawSystem,
+    };
+    use cgmath::{Matrix4, SquareMatrix, Vector3};
+    use std::sync::Arc;
+    use vulkano::{
+        command_buffer::{allocator::StandardCommandBufferAllocator, SubpassContents},
+        device::{Device, DeviceCreateInfo, QueueCreateInfo, QueueFlags},
+        format::Format,
+        image::{view::ImageView, Image, ImageCreateInfo, ImageUsage},
+        instance::{Instance, InstanceCreateFlags, InstanceCreateInfo},
+        memory::allocator::{AllocationCreateInfo, StandardMemoryAllocator},
+        sync::{self, GpuFuture},
+        VulkanLibrary,
+    };
+
+    #[test]
+    fn inline_frame() {
+        // Skip the test if there is no Vulkan implementation with a graphics queue available.
+        let library = match VulkanLibrary::new() {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+        let instance = match Instance::new(
+            library,
+            InstanceCreateInfo {
+                flags: InstanceCreateFlags::ENUMERATE_PORTABILITY,
+                ..Default::default()
+            },
+        ) {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+        let (physical_device, queue_family_index) = match instance
+            .enumerate_physical_devices()
+            .unwrap()
+            .find_map(|p| {
+                p.queue_family_properties()
+                    .iter()
+                    .position(|q| q.queue_flags.intersects(QueueFlags::GRAPHICS))
+                    .map(|i| (p, i as u32))
+            }) {
+            Some(x) => x,
+            None => return,
+        };
+
+        let (device, mut queues) = Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let queue = queues.next().unwrap();
+
+        let memory_allocator = Arc::new(StandardMemoryAllocator::new_default(device.clone()));
+        let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+            device.clone(),
+            Default::default(),
+        ));
+
+        let final_image_view = ImageView::new_default(
+            Image::new(
+                memory_allocator.clone(),
+                ImageCreateInfo {
+                    format: Format::R8G8B8A8_UNORM,
+                    extent: [64, 64, 1],
+                    usage: ImageUsage::COLOR_ATTACHMENT,
+                    ..Default::default()
+                },
+                AllocationCreateInfo::default(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let mut frame_system = FrameSystem::new(
+            queue.clone(),
+            Format::R8G8B8A8_UNORM,
+            memory_allocator.clone(),
+            command_buffer_allocator.clone(),
+            FrameSystemCreateInfo {
+                contents: SubpassContents::Inline,
+            },
+        );
+        let triangle_draw_system = TriangleDrawSystem::new(
+            queue.clone(),
+            frame_system.deferred_subpass(),
+            memory_allocator,
+            command_buffer_allocator,
+        );
+
+        let mut frame =
+            frame_system.frame(sync::now(device), final_image_view, Matrix4::identity());
+        let mut after_future = None;
+
+        while let Some(pass) = frame.next_pass() {
+            match pass {
+                Pass::Deferred(mut draw_pass) => {
+                    assert_eq!(draw_pass.contents(), SubpassContents::Inline);
+                    let viewport_dimensions = draw_pass.viewport_dimensions();
+                    triangle_draw_system
+                        .record(draw_pass.command_buffer_builder(), viewport_dimensions);
+                }
+                Pass::Lighting(mut lighting) => {
+                    lighting.ambient_light([0.1, 0.1, 0.1]);
+                    lighting.directional_light(Vector3::new(0.2, -0.1, -0.7), [0.6, 0.6, 0.6]);
+                    lighting.point_light(Vector3::new(0.5, -0.5, -0.1), [1.0, 0.0, 0.0]);
+                }
+                Pass::Finished(af) => {
+                    after_future = Some(af);
+                }
+            }
+        }
+
+        after_future
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+    }
+}
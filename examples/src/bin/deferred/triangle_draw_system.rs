@@ -147,6 +147,20 @@ impl TriangleDrawSystem {
             },
         )
         .unwrap();
+        self.record(&mut builder, viewport_dimensions);
+        builder.build().unwrap()
+    }
+
+    /// Records the triangle into `builder`. The main loop calls this with
+    /// [`DrawPass::command_buffer_builder`] when the frame is recorded inline, and calls `draw`
+    /// otherwise.
+    ///
+    /// [`DrawPass::command_buffer_builder`]: crate::frame::DrawPass::command_buffer_builder
+    pub fn record<L>(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<L>,
+        viewport_dimensions: [u32; 2],
+    ) {
         builder
             .set_viewport(
                 0,
@@ -165,7 +179,6 @@ impl TriangleDrawSystem {
             .unwrap()
             .draw(self.vertex_buffer.len() as u32, 1, 0, 0)
             .unwrap();
-        builder.build().unwrap()
     }
 }
 
@@ -248,7 +248,7 @@ pub(super) fn reflect(
             device: ::std::sync::Arc<::vulkano::device::Device>,
         ) -> ::std::result::Result<
             ::std::sync::Arc<::vulkano::shader::ShaderModule>,
-            ::vulkano::Validated<::vulkano::VulkanError>,
+            ::vulkano::Validated<::vulkano::shader::CreateShaderModuleError>,
         > {
             let _bytes = ( #( #include_bytes ),* );
 
@@ -271,7 +271,14 @@ pub(super) fn reflect(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use vulkano::shader::reflect;
+    use vulkano::{
+        descriptor_set::layout::DescriptorType,
+        device::physical::SubgroupFeatures,
+        format::{Format, NumericType},
+        shader::{
+            reflect, spirv::SourceLanguage, FragmentOrigin, PushConstantMemberType, ShaderStages,
+        },
+    };
 
     fn convert_paths(root_path: &Path, paths: &[PathBuf]) -> Vec<String> {
         paths
@@ -638,4 +645,1390 @@ mod tests {
         }
         panic!("could not find entrypoint");
     }
+
+    #[test]
+    fn descriptor_calculation_with_nonuniform_indexing() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+                #extension GL_EXT_nonuniform_qualifier : require
+
+                layout(set = 0, binding = 0) uniform sampler2D textures[4];
+                layout(set = 0, binding = 1) uniform sampler2D otherTextures[4];
+
+                layout(location = 0) flat in int index;
+                layout(location = 0) out vec4 color;
+
+                void main() {
+                    color = texture(textures[nonuniformEXT(index)], vec2(0.0))
+                        + texture(otherTextures[2], vec2(0.0));
+                }
+            "#,
+            ShaderKind::Fragment,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert!(info.descriptor_binding_requirements[&(0, 0)].nonuniform_indexing);
+        assert!(!info.descriptor_binding_requirements[&(0, 1)].nonuniform_indexing);
+    }
+
+    #[test]
+    fn entry_point_uses_fragment_shading_rate() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+                #extension GL_EXT_fragment_shading_rate : require
+
+                layout(location = 0) in vec4 position;
+
+                void main() {
+                    gl_Position = position;
+                    gl_PrimitiveShadingRateEXT = gl_ShadingRateFlag2VerticalPixelsEXT;
+                }
+            "#,
+            ShaderKind::Vertex,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert!(info.uses_fragment_shading_rate);
+    }
+
+    #[test]
+    fn descriptor_calculation_with_input_attachments() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(input_attachment_index = 0, set = 0, binding = 0) uniform subpassInput u_diffuse;
+                layout(input_attachment_index = 1, set = 0, binding = 1) uniform subpassInput u_normals;
+
+                layout(location = 0) out vec4 f_color;
+
+                void main() {
+                    f_color = subpassLoad(u_diffuse) * subpassLoad(u_normals);
+                }
+            "#,
+            ShaderKind::Fragment,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        for binding in [(0, 0), (0, 1)] {
+            assert_eq!(
+                info.descriptor_binding_requirements[&binding].descriptor_types,
+                [DescriptorType::InputAttachment],
+            );
+        }
+        assert_eq!(
+            info.descriptor_binding_requirements[&(0, 0)].input_attachment_index,
+            Some(0),
+        );
+        assert_eq!(
+            info.descriptor_binding_requirements[&(0, 1)].input_attachment_index,
+            Some(1),
+        );
+    }
+
+    #[test]
+    fn descriptor_bindings_sorted() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(set = 1, binding = 0) uniform sampler2D u_texture;
+                layout(set = 0, binding = 3) uniform Uniforms { vec4 color; } u_uniforms;
+                layout(set = 1, binding = 2) buffer Data { vec4 data[]; } u_data;
+                layout(set = 0, binding = 1) uniform sampler2D u_mask;
+
+                layout(location = 0) out vec4 f_color;
+
+                void main() {
+                    f_color = texture(u_texture, vec2(0.0)) * texture(u_mask, vec2(0.0))
+                        * u_uniforms.color * u_data.data[0];
+                }
+            "#,
+            ShaderKind::Fragment,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        let keys: Vec<_> = info
+            .descriptor_bindings_sorted()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(keys, [(0, 1), (0, 3), (1, 0), (1, 2)]);
+
+        for _ in 0..4 {
+            let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+            let keys_again: Vec<_> = info
+                .descriptor_bindings_sorted()
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect();
+            assert_eq!(keys_again, keys);
+        }
+    }
+
+    #[test]
+    fn entry_point_clip_distance_count() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                out gl_PerVertex {
+                    vec4 gl_Position;
+                    float gl_ClipDistance[2];
+                };
+
+                void main() {
+                    gl_Position = vec4(0.0);
+                    gl_ClipDistance[0] = 1.0;
+                    gl_ClipDistance[1] = -1.0;
+                }
+            "#,
+            ShaderKind::Vertex,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert_eq!(info.clip_distance_count, 2);
+        assert_eq!(info.cull_distance_count, 0);
+    }
+
+    #[test]
+    fn descriptor_calculation_with_coherent_buffer() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(local_size_x = 64) in;
+
+                layout(set = 0, binding = 0) coherent buffer Shared {
+                    uint counter;
+                } shared_data;
+
+                layout(set = 0, binding = 1) buffer Output {
+                    uint value;
+                } output_data;
+
+                void main() {
+                    output_data.value = atomicAdd(shared_data.counter, 1);
+                }
+            "#,
+            ShaderKind::Compute,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        let shared = &info.descriptor_binding_requirements[&(0, 0)].descriptors[&Some(0)];
+        assert!(shared.coherent);
+        assert!(!shared.volatile);
+
+        let output = &info.descriptor_binding_requirements[&(0, 1)].descriptors[&Some(0)];
+        assert!(!output.coherent);
+        assert!(!output.volatile);
+    }
+
+    #[test]
+    fn descriptor_calculation_with_per_index_image_format() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(local_size_x = 1) in;
+
+                layout(set = 0, binding = 0, r32f) uniform writeonly image2D float_images[2];
+                layout(set = 0, binding = 0, rgba8) uniform writeonly image2D unorm_images[2];
+
+                void main() {
+                    imageStore(float_images[0], ivec2(0), vec4(1.0));
+                    imageStore(unorm_images[1], ivec2(0), vec4(1.0));
+                }
+            "#,
+            ShaderKind::Compute,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        let binding = &info.descriptor_binding_requirements[&(0, 0)];
+        assert_eq!(binding.descriptor_count, Some(2));
+        assert_eq!(binding.image_format, None);
+        assert_eq!(
+            binding.descriptors[&Some(0)].image_format,
+            Some(Format::R32_SFLOAT),
+        );
+        assert_eq!(
+            binding.descriptors[&Some(1)].image_format,
+            Some(Format::R8G8B8A8_UNORM),
+        );
+    }
+
+    #[test]
+    fn descriptor_calculation_with_ycbcr_compatible_samplers() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(location = 0) in vec2 tex_coords;
+                layout(location = 0) out vec4 f_color;
+
+                // The first descriptor can be used with an immutable sampler that has a YCbCr
+                // conversion. The second is gathered from, which a conversion doesn't allow.
+                layout(set = 0, binding = 0) uniform sampler2D textures[2];
+
+                void main() {
+                    f_color = texture(textures[0], tex_coords)
+                        + textureGather(textures[1], tex_coords);
+                }
+            "#,
+            ShaderKind::Fragment,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        let binding = &info.descriptor_binding_requirements[&(0, 0)];
+        assert!(!binding.descriptors[&Some(0)].sampler_no_ycbcr_conversion);
+        assert!(binding.descriptors[&Some(1)].sampler_no_ycbcr_conversion);
+        assert_eq!(binding.sampler_ycbcr_conversion_compatible_count(), Some(1));
+    }
+
+    #[test]
+    fn descriptor_calculation_with_explicit_lod() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(set = 0, binding = 0) uniform sampler2D tex;
+                layout(set = 0, binding = 1) buffer Output {
+                    vec4 color;
+                } data;
+
+                void main() {
+                    data.color = textureLod(tex, vec2(0.5), 0.0);
+                }
+            "#,
+            ShaderKind::Compute,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        let binding = &info.descriptor_binding_requirements[&(0, 0)];
+        assert!(!binding.descriptors[&Some(0)].sampler_uses_implicit_lod);
+    }
+
+    #[test]
+    fn descriptor_calculation_with_shadow_sampler() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(set = 0, binding = 0) uniform sampler2DShadow shadow;
+                layout(set = 0, binding = 1) uniform sampler2D tex;
+                layout(set = 0, binding = 2) buffer Output {
+                    vec4 color;
+                } data;
+
+                void main() {
+                    float depth = textureLod(shadow, vec3(0.5), 0.0);
+                    data.color = textureLod(tex, vec2(0.5), 0.0) * depth;
+                }
+            "#,
+            ShaderKind::Compute,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        let binding = &info.descriptor_binding_requirements[&(0, 0)];
+        assert!(binding.image_depth);
+        assert!(binding.descriptors[&Some(0)].sampler_compare);
+
+        let binding = &info.descriptor_binding_requirements[&(0, 1)];
+        assert!(!binding.image_depth);
+        assert!(!binding.descriptors[&Some(0)].sampler_compare);
+    }
+
+    #[test]
+    fn descriptor_calculation_runtime_array_stride() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(set = 0, binding = 0, std430) buffer Packed {
+                    uint count;
+                    float data[];
+                } packed;
+                layout(set = 0, binding = 1, std140) buffer Padded {
+                    uint count;
+                    float data[];
+                } padded;
+                layout(set = 0, binding = 2) uniform Uniforms {
+                    float scale;
+                } uniforms;
+
+                void main() {
+                    packed.data[0] = uniforms.scale;
+                    padded.data[0] = uniforms.scale;
+                }
+            "#,
+            ShaderKind::Compute,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        let reqs = &info.descriptor_binding_requirements;
+        assert_eq!(reqs[&(0, 0)].runtime_array_stride, Some(4));
+        assert_eq!(reqs[&(0, 1)].runtime_array_stride, Some(16));
+        assert_eq!(reqs[&(0, 2)].runtime_array_stride, None);
+    }
+
+    #[test]
+    fn descriptor_calculation_storage_image_without_format() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+                #extension GL_EXT_shader_image_load_formatted : enable
+
+                layout(set = 0, binding = 0) uniform image2D unknown_image;
+                layout(set = 0, binding = 1, rgba32f) uniform image2D known_image;
+
+                void main() {
+                    vec4 texel = imageLoad(unknown_image, ivec2(0));
+                    imageStore(unknown_image, ivec2(1), texel);
+                    imageStore(known_image, ivec2(0), imageLoad(known_image, ivec2(1)));
+                }
+            "#,
+            ShaderKind::Compute,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        let reqs = &info.descriptor_binding_requirements;
+        assert!(reqs[&(0, 0)].storage_read_without_format);
+        assert!(reqs[&(0, 0)].storage_write_without_format);
+        assert!(!reqs[&(0, 1)].storage_read_without_format);
+        assert!(!reqs[&(0, 1)].storage_write_without_format);
+    }
+
+    #[test]
+    fn storage_image_formats() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(set = 0, binding = 0, rgba16f) uniform readonly image2D half_image;
+                layout(set = 0, binding = 1, r32f) uniform writeonly image2D float_image;
+                layout(set = 0, binding = 2) uniform sampler2D tex;
+
+                void main() {
+                    vec4 color = imageLoad(half_image, ivec2(0)) + textureLod(tex, vec2(0.5), 0.0);
+                    imageStore(float_image, ivec2(0), color);
+                }
+            "#,
+            ShaderKind::Compute,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+
+        let mut formats: Vec<_> = spirv
+            .reflect_module()
+            .storage_image_formats
+            .into_iter()
+            .collect();
+        formats.sort_unstable_by_key(|&format| format as i32);
+        assert_eq!(formats, [Format::R16G16B16A16_SFLOAT, Format::R32_SFLOAT],);
+    }
+
+    #[test]
+    fn descriptor_calculation_with_unused_bindings() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(local_size_x = 64) in;
+
+                layout(set = 0, binding = 0) buffer Used {
+                    float used[];
+                };
+
+                layout(set = 0, binding = 1) buffer Unused {
+                    float unused[];
+                };
+
+                void main() {
+                    used[gl_GlobalInvocationID.x] = 1.0;
+                }
+            "#,
+            ShaderKind::Compute,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+        assert!(info.descriptor_binding_requirements.contains_key(&(0, 0)));
+        assert!(!info.descriptor_binding_requirements.contains_key(&(0, 1)));
+
+        let (_, info) = reflect::entry_points_with_unused_bindings(&spirv, true)
+            .next()
+            .unwrap();
+        assert!(info.descriptor_binding_requirements.contains_key(&(0, 0)));
+
+        let unused = &info.descriptor_binding_requirements[&(0, 1)];
+        assert!(unused
+            .descriptor_types
+            .contains(&DescriptorType::StorageBuffer));
+        assert_eq!(unused.stages, ShaderStages::COMPUTE);
+        assert!(unused.descriptors.is_empty());
+    }
+
+    #[test]
+    fn descriptor_calculation_with_hints() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(local_size_x = 64) in;
+
+                layout(set = 0, binding = 0) uniform Uniforms {
+                    float scale;
+                };
+
+                layout(set = 0, binding = 1) buffer Data {
+                    float data[];
+                };
+
+                void main() {
+                    data[gl_GlobalInvocationID.x] *= scale;
+                }
+            "#,
+            ShaderKind::Compute,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+
+        let hints: ahash::HashMap<_, _> = [
+            ((0, 0), DescriptorType::UniformBufferDynamic),
+            ((1, 0), DescriptorType::StorageBufferDynamic),
+        ]
+        .into_iter()
+        .collect();
+        let (_, info) = reflect::entry_points_with_hints(&spirv, &hints)
+            .next()
+            .unwrap();
+
+        assert_eq!(
+            info.descriptor_binding_requirements[&(0, 0)].descriptor_types,
+            [DescriptorType::UniformBufferDynamic],
+        );
+        assert_eq!(
+            info.descriptor_binding_requirements[&(0, 1)].descriptor_types,
+            [
+                DescriptorType::StorageBuffer,
+                DescriptorType::StorageBufferDynamic,
+            ],
+        );
+    }
+
+    #[test]
+    fn descriptor_calculation_multisampled_images() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(location = 0) out vec4 f_color;
+
+                layout(set = 0, binding = 0) uniform sampler2DMS u_multisampled;
+                layout(set = 0, binding = 1, rgba8) uniform readonly image2DMS u_storage;
+                layout(set = 0, binding = 2) uniform sampler2D u_single_sampled;
+
+                void main() {
+                    f_color = texelFetch(u_multisampled, ivec2(0), 0)
+                        + imageLoad(u_storage, ivec2(0), 0)
+                        + texture(u_single_sampled, vec2(0.0));
+                }
+            "#,
+            ShaderKind::Fragment,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        let multisampled = &info.descriptor_binding_requirements[&(0, 0)];
+        assert!(multisampled.image_multisampled);
+        assert!(info.descriptor_binding_requirements[&(0, 1)].image_multisampled);
+
+        let single_sampled = &info.descriptor_binding_requirements[&(0, 2)];
+        assert!(!single_sampled.image_multisampled);
+
+        // A binding can't be both multisampled and single-sampled.
+        assert!(multisampled.clone().merge(single_sampled).is_err());
+    }
+
+    #[test]
+    fn descriptor_calculation_storage_image_scalar_type() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(local_size_x = 1) in;
+
+                layout(set = 0, binding = 0, r32ui) uniform readonly uimage2D u_src;
+                layout(set = 0, binding = 1, r32i) uniform writeonly iimage2D u_dst;
+
+                void main() {
+                    uint value = imageLoad(u_src, ivec2(gl_GlobalInvocationID.xy)).x;
+                    imageStore(u_dst, ivec2(gl_GlobalInvocationID.xy), ivec4(value));
+                }
+            "#,
+            ShaderKind::Compute,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert_eq!(
+            info.descriptor_binding_requirements[&(0, 0)].image_scalar_type,
+            Some(NumericType::Uint),
+        );
+        assert_eq!(
+            info.descriptor_binding_requirements[&(0, 1)].image_scalar_type,
+            Some(NumericType::Int),
+        );
+    }
+
+    #[test]
+    fn entry_point_workgroup_shared_size() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(local_size_x = 256) in;
+
+                layout(set = 0, binding = 0) buffer Data {
+                    float values[];
+                };
+
+                shared float data[256];
+
+                void main() {
+                    data[gl_LocalInvocationIndex] = values[gl_GlobalInvocationID.x];
+                    barrier();
+                    values[gl_GlobalInvocationID.x] = data[255 - gl_LocalInvocationIndex];
+                }
+            "#,
+            ShaderKind::Compute,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert_eq!(info.workgroup_shared_size, Some(1024));
+    }
+
+    #[test]
+    fn entry_point_subgroup_operations() {
+        let (comp, _) = compile(
+            &MacroInput {
+                vulkan_version: Some(EnvVersion::Vulkan1_1),
+                ..MacroInput::empty()
+            },
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+                #extension GL_KHR_shader_subgroup_arithmetic : require
+                #extension GL_KHR_shader_subgroup_ballot : require
+
+                layout(local_size_x = 64) in;
+
+                layout(set = 0, binding = 0) buffer Data {
+                    uint values[];
+                };
+
+                void main() {
+                    uint sum = subgroupAdd(values[gl_GlobalInvocationID.x]);
+                    uvec4 ballot = subgroupBallot(sum > 0);
+                    values[gl_GlobalInvocationID.x] = ballot.x;
+                }
+            "#,
+            ShaderKind::Compute,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert_eq!(
+            info.subgroup_operations,
+            SubgroupFeatures::BASIC | SubgroupFeatures::ARITHMETIC | SubgroupFeatures::BALLOT,
+        );
+    }
+
+    #[test]
+    fn descriptor_calculation_ray_generation_called_functions() {
+        let (comp, _) = compile(
+            &MacroInput {
+                vulkan_version: Some(EnvVersion::Vulkan1_2),
+                ..MacroInput::empty()
+            },
+            None,
+            Path::new(""),
+            r#"
+                #version 460
+                #extension GL_EXT_ray_tracing : require
+
+                layout(set = 0, binding = 0) uniform accelerationStructureEXT tlas;
+                layout(set = 0, binding = 1, rgba8) uniform writeonly image2D result;
+                layout(set = 0, binding = 2) uniform accelerationStructureEXT unused;
+
+                layout(location = 0) rayPayloadEXT vec4 payload;
+
+                void trace() {
+                    traceRayEXT(tlas, gl_RayFlagsOpaqueEXT, 0xff, 0, 0, 0, vec3(0.0), 0.0,
+                        vec3(0.0, 0.0, 1.0), 100.0, 0);
+                }
+
+                void store() {
+                    imageStore(result, ivec2(gl_LaunchIDEXT.xy), payload);
+                }
+
+                void main() {
+                    trace();
+                    store();
+                }
+            "#,
+            ShaderKind::RayGeneration,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        let mut bindings: Vec<_> = info.descriptor_binding_requirements.keys().collect();
+        bindings.sort_unstable();
+        assert_eq!(bindings, [&(0, 0), &(0, 1)]);
+
+        let tlas = &info.descriptor_binding_requirements[&(0, 0)];
+        assert_eq!(
+            tlas.descriptor_types,
+            [DescriptorType::AccelerationStructure]
+        );
+        assert_eq!(tlas.stages, ShaderStages::RAYGEN);
+
+        let result = &info.descriptor_binding_requirements[&(0, 1)];
+        assert!(result.descriptors[&Some(0)]
+            .memory_write
+            .intersects(ShaderStages::RAYGEN));
+    }
+
+    #[test]
+    fn source_language() {
+        let source_language = |code| {
+            let (comp, _) = compile(
+                &MacroInput::empty(),
+                None,
+                Path::new(""),
+                code,
+                ShaderKind::Compute,
+            )
+            .unwrap();
+            let spirv = Spirv::new(comp.as_binary()).unwrap();
+            assert_eq!(
+                spirv.reflect_module().source_language,
+                spirv.source_language(),
+            );
+
+            spirv.source_language()
+        };
+
+        assert_eq!(
+            source_language(
+                r#"
+                    #version 310 es
+
+                    layout(local_size_x = 1) in;
+
+                    void main() {}
+                "#
+            ),
+            Some((SourceLanguage::ESSL, 310)),
+        );
+        assert_eq!(
+            source_language(
+                r#"
+                    #version 450
+
+                    layout(local_size_x = 1) in;
+
+                    void main() {}
+                "#
+            ),
+            Some((SourceLanguage::GLSL, 450)),
+        );
+    }
+
+    #[test]
+    fn entry_point_uses_debug_printf() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+                #extension GL_EXT_debug_printf : require
+
+                layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+                void print_id() {
+                    debugPrintfEXT("invocation %u", gl_GlobalInvocationID.x);
+                }
+
+                void main() {
+                    print_id();
+                }
+            "#,
+            ShaderKind::Compute,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert!(spirv
+            .ext_inst_imports()
+            .contains(&"NonSemantic.DebugPrintf"));
+        assert!(info.uses_debug_printf);
+    }
+
+    #[test]
+    fn push_constant_range_with_nonzero_offset() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(push_constant) uniform PushConstants {
+                    layout(offset = 16) vec4 color;
+                    float scale;
+                } push;
+
+                layout(location = 0) out vec4 f_color;
+
+                void main() {
+                    f_color = push.color * push.scale;
+                }
+            "#,
+            ShaderKind::Fragment,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+        let range = info.push_constant_requirements.unwrap();
+
+        assert_eq!(range.offset, 16);
+        assert_eq!(range.size, 20);
+    }
+
+    #[test]
+    fn push_constant_members() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(push_constant) uniform PushConstants {
+                    mat4 transform;
+                    int index;
+                } push_constants;
+
+                void main() {
+                    gl_Position = push_constants.transform * vec4(float(push_constants.index));
+                }
+            "#,
+            ShaderKind::Vertex,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert_eq!(
+            info.push_constant_members,
+            [
+                (
+                    0,
+                    PushConstantMemberType {
+                        base_type: NumericType::Float,
+                        width: 32,
+                        num_components: 4,
+                        num_elements: 4,
+                    },
+                ),
+                (
+                    64,
+                    PushConstantMemberType {
+                        base_type: NumericType::Int,
+                        width: 32,
+                        num_components: 1,
+                        num_elements: 1,
+                    },
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn shader_record_members() {
+        let (comp, _) = compile(
+            &MacroInput {
+                vulkan_version: Some(EnvVersion::Vulkan1_2),
+                ..MacroInput::empty()
+            },
+            None,
+            Path::new(""),
+            r#"
+                #version 460
+                #extension GL_EXT_ray_tracing : require
+
+                layout(shaderRecordEXT, std430) buffer ShaderRecord {
+                    vec3 color;
+                    uint material_index;
+                } shader_record;
+
+                layout(location = 0) rayPayloadInEXT vec4 payload;
+
+                void main() {
+                    payload = vec4(shader_record.color, float(shader_record.material_index));
+                }
+            "#,
+            ShaderKind::ClosestHit,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert_eq!(
+            info.shader_record_members,
+            [
+                (
+                    0,
+                    PushConstantMemberType {
+                        base_type: NumericType::Float,
+                        width: 32,
+                        num_components: 3,
+                        num_elements: 1,
+                    },
+                ),
+                (
+                    12,
+                    PushConstantMemberType {
+                        base_type: NumericType::Uint,
+                        width: 32,
+                        num_components: 1,
+                        num_elements: 1,
+                    },
+                ),
+            ],
+        );
+        assert!(info.push_constant_members.is_empty());
+    }
+
+    #[test]
+    fn entry_point_uses_ray_query() {
+        let (comp, _) = compile(
+            &MacroInput {
+                vulkan_version: Some(EnvVersion::Vulkan1_2),
+                ..MacroInput::empty()
+            },
+            None,
+            Path::new(""),
+            r#"
+                #version 460
+                #extension GL_EXT_ray_query : require
+
+                layout(set = 0, binding = 0) uniform accelerationStructureEXT tlas;
+                layout(set = 0, binding = 1) buffer Hits {
+                    uint hit;
+                };
+
+                void main() {
+                    rayQueryEXT query;
+                    rayQueryInitializeEXT(
+                        query, tlas, gl_RayFlagsOpaqueEXT, 0xFFu,
+                        vec3(0.0), 0.0, vec3(0.0, 0.0, 1.0), 100.0
+                    );
+                    while (rayQueryProceedEXT(query)) {}
+                    hit = rayQueryGetIntersectionTypeEXT(query, true);
+                }
+            "#,
+            ShaderKind::Compute,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert!(info.uses_ray_query);
+        assert_eq!(
+            info.descriptor_binding_requirements[&(0, 0)].descriptor_types,
+            [DescriptorType::AccelerationStructure],
+        );
+    }
+
+    #[test]
+    fn entry_point_uses_no_contraction() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(location = 0) in vec4 v_a;
+                layout(location = 1) in vec4 v_b;
+                layout(location = 2) in vec4 v_c;
+
+                layout(location = 0) precise out vec4 f_color;
+
+                void main() {
+                    f_color = v_a * v_b + v_c;
+                }
+            "#,
+            ShaderKind::Fragment,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert!(info.uses_no_contraction);
+    }
+
+    #[test]
+    fn entry_point_uses_dual_source_blend() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(location = 0, index = 0) out vec4 f_color;
+                layout(location = 0, index = 1) out vec4 f_blend_factor;
+
+                void main() {
+                    f_color = vec4(1.0);
+                    f_blend_factor = vec4(0.5);
+                }
+            "#,
+            ShaderKind::Fragment,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert!(info.uses_dual_source_blend);
+    }
+
+    #[test]
+    fn color_output_numeric_types() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(location = 0) out vec4 f_color;
+                layout(location = 1) out ivec4 f_id;
+                layout(location = 2) out uvec2 f_flags[2];
+
+                void main() {
+                    f_color = vec4(1.0);
+                    f_id = ivec4(1);
+                    f_flags[0] = uvec2(1);
+                    f_flags[1] = uvec2(2);
+                }
+            "#,
+            ShaderKind::Fragment,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert_eq!(
+            info.output_interface.color_output_numeric_types(),
+            [
+                (0, NumericType::Float),
+                (1, NumericType::Int),
+                (2, NumericType::Uint),
+                (3, NumericType::Uint),
+            ],
+        );
+    }
+
+    #[test]
+    fn interface_component_usage() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(location = 0, component = 0) out vec2 v_uv;
+                layout(location = 0, component = 2) out vec2 v_offset;
+                layout(location = 1) out float v_alpha;
+
+                void main() {
+                    v_uv = vec2(0.0);
+                    v_offset = vec2(1.0);
+                    v_alpha = 1.0;
+                    gl_Position = vec4(0.0);
+                }
+            "#,
+            ShaderKind::Vertex,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+        let usage = info.output_interface.component_usage();
+
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[&0], 0b1111);
+        assert_eq!(usage[&1], 0b0001);
+    }
+
+    #[test]
+    fn entry_point_may_discard() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(location = 0) in vec4 v_color;
+                layout(location = 0) out vec4 f_color;
+
+                void discard_transparent() {
+                    if (v_color.a == 0.0) {
+                        discard;
+                    }
+                }
+
+                void main() {
+                    discard_transparent();
+                    f_color = v_color;
+                }
+            "#,
+            ShaderKind::Fragment,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert!(info.may_discard);
+    }
+
+    #[test]
+    fn entry_point_fragment_origin() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(location = 0) out vec4 f_color;
+
+                void main() {
+                    f_color = gl_FragCoord;
+                }
+            "#,
+            ShaderKind::Fragment,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert_eq!(info.fragment_origin, Some(FragmentOrigin::UpperLeft));
+    }
+
+    #[test]
+    fn entry_point_exports_stencil_ref() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+                #extension GL_ARB_shader_stencil_export : require
+
+                void main() {
+                    gl_FragStencilRefARB = 1;
+                }
+            "#,
+            ShaderKind::Fragment,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert!(info.exports_stencil_ref);
+    }
+
+    #[test]
+    fn entry_point_uses_frag_coord() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+                #extension GL_ARB_fragment_shader_interlock : require
+
+                layout(sample_interlock_ordered) in;
+
+                layout(location = 0) out vec4 f_color;
+
+                void main() {
+                    beginInvocationInterlockARB();
+                    f_color = vec4(gl_FragCoord.xy, 0.0, 1.0);
+                    endInvocationInterlockARB();
+                }
+            "#,
+            ShaderKind::Fragment,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert!(info.uses_frag_coord);
+        assert!(info.sample_interlock);
+        assert!(!info.pixel_center_integer);
+    }
+
+    #[test]
+    fn entry_point_writes_layer() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(triangles, invocations = 6) in;
+                layout(triangle_strip, max_vertices = 3) out;
+
+                void main() {
+                    for (int i = 0; i < 3; i++) {
+                        gl_Position = gl_in[i].gl_Position;
+                        gl_Layer = gl_InvocationID;
+                        EmitVertex();
+                    }
+
+                    EndPrimitive();
+                }
+            "#,
+            ShaderKind::Geometry,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert!(info.writes_layer);
+    }
+
+    #[test]
+    fn entry_point_position_invariant() {
+        let source = |qualifier| {
+            format!(
+                r#"
+                    #version 450
+
+                    layout(location = 0) in vec4 position;
+
+                    {}
+
+                    void main() {{
+                        gl_Position = position;
+                    }}
+                "#,
+                qualifier,
+            )
+        };
+
+        let (vs, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            &source("invariant gl_Position;"),
+            ShaderKind::Vertex,
+        )
+        .unwrap();
+        let spirv = Spirv::new(vs.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+        assert!(info.position_invariant);
+
+        let (vs, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            &source(""),
+            ShaderKind::Vertex,
+        )
+        .unwrap();
+        let spirv = Spirv::new(vs.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+        assert!(!info.position_invariant);
+    }
+
+    #[test]
+    fn shader_interface_relaxed_precision() {
+        let (vs, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(location = 0) out mediump vec4 v_color;
+
+                void main() {
+                    v_color = vec4(1.0);
+                    gl_Position = vec4(0.0);
+                }
+            "#,
+            ShaderKind::Vertex,
+        )
+        .unwrap();
+        let (fs, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(location = 0) in highp vec4 v_color;
+                layout(location = 0) out vec4 f_color;
+
+                void main() {
+                    f_color = v_color;
+                }
+            "#,
+            ShaderKind::Fragment,
+        )
+        .unwrap();
+        let vs_spirv = Spirv::new(vs.as_binary()).unwrap();
+        let (_, vs_info) = reflect::entry_points(&vs_spirv).next().unwrap();
+        let fs_spirv = Spirv::new(fs.as_binary()).unwrap();
+        let (_, fs_info) = reflect::entry_points(&fs_spirv).next().unwrap();
+
+        assert!(vs_info.output_interface.elements()[0].relaxed_precision);
+        assert!(!fs_info.input_interface.elements()[0].relaxed_precision);
+
+        // The precision doesn't affect compatibility, but the mismatch is reported.
+        assert!(fs_info
+            .input_interface
+            .matches(&vs_info.output_interface)
+            .is_ok());
+        assert_eq!(
+            fs_info
+                .input_interface
+                .relaxed_precision_mismatches(&vs_info.output_interface),
+            [0],
+        );
+    }
+
+    #[test]
+    fn entry_point_uses_sample_rate_shading() {
+        let (comp, _) = compile(
+            &MacroInput::empty(),
+            None,
+            Path::new(""),
+            r#"
+                #version 450
+
+                layout(location = 0) out vec4 f_color;
+
+                void main() {
+                    f_color = vec4(float(gl_SampleID));
+                }
+            "#,
+            ShaderKind::Fragment,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert!(info.uses_sample_rate_shading);
+        assert!(!info.uses_dual_source_blend);
+    }
 }
@@ -33,7 +33,8 @@
 //!
 //! - The `load` constructor. This function takes an `Arc<Device>`, constructs a
 //!   [`ShaderModule`] with the passed-in device and the shader data provided
-//!   via the macro, and returns `Result<Arc<ShaderModule>, Validated<VulkanError>>`.
+//!   via the macro, and returns
+//!   `Result<Arc<ShaderModule>, Validated<CreateShaderModuleError>>`.
 //!   Before doing so, it checks every capability instruction in the shader data,
 //!   verifying that the passed-in `Device` has the appropriate features enabled.
 //! - If the `shaders` option is used, then instead of one `load` constructor, there is one for
@@ -50,7 +51,11 @@
 //! ```
 //! # fn main() {}
 //! # use std::sync::Arc;
-//! # use vulkano::{device::Device, shader::ShaderModule, Validated, VulkanError};
+//! # use vulkano::{
+//! #     device::Device,
+//! #     shader::{CreateShaderModuleError, ShaderModule},
+//! #     Validated,
+//! # };
 //! #
 //! # mod vs {
 //! #     vulkano_shaders::shader!{
@@ -74,7 +79,7 @@
 //! }
 //!
 //! impl Shaders {
-//!     pub fn load(device: Arc<Device>) -> Result<Self, Validated<VulkanError>> {
+//!     pub fn load(device: Arc<Device>) -> Result<Self, Validated<CreateShaderModuleError>> {
 //!         Ok(Self {
 //!             vs: vs::load(device)?,
 //!         })
@@ -69,6 +69,9 @@ pub struct SpirvKindEnumerant {
     pub parameters: Vec<SpirvParameter>,
     #[serde(default)]
     pub capabilities: Vec<String>,
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    pub version: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
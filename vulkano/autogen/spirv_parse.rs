@@ -11,7 +11,7 @@ use super::{write_file, SpirvGrammar};
 use ahash::{HashMap, HashSet};
 use heck::ToSnakeCase;
 use once_cell::sync::Lazy;
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::{Ident, Literal, TokenStream};
 use quote::{format_ident, quote};
 
 // From the documentation of the OpSpecConstantOp instruction.
@@ -78,6 +78,7 @@ pub fn write(grammar: &SpirvGrammar) {
 
     let bit_enum_output = bit_enum_output(&bit_enum_members(grammar));
     let value_enum_output = value_enum_output(&value_enum_members(grammar));
+    let capability_output = capability_output(grammar);
 
     write_file(
         "spirv_parse.rs",
@@ -90,6 +91,7 @@ pub fn write(grammar: &SpirvGrammar) {
             #spec_constant_instr_output
             #bit_enum_output
             #value_enum_output
+            #capability_output
         },
     );
 }
@@ -108,6 +110,9 @@ struct OperandMember {
     name: Ident,
     ty: TokenStream,
     parse: TokenStream,
+    write: TokenStream,
+    quantifier: Option<char>,
+    visit_ids: Option<TokenStream>,
 }
 
 fn instruction_output(members: &[InstructionMember], spec_constant: bool) -> TokenStream {
@@ -167,6 +172,99 @@ fn instruction_output(members: &[InstructionMember], spec_constant: bool) -> Tok
         format_ident!("Instruction")
     };
 
+    let visit_ids_items =
+        members
+            .iter()
+            .filter_map(|InstructionMember { name, operands, .. }| {
+                let operands = operands
+                    .iter()
+                    .filter(|operand| operand.visit_ids.is_some())
+                    .collect::<Vec<_>>();
+
+                if operands.is_empty() {
+                    return None;
+                }
+
+                let names = operands.iter().map(|OperandMember { name, .. }| name);
+                let visits = operands.iter().map(
+                    |OperandMember {
+                         name,
+                         quantifier,
+                         visit_ids,
+                         ..
+                     }| match quantifier {
+                        Some('?' | '*') => quote! {
+                            for id_value in #name.iter() {
+                                #visit_ids
+                            }
+                        },
+                        _ => quote! {
+                            let id_value = #name;
+                            #visit_ids
+                        },
+                    },
+                );
+
+                Some(quote! {
+                    Self::#name { #(#names,)* .. } => {
+                        #({ #visits })*
+                    }
+                })
+            });
+
+    let write_items = members.iter().map(
+        |InstructionMember {
+             name,
+             opcode,
+             operands,
+             ..
+         }| {
+            if operands.is_empty() {
+                quote! {
+                    Self::#name => #opcode,
+                }
+            } else {
+                let names = operands.iter().map(|OperandMember { name, .. }| name);
+                let writes = operands.iter().map(
+                    |OperandMember {
+                         name,
+                         write,
+                         quantifier,
+                         ..
+                     }| match quantifier {
+                        Some('?' | '*') => quote! {
+                            for write_value in #name.iter() {
+                                #write
+                            }
+                        },
+                        _ => quote! {
+                            let write_value = #name;
+                            #write
+                        },
+                    },
+                );
+
+                quote! {
+                    Self::#name { #(#names),* } => {
+                        #({ #writes })*
+                        #opcode
+                    }
+                }
+            }
+        },
+    );
+    let (write_doc, write_first_word) = if spec_constant {
+        (
+            "Writes the opcode and operands of the instruction to `words`.",
+            quote! { opcode as u32 },
+        )
+    } else {
+        (
+            "Writes the instruction to `words`, including the word count and opcode.",
+            quote! { ((words.len() - start) as u32) << 16 | opcode as u32 },
+        )
+    };
+
     let result_fns = if spec_constant {
         quote! {}
     } else {
@@ -184,6 +282,20 @@ fn instruction_output(members: &[InstructionMember], spec_constant: bool) -> Tok
             },
         );
 
+        let result_type_id_items = members.iter().filter_map(
+            |InstructionMember {
+                 name,
+                 has_result_type_id,
+                 ..
+             }| {
+                if *has_result_type_id {
+                    Some(quote! { Self::#name { result_type_id, .. } })
+                } else {
+                    None
+                }
+            },
+        );
+
         quote! {
             /// Returns the `Id` that is assigned by this instruction, if any.
             pub fn result_id(&self) -> Option<Id> {
@@ -192,6 +304,14 @@ fn instruction_output(members: &[InstructionMember], spec_constant: bool) -> Tok
                     _ => None
                 }
             }
+
+            /// Returns the `Id` of the type of the result of this instruction, if any.
+            pub fn result_type_id(&self) -> Option<Id> {
+                match self {
+                    #(#result_type_id_items)|* => Some(*result_type_id),
+                    _ => None
+                }
+            }
         }
     };
 
@@ -220,12 +340,36 @@ fn instruction_output(members: &[InstructionMember], spec_constant: bool) -> Tok
             }
 
             #result_fns
+
+            /// Calls `f` for every `Id` that is referenced by an operand of this instruction,
+            /// not including the `Id` that is assigned by the instruction itself.
+            #[allow(dead_code)]
+            pub(crate) fn visit_ids(&self, f: &mut impl FnMut(Id)) {
+                match self {
+                    #(#visit_ids_items)*
+                    _ => (),
+                }
+            }
+
+            #[doc=#write_doc]
+            #[allow(dead_code)]
+            fn write_words(&self, words: &mut Vec<u32>) {
+                let start = words.len();
+                words.push(0);
+
+                let opcode: u16 = match self {
+                    #(#write_items)*
+                };
+
+                words[start] = #write_first_word;
+            }
         }
     }
 }
 
 fn instruction_members(grammar: &SpirvGrammar) -> Vec<InstructionMember> {
     let operand_kinds = kinds_to_types(grammar);
+    let id_kinds = kinds_with_ids(grammar);
     grammar
         .instructions
         .iter()
@@ -251,7 +395,7 @@ fn instruction_members(grammar: &SpirvGrammar) -> Vec<InstructionMember> {
 
                     *operand_names.entry(name.clone()).or_insert(0) += 1;
 
-                    let (ty, parse) = &operand_kinds[operand.kind.as_str()];
+                    let (ty, parse, write) = &operand_kinds[operand.kind.as_str()];
                     let ty = match operand.quantifier {
                         Some('?') => quote! { Option<#ty> },
                         Some('*') => quote! { Vec<#ty> },
@@ -274,8 +418,20 @@ fn instruction_members(grammar: &SpirvGrammar) -> Vec<InstructionMember> {
                         }},
                         _ => parse.clone(),
                     };
+                    let visit_ids = if operand.kind == "IdResult" {
+                        None
+                    } else {
+                        kind_visit_ids(&operand.kind, &id_kinds)
+                    };
 
-                    OperandMember { name, ty, parse }
+                    OperandMember {
+                        name,
+                        ty,
+                        parse,
+                        write: write.clone(),
+                        quantifier: operand.quantifier,
+                        visit_ids,
+                    }
                 })
                 .collect::<Vec<_>>();
 
@@ -364,6 +520,114 @@ fn bit_enum_output(enums: &[(Ident, Vec<KindEnumMember>)]) -> TokenStream {
             },
         );
 
+        let visit_ids_items = members
+            .iter()
+            .filter_map(
+                |KindEnumMember {
+                     name, parameters, ..
+                 }| {
+                    let visits = if let [OperandMember { visit_ids, .. }] = parameters.as_slice() {
+                        visit_ids.clone()?
+                    } else {
+                        let visits = parameters
+                            .iter()
+                            .enumerate()
+                            .filter_map(|(index, OperandMember { visit_ids, .. })| {
+                                let index = Literal::usize_unsuffixed(index);
+                                visit_ids.as_ref().map(|visit_ids| {
+                                    quote! {{
+                                        let id_value = &id_value.#index;
+                                        #visit_ids
+                                    }}
+                                })
+                            })
+                            .collect::<Vec<_>>();
+
+                        if visits.is_empty() {
+                            return None;
+                        }
+
+                        quote! { #(#visits)* }
+                    };
+
+                    Some(quote! {
+                        if let Some(id_value) = &self.#name {
+                            #visits
+                        }
+                    })
+                },
+            )
+            .collect::<Vec<_>>();
+        let visit_ids_fn = if visit_ids_items.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                #[allow(dead_code)]
+                fn visit_ids(&self, f: &mut impl FnMut(Id)) {
+                    #(#visit_ids_items)*
+                }
+            }
+        };
+
+        let write_value_items = members.iter().map(
+            |KindEnumMember {
+                 name,
+                 value,
+                 parameters,
+             }| {
+                if parameters.is_empty() {
+                    quote! {
+                        if self.#name {
+                            value |= #value;
+                        }
+                    }
+                } else {
+                    quote! {
+                        if self.#name.is_some() {
+                            value |= #value;
+                        }
+                    }
+                }
+            },
+        );
+        let write_parameters_items = members.iter().filter_map(
+            |KindEnumMember {
+                 name, parameters, ..
+             }| {
+                let writes = if let [OperandMember { write, .. }] = parameters.as_slice() {
+                    write.clone()
+                } else if parameters.is_empty() {
+                    return None;
+                } else {
+                    let writes = parameters.iter().enumerate().map(
+                        |(index, OperandMember { write, .. })| {
+                            let index = Literal::usize_unsuffixed(index);
+                            quote! {{
+                                let write_value = &write_value.#index;
+                                #write
+                            }}
+                        },
+                    );
+                    quote! { #(#writes)* }
+                };
+
+                Some(quote! {
+                    if let Some(write_value) = &self.#name {
+                        #writes
+                    }
+                })
+            },
+        );
+        let write_value = if members.is_empty() {
+            quote! { words.push(0); }
+        } else {
+            quote! {
+                let mut value = 0;
+                #(#write_value_items)*
+                words.push(value);
+            }
+        };
+
         quote! {
             #[derive(Clone, Copy, Debug, PartialEq, Eq)]
             #[allow(non_camel_case_types)]
@@ -380,6 +644,14 @@ fn bit_enum_output(enums: &[(Ident, Vec<KindEnumMember>)]) -> TokenStream {
                         #(#parse_items)*
                     })
                 }
+
+                #visit_ids_fn
+
+                #[allow(dead_code)]
+                fn write_words(&self, words: &mut Vec<u32>) {
+                    #write_value
+                    #(#write_parameters_items)*
+                }
             }
         }
     });
@@ -391,6 +663,7 @@ fn bit_enum_output(enums: &[(Ident, Vec<KindEnumMember>)]) -> TokenStream {
 
 fn bit_enum_members(grammar: &SpirvGrammar) -> Vec<(Ident, Vec<KindEnumMember>)> {
     let parameter_kinds = kinds_to_types(grammar);
+    let id_kinds = kinds_with_ids(grammar);
 
     grammar
         .operand_kinds
@@ -433,9 +706,17 @@ fn bit_enum_members(grammar: &SpirvGrammar) -> Vec<(Ident, Vec<KindEnumMember>)>
                         .iter()
                         .map(|param| {
                             let name = to_member_name(&param.kind, param.name.as_deref());
-                            let (ty, parse) = parameter_kinds[param.kind.as_str()].clone();
+                            let (ty, parse, write) = parameter_kinds[param.kind.as_str()].clone();
+                            let visit_ids = kind_visit_ids(&param.kind, &id_kinds);
 
-                            OperandMember { name, ty, parse }
+                            OperandMember {
+                                name,
+                                ty,
+                                parse,
+                                write,
+                                quantifier: None,
+                                visit_ids,
+                            }
                         })
                         .collect();
 
@@ -504,11 +785,91 @@ fn value_enum_output(enums: &[(Ident, Vec<KindEnumMember>)]) -> TokenStream {
         let name_string = name.to_string();
 
         let derives = match name_string.as_str() {
-            "ExecutionModel" => quote! { #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)] },
+            "ExecutionModel" | "StorageClass" => {
+                quote! { #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)] }
+            }
             "Decoration" => quote! { #[derive(Clone, Debug, PartialEq)] },
             _ => quote! { #[derive(Clone, Copy, Debug, PartialEq, Eq)] },
         };
 
+        let visit_ids_items = members
+            .iter()
+            .filter_map(
+                |KindEnumMember {
+                     name, parameters, ..
+                 }| {
+                    let parameters = parameters
+                        .iter()
+                        .filter(|parameter| parameter.visit_ids.is_some())
+                        .collect::<Vec<_>>();
+
+                    if parameters.is_empty() {
+                        return None;
+                    }
+
+                    let names = parameters.iter().map(|OperandMember { name, .. }| name);
+                    let visits = parameters.iter().map(
+                        |OperandMember {
+                             name, visit_ids, ..
+                         }| {
+                            quote! {{
+                                let id_value = #name;
+                                #visit_ids
+                            }}
+                        },
+                    );
+
+                    Some(quote! {
+                        Self::#name { #(#names,)* .. } => {
+                            #(#visits)*
+                        }
+                    })
+                },
+            )
+            .collect::<Vec<_>>();
+        let visit_ids_fn = if visit_ids_items.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                #[allow(dead_code)]
+                fn visit_ids(&self, f: &mut impl FnMut(Id)) {
+                    match self {
+                        #(#visit_ids_items)*
+                        _ => (),
+                    }
+                }
+            }
+        };
+
+        let write_items = members.iter().map(
+            |KindEnumMember {
+                 name,
+                 value,
+                 parameters,
+             }| {
+                if parameters.is_empty() {
+                    quote! {
+                        Self::#name => words.push(#value),
+                    }
+                } else {
+                    let names = parameters.iter().map(|OperandMember { name, .. }| name);
+                    let writes = parameters.iter().map(|OperandMember { name, write, .. }| {
+                        quote! {{
+                            let write_value = #name;
+                            #write
+                        }}
+                    });
+
+                    quote! {
+                        Self::#name { #(#names),* } => {
+                            words.push(#value);
+                            #(#writes)*
+                        }
+                    }
+                }
+            },
+        );
+
         quote! {
             #derives
             #[allow(non_camel_case_types)]
@@ -524,6 +885,15 @@ fn value_enum_output(enums: &[(Ident, Vec<KindEnumMember>)]) -> TokenStream {
                         value => return Err(reader.map_err(ParseErrors::UnknownEnumerant(#name_string, value))),
                     })
                 }
+
+                #visit_ids_fn
+
+                #[allow(dead_code)]
+                fn write_words(&self, words: &mut Vec<u32>) {
+                    match self {
+                        #(#write_items)*
+                    }
+                }
             }
         }
     });
@@ -535,6 +905,7 @@ fn value_enum_output(enums: &[(Ident, Vec<KindEnumMember>)]) -> TokenStream {
 
 fn value_enum_members(grammar: &SpirvGrammar) -> Vec<(Ident, Vec<KindEnumMember>)> {
     let parameter_kinds = kinds_to_types(grammar);
+    let id_kinds = kinds_with_ids(grammar);
 
     grammar
         .operand_kinds
@@ -565,9 +936,17 @@ fn value_enum_members(grammar: &SpirvGrammar) -> Vec<(Ident, Vec<KindEnumMember>
                         .iter()
                         .map(|param| {
                             let name = to_member_name(&param.kind, param.name.as_deref());
-                            let (ty, parse) = parameter_kinds[param.kind.as_str()].clone();
+                            let (ty, parse, write) = parameter_kinds[param.kind.as_str()].clone();
+                            let visit_ids = kind_visit_ids(&param.kind, &id_kinds);
 
-                            OperandMember { name, ty, parse }
+                            OperandMember {
+                                name,
+                                ty,
+                                parse,
+                                write,
+                                quantifier: None,
+                                visit_ids,
+                            }
                         })
                         .collect();
 
@@ -584,6 +963,82 @@ fn value_enum_members(grammar: &SpirvGrammar) -> Vec<(Ident, Vec<KindEnumMember>
         .collect()
 }
 
+fn capability_output(grammar: &SpirvGrammar) -> TokenStream {
+    let capability_kind = grammar
+        .operand_kinds
+        .iter()
+        .find(|operand_kind| operand_kind.kind == "Capability")
+        .unwrap();
+
+    // Aliases share the value of the enumerant they alias, but may be enabled by different
+    // extensions. They are merged into the first enumerant with that value.
+    let mut members: Vec<(&str, u64, Option<&str>, Vec<&str>)> = Vec::new();
+
+    for enumerant in &capability_kind.enumerants {
+        let value = enumerant.value.as_u64().unwrap();
+        let extensions = enumerant.extensions.iter().map(String::as_str);
+
+        match members.last_mut() {
+            Some((_, member_value, _, member_extensions)) if *member_value == value => {
+                member_extensions.extend(extensions);
+            }
+            _ => members.push((
+                &enumerant.enumerant,
+                value,
+                enumerant.version.as_deref(),
+                extensions.collect(),
+            )),
+        }
+    }
+
+    let version_items = members.iter().filter_map(|&(name, _, version, _)| {
+        let name = format_ident!("{}", name);
+        let (major, minor) = version?.split_once('.')?;
+        let major: u32 = major.parse().unwrap();
+        let minor: u32 = minor.parse().unwrap();
+
+        Some(quote! {
+            Self::#name => Some(Version::major_minor(#major, #minor)),
+        })
+    });
+    let extensions_items = members.iter().filter_map(|(name, _, _, extensions)| {
+        if extensions.is_empty() {
+            return None;
+        }
+
+        let name = format_ident!("{}", name);
+        let mut extensions = extensions.clone();
+        extensions.sort_unstable();
+        extensions.dedup();
+
+        Some(quote! {
+            Self::#name => &[#(#extensions),*],
+        })
+    });
+
+    quote! {
+        impl Capability {
+            /// Returns the minimum SPIR-V version in which the capability is available without
+            /// declaring an extension, or `None` if it is only available through an extension.
+            pub fn spirv_version(self) -> Option<Version> {
+                match self {
+                    #(#version_items)*
+                    _ => None,
+                }
+            }
+
+            /// Returns the SPIR-V extensions that make the capability available, if the module
+            /// version is lower than [`spirv_version`](Self::spirv_version).
+            pub fn spirv_extensions(self) -> &'static [&'static str] {
+                match self {
+                    #(#extensions_items)*
+                    _ => &[],
+                }
+            }
+        }
+    }
+}
+
 fn to_member_name(kind: &str, name: Option<&str>) -> Ident {
     if let Some(name) = name {
         let name = name.to_snake_case();
@@ -605,23 +1060,67 @@ fn to_member_name(kind: &str, name: Option<&str>) -> Ident {
     }
 }
 
-fn kinds_to_types(grammar: &SpirvGrammar) -> HashMap<&str, (TokenStream, TokenStream)> {
+/// Returns the names of the enum operand kinds that have an enumerant with an `Id` parameter.
+fn kinds_with_ids(grammar: &SpirvGrammar) -> HashSet<&str> {
+    grammar
+        .operand_kinds
+        .iter()
+        .filter(|operand_kind| {
+            operand_kind.enumerants.iter().any(|enumerant| {
+                enumerant
+                    .parameters
+                    .iter()
+                    .any(|param| param.kind.starts_with("Id"))
+            })
+        })
+        .map(|operand_kind| operand_kind.kind.as_str())
+        .collect()
+}
+
+/// Returns the code that calls `f` for every `Id` within `id_value`, an operand of the given
+/// kind.
+fn kind_visit_ids(kind: &str, id_kinds: &HashSet<&str>) -> Option<TokenStream> {
+    match kind {
+        "PairIdRefIdRef" => Some(quote! { f(id_value.0); f(id_value.1); }),
+        "PairIdRefLiteralInteger" => Some(quote! { f(id_value.0); }),
+        "PairLiteralIntegerIdRef" => Some(quote! { f(id_value.1); }),
+        "LiteralSpecConstantOpInteger" => Some(quote! { id_value.visit_ids(f); }),
+        _ if kind.starts_with("Id") => Some(quote! { f(*id_value); }),
+        _ if id_kinds.contains(kind) => Some(quote! { id_value.visit_ids(f); }),
+        _ => None,
+    }
+}
+
+/// Returns, for each operand kind, its Rust type, the code that parses it from `reader`, and the
+/// code that writes `write_value`, a reference to an operand of the kind, to `words`.
+fn kinds_to_types(
+    grammar: &SpirvGrammar,
+) -> HashMap<&str, (TokenStream, TokenStream, TokenStream)> {
     grammar
         .operand_kinds
         .iter()
         .map(|k| {
-            let (ty, parse) = match k.kind.as_str() {
-                "LiteralContextDependentNumber" => {
-                    (quote! { Vec<u32> }, quote! { reader.remainder() })
-                }
-                "LiteralInteger" | "LiteralExtInstInteger" => {
-                    (quote! { u32 }, quote! { reader.next_word()? })
-                }
+            let (ty, parse, write) = match k.kind.as_str() {
+                "LiteralContextDependentNumber" => (
+                    quote! { Vec<u32> },
+                    quote! { reader.remainder() },
+                    quote! { words.extend_from_slice(write_value); },
+                ),
+                "LiteralInteger" | "LiteralExtInstInteger" => (
+                    quote! { u32 },
+                    quote! { reader.next_word()? },
+                    quote! { words.push(*write_value); },
+                ),
                 "LiteralSpecConstantOpInteger" => (
                     quote! { SpecConstantInstruction },
                     quote! { SpecConstantInstruction::parse(reader)? },
+                    quote! { write_value.write_words(words); },
+                ),
+                "LiteralString" => (
+                    quote! { String },
+                    quote! { reader.next_string()? },
+                    quote! { write_string(words, write_value); },
                 ),
-                "LiteralString" => (quote! { String }, quote! { reader.next_string()? }),
                 "PairIdRefIdRef" => (
                     quote! { (Id, Id) },
                     quote! {
@@ -630,6 +1129,10 @@ fn kinds_to_types(grammar: &SpirvGrammar) -> HashMap<&str, (TokenStream, TokenSt
                             Id(reader.next_word()?),
                         )
                     },
+                    quote! {
+                        words.push(write_value.0.as_raw());
+                        words.push(write_value.1.as_raw());
+                    },
                 ),
                 "PairIdRefLiteralInteger" => (
                     quote! { (Id, u32) },
@@ -639,6 +1142,10 @@ fn kinds_to_types(grammar: &SpirvGrammar) -> HashMap<&str, (TokenStream, TokenSt
                             reader.next_word()?
                         )
                     },
+                    quote! {
+                        words.push(write_value.0.as_raw());
+                        words.push(write_value.1);
+                    },
                 ),
                 "PairLiteralIntegerIdRef" => (
                     quote! { (u32, Id) },
@@ -647,23 +1154,34 @@ fn kinds_to_types(grammar: &SpirvGrammar) -> HashMap<&str, (TokenStream, TokenSt
                         reader.next_word()?,
                         Id(reader.next_word()?)),
                     },
+                    quote! {
+                        words.push(write_value.0);
+                        words.push(write_value.1.as_raw());
+                    },
+                ),
+                _ if k.kind.starts_with("Id") => (
+                    quote! { Id },
+                    quote! { Id(reader.next_word()?) },
+                    quote! { words.push(write_value.as_raw()); },
                 ),
-                _ if k.kind.starts_with("Id") => {
-                    (quote! { Id }, quote! { Id(reader.next_word()?) })
-                }
                 ident => {
                     let ident = format_ident!("{}", ident);
-                    (quote! { #ident }, quote! { #ident::parse(reader)? })
+                    (
+                        quote! { #ident },
+                        quote! { #ident::parse(reader)? },
+                        quote! { write_value.write_words(words); },
+                    )
                 }
             };
 
-            (k.kind.as_str(), (ty, parse))
+            (k.kind.as_str(), (ty, parse, write))
         })
         .chain([(
             "LiteralFloat",
             (
                 quote! { f32 },
                 quote! { f32::from_bits(reader.next_word()?) },
+                quote! { words.push(write_value.to_bits()); },
             ),
         )])
         .collect()
@@ -67,34 +67,40 @@ enum PropertyValue {
 }
 
 fn spirv_reqs_output(members: &[SpirvReqsMember], is_extension: bool) -> TokenStream {
-    let (item_type, fn_def, not_supported_vuid, item_vuid) = if is_extension {
+    let (item_type, fn_def, requirements_fn_def, not_supported_vuid, item_vuid) = if is_extension {
         (
             "extension",
-            quote! { validate_spirv_extension(device: &Device, item: &str) },
+            quote! { validate_spirv_extension(capabilities: &DeviceCapabilities<'_>, item: &str) },
+            quote! { spirv_extension_requirements(item: &str) },
             "VUID-VkShaderModuleCreateInfo-pCode-08739",
             "VUID-VkShaderModuleCreateInfo-pCode-08740",
         )
     } else {
         (
             "capability",
-            quote! { validate_spirv_capability(device: &Device, item: Capability) },
+            quote! { validate_spirv_capability(capabilities: &DeviceCapabilities<'_>, item: Capability) },
+            quote! { spirv_capability_requirements(item: Capability) },
             "VUID-VkShaderModuleCreateInfo-pCode-08741",
             "VUID-VkShaderModuleCreateInfo-pCode-08742",
         )
     };
 
+    let arm = |name: &str| {
+        if is_extension {
+            quote! { #name }
+        } else {
+            let name = format_ident!("{}", name);
+            quote! { Capability::#name }
+        }
+    };
+
     let items = members.iter().map(
         |SpirvReqsMember {
              name,
              requires_one_of,
              requires_properties,
          }| {
-            let arm = if is_extension {
-                quote! { #name }
-            } else {
-                let name = format_ident!("{}", name);
-                quote! { Capability::#name }
-            };
+            let arm = arm(name);
 
             if !requires_one_of.is_empty() {
                 let &RequiresOneOf {
@@ -116,28 +122,7 @@ fn spirv_reqs_output(members: &[SpirvReqsMember], is_extension: bool) -> TokenSt
                     let ident = format_ident!("{}", name);
                     quote! { features.#ident }
                 }));
-                let requires_one_of_items = (api_version.iter().map(|(major, minor)| {
-                    let version = format_ident!("V{}_{}", major, minor);
-                    quote! {
-                        crate::RequiresAllOf(&[
-                            crate::Requires::APIVersion(crate::Version::#version),
-                        ]),
-                    }
-                }))
-                .chain(device_extensions.iter().map(|name| {
-                    quote! {
-                        crate::RequiresAllOf(&[
-                            crate::Requires::DeviceExtension(#name),
-                        ]),
-                    }
-                }))
-                .chain(features.iter().map(|name| {
-                    quote! {
-                        crate::RequiresAllOf(&[
-                            crate::Requires::Feature(#name),
-                        ]),
-                    }
-                }));
+                let requires_one_of_items = requires_one_of_items(requires_one_of);
                 let problem = format!("uses the SPIR-V {} `{}`", item_type, name);
 
                 quote! {
@@ -155,25 +140,26 @@ fn spirv_reqs_output(members: &[SpirvReqsMember], is_extension: bool) -> TokenSt
                     },
                 }
             } else if !requires_properties.is_empty() {
-                let condition_items = requires_properties.iter().map(
-                    |RequiresProperty { name, value }| {
-                        let name = format_ident!("{}", name);
-                        let access = match value {
-                            PropertyValue::Bool => quote! {},
-                            PropertyValue::FlagsIntersects { path, ty, flag } => {
-                                let ty = format_ident!("{}", ty);
-                                let flag = format_ident!("{}", flag);
-                                quote! {
-                                    .map(|x| x.intersects(#path :: #ty :: #flag))
+                let condition_items =
+                    requires_properties
+                        .iter()
+                        .map(|RequiresProperty { name, value }| {
+                            let name = format_ident!("{}", name);
+                            let access = match value {
+                                PropertyValue::Bool => quote! {},
+                                PropertyValue::FlagsIntersects { path, ty, flag } => {
+                                    let ty = format_ident!("{}", ty);
+                                    let flag = format_ident!("{}", flag);
+                                    quote! {
+                                        .map(|x| x.intersects(#path :: #ty :: #flag))
+                                    }
                                 }
-                            }
-                        };
+                            };
 
-                        quote! {
-                            device.physical_device().properties().#name #access .unwrap_or(false)
-                        }
-                    },
-                );
+                            quote! {
+                                properties.#name #access .unwrap_or(false)
+                            }
+                        });
                 let problem = {
                     let requirements_items: Vec<_> = requires_properties
                         .iter()
@@ -213,20 +199,44 @@ fn spirv_reqs_output(members: &[SpirvReqsMember], is_extension: bool) -> TokenSt
         },
     );
 
+    let requirements_items = members.iter().map(
+        |SpirvReqsMember {
+             name,
+             requires_one_of,
+             requires_properties: _,
+         }| {
+            let arm = arm(name);
+            let requires_one_of_items = requires_one_of_items(requires_one_of);
+
+            quote! {
+                #arm => Some(crate::RequiresOneOf(&[
+                    #(#requires_one_of_items)*
+                ])),
+            }
+        },
+    );
+
     let problem = format!(
         "uses the SPIR-V {} `{{item:?}}`, which is not supported by Vulkan",
         item_type,
     );
+    let requirements_doc = format!(
+        "Returns what must be supported or enabled on a device to use the SPIR-V {0} `item` in a \
+        shader module, or `None` if the {0} is not supported by Vulkan at all.\n\n\
+        Some {0}s instead require certain device properties. These requirements are not \
+        included, and an empty `RequiresOneOf` is returned for them.",
+        item_type,
+    );
     quote! {
         fn #fn_def -> Result<(), Box<ValidationError>> {
             #[allow(unused_variables)]
-            let api_version = device.api_version();
+            let api_version = capabilities.api_version;
             #[allow(unused_variables)]
-            let device_extensions = device.enabled_extensions();
+            let device_extensions = capabilities.enabled_extensions;
             #[allow(unused_variables)]
-            let features = device.enabled_features();
+            let features = capabilities.enabled_features;
             #[allow(unused_variables)]
-            let properties = device.physical_device().properties();
+            let properties = capabilities.properties;
 
             match item {
                 #(#items)*
@@ -240,9 +250,50 @@ fn spirv_reqs_output(members: &[SpirvReqsMember], is_extension: bool) -> TokenSt
             }
             Ok(())
         }
+
+        #[doc = #requirements_doc]
+        pub fn #requirements_fn_def -> Option<crate::RequiresOneOf> {
+            match item {
+                #(#requirements_items)*
+                _ => None,
+            }
+        }
     }
 }
 
+fn requires_one_of_items(requires_one_of: &RequiresOneOf) -> Vec<TokenStream> {
+    let &RequiresOneOf {
+        api_version,
+        ref device_extensions,
+        instance_extensions: _,
+        ref features,
+    } = requires_one_of;
+
+    (api_version.iter().map(|(major, minor)| {
+        let version = format_ident!("V{}_{}", major, minor);
+        quote! {
+            crate::RequiresAllOf(&[
+                crate::Requires::APIVersion(crate::Version::#version),
+            ]),
+        }
+    }))
+    .chain(device_extensions.iter().map(|name| {
+        quote! {
+            crate::RequiresAllOf(&[
+                crate::Requires::DeviceExtension(#name),
+            ]),
+        }
+    }))
+    .chain(features.iter().map(|name| {
+        quote! {
+            crate::RequiresAllOf(&[
+                crate::Requires::Feature(#name),
+            ]),
+        }
+    }))
+    .collect()
+}
+
 fn spirv_capabilities_members(
     capabilities: &[&SpirvExtOrCap],
     grammar_enumerants: &[SpirvKindEnumerant],
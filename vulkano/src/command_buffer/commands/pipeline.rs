@@ -926,6 +926,7 @@ where
                     {
                         if layout_binding.descriptor_type == DescriptorType::StorageTexelBuffer {
                             if binding_reqs.image_format.is_none()
+                                && desc_reqs.image_format.is_none()
                                 && !desc_reqs.memory_write.is_empty()
                                 && !buffer_view
                                     .format_features()
@@ -946,6 +947,7 @@ where
                             }
 
                             if binding_reqs.image_format.is_none()
+                                && desc_reqs.image_format.is_none()
                                 && !desc_reqs.memory_read.is_empty()
                                 && !buffer_view
                                     .format_features()
@@ -996,6 +998,7 @@ where
 
                         if layout_binding.descriptor_type == DescriptorType::StorageImage {
                             if binding_reqs.image_format.is_none()
+                                && desc_reqs.image_format.is_none()
                                 && !desc_reqs.memory_write.is_empty()
                                 && !image_view
                                     .format_features()
@@ -1016,6 +1019,7 @@ where
                             }
 
                             if binding_reqs.image_format.is_none()
+                                && desc_reqs.image_format.is_none()
                                 && !desc_reqs.memory_read.is_empty()
                                 && !image_view
                                     .format_features()
@@ -1043,7 +1047,13 @@ where
                     */
 
                     // The SPIR-V Image Format is not compatible with the image view’s format.
-                    if let Some(format) = binding_reqs.image_format {
+                    let required_format = binding_reqs.image_format.or_else(|| {
+                        (binding_reqs.descriptors.get(&Some(index)).into_iter())
+                            .chain(binding_reqs.descriptors.get(&None))
+                            .find_map(|desc_reqs| desc_reqs.image_format)
+                    });
+
+                    if let Some(format) = required_format {
                         if image_view.format() != format {
                             return Err(Box::new(ValidationError {
                                 problem: format!(
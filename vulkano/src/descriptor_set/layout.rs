@@ -629,12 +629,19 @@ impl DescriptorSetLayoutBinding {
         let &DescriptorBindingRequirements {
             ref descriptor_types,
             descriptor_count,
+            nonuniform_indexing: _,
             image_format: _,
             image_multisampled: _,
+            image_depth: _,
             image_scalar_type: _,
+            image_scalar_width: _,
             image_view_type: _,
+            input_attachment_index: _,
+            runtime_array_stride: _,
+            storage_read_without_format: _,
+            storage_write_without_format: _,
             stages,
-            descriptors: _,
+            ref descriptors,
         } = binding_requirements;
 
         if !descriptor_types.contains(&self.descriptor_type) {
@@ -666,6 +673,28 @@ impl DescriptorSetLayoutBinding {
             }));
         }
 
+        for (index, sampler) in self.immutable_samplers.iter().enumerate() {
+            if sampler.sampler_ycbcr_conversion().is_none() {
+                continue;
+            }
+
+            if (descriptors.get(&Some(index as u32)).into_iter())
+                .chain(descriptors.get(&None))
+                .any(|desc_reqs| desc_reqs.sampler_no_ycbcr_conversion)
+            {
+                return Err(Box::new(ValidationError {
+                    problem: format!(
+                        "the immutable sampler for descriptor index {} has a sampler YCbCr \
+                        conversion, but the descriptor binding requirements forbid a sampler \
+                        YCbCr conversion for this descriptor",
+                        index,
+                    )
+                    .into(),
+                    ..Default::default()
+                }));
+            }
+        }
+
         Ok(())
     }
 
@@ -1115,6 +1144,7 @@ vulkan_enum! {
     #[non_exhaustive]
 
     /// Describes what kind of resource may later be bound to a descriptor.
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     DescriptorType = DescriptorType(i32);
 
     /// Describes how a `SampledImage` descriptor should be read.
@@ -1268,7 +1298,15 @@ mod tests {
             DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo,
             DescriptorType,
         },
-        shader::ShaderStages,
+        format::Format,
+        image::sampler::{
+            ycbcr::{
+                SamplerYcbcrConversion, SamplerYcbcrConversionCreateInfo,
+                SamplerYcbcrModelConversion,
+            },
+            Sampler, SamplerCreateInfo,
+        },
+        shader::{DescriptorBindingRequirements, DescriptorRequirements, ShaderStages},
     };
     use ahash::HashMap;
 
@@ -1305,4 +1343,69 @@ mod tests {
                 .collect::<HashMap<_, _>>(),
         );
     }
+
+    #[test]
+    fn ycbcr_immutable_samplers_compatible_with_shader() {
+        let (device, _) = gfx_dev_and_queue!(sampler_ycbcr_conversion);
+
+        let conversion = SamplerYcbcrConversion::new(
+            device.clone(),
+            SamplerYcbcrConversionCreateInfo {
+                format: Format::G8_B8_R8_3PLANE_420_UNORM,
+                ycbcr_model: SamplerYcbcrModelConversion::YcbcrIdentity,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let sampler = Sampler::new(
+            device,
+            SamplerCreateInfo {
+                sampler_ycbcr_conversion: Some(conversion),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let binding = DescriptorSetLayoutBinding {
+            stages: ShaderStages::FRAGMENT,
+            descriptor_count: 2,
+            immutable_samplers: vec![sampler.clone(), sampler],
+            ..DescriptorSetLayoutBinding::descriptor_type(DescriptorType::CombinedImageSampler)
+        };
+
+        // The shader samples both descriptors in a way that is allowed with a conversion.
+        let mut binding_requirements = DescriptorBindingRequirements {
+            descriptor_types: vec![DescriptorType::CombinedImageSampler],
+            descriptor_count: Some(2),
+            stages: ShaderStages::FRAGMENT,
+            descriptors: [
+                (Some(0), DescriptorRequirements::default()),
+                (Some(1), DescriptorRequirements::default()),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+        assert_eq!(
+            binding_requirements.sampler_ycbcr_conversion_compatible_count(),
+            Some(2),
+        );
+        assert!(binding
+            .ensure_compatible_with_shader(&binding_requirements)
+            .is_ok());
+
+        // The shader gathers from the second descriptor, which is forbidden with a conversion.
+        binding_requirements
+            .descriptors
+            .get_mut(&Some(1))
+            .unwrap()
+            .sampler_no_ycbcr_conversion = true;
+        assert_eq!(
+            binding_requirements.sampler_ycbcr_conversion_compatible_count(),
+            Some(1),
+        );
+        assert!(binding
+            .ensure_compatible_with_shader(&binding_requirements)
+            .is_err());
+    }
 }
@@ -101,8 +101,11 @@
 //!
 //! TODO: write
 
-use self::physical::PhysicalDevice;
 pub(crate) use self::properties::PropertiesFfi;
+use self::{
+    physical::PhysicalDevice,
+    private_data::{PrivateDataSlot, PrivateDataSlotCreateInfo},
+};
 pub use self::{
     properties::Properties,
     queue::{Queue, QueueFamilyProperties, QueueFlags, QueueGuard},
@@ -164,6 +167,7 @@ pub struct Device {
     api_version: Version,
     fns: DeviceFunctions,
     active_queue_family_indices: SmallVec<[u32; 2]>,
+    private_data_slot_request_count: u32,
 
     // This is required for validation in `memory::device_memory`, the count must only be modified
     // in that module.
@@ -416,7 +420,7 @@ impl Device {
             enabled_features,
             enabled_extensions,
             physical_devices,
-            private_data_slot_request_count: _,
+            private_data_slot_request_count,
             _ne: _,
         } = create_info;
 
@@ -476,6 +480,7 @@ impl Device {
             api_version,
             fns,
             active_queue_family_indices,
+            private_data_slot_request_count,
 
             allocation_count: AtomicU32::new(0),
             fence_pool: Mutex::new(Vec::new()),
@@ -544,6 +549,24 @@ impl Device {
         &self.active_queue_family_indices
     }
 
+    /// Returns the number of private data slots that were reserved when creating the device,
+    /// as given by [`DeviceCreateInfo::private_data_slot_request_count`].
+    #[inline]
+    pub fn private_data_slot_request_count(&self) -> u32 {
+        self.private_data_slot_request_count
+    }
+
+    /// Creates a new [`PrivateDataSlot`] with the default create info.
+    ///
+    /// This is a shortcut for calling [`PrivateDataSlot::new`]. The `private_data` feature must
+    /// be enabled on the device.
+    #[inline]
+    pub fn create_private_data_slot(
+        self: &Arc<Self>,
+    ) -> Result<PrivateDataSlot, Validated<VulkanError>> {
+        PrivateDataSlot::new(self.clone(), PrivateDataSlotCreateInfo::default())
+    }
+
     /// Returns the extensions that have been enabled on the device.
     ///
     /// This includes both the extensions specified in [`DeviceCreateInfo::enabled_extensions`],
@@ -1110,6 +1133,7 @@ impl Debug for Device {
             api_version,
             fns,
             active_queue_family_indices,
+            private_data_slot_request_count,
 
             allocation_count,
             fence_pool: _,
@@ -1127,6 +1151,10 @@ impl Debug for Device {
             .field("api_version", api_version)
             .field("fns", fns)
             .field("active_queue_family_indices", active_queue_family_indices)
+            .field(
+                "private_data_slot_request_count",
+                private_data_slot_request_count,
+            )
             .field("allocation_count", allocation_count)
             .finish_non_exhaustive()
     }
@@ -1784,7 +1812,10 @@ pub struct MemoryFdProperties {
 
 #[cfg(test)]
 mod tests {
-    use crate::device::{Device, DeviceCreateInfo, DeviceExtensions, Features, QueueCreateInfo};
+    use crate::{
+        device::{Device, DeviceCreateInfo, DeviceExtensions, Features, QueueCreateInfo},
+        Version,
+    };
     use std::{ffi::CString, sync::Arc};
 
     #[test]
@@ -1829,6 +1860,58 @@ mod tests {
         assert!(Arc::get_mut(&mut device).is_some());
     }
 
+    #[test]
+    fn private_data_slot_request_count() {
+        let instance = instance!();
+        let physical_device = match instance.enumerate_physical_devices().unwrap().next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        if physical_device.api_version() < Version::V1_3
+            && !physical_device.supported_extensions().ext_private_data
+        {
+            return;
+        }
+
+        let (device, _) = Device::new(
+            physical_device.clone(),
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index: 0,
+                    ..Default::default()
+                }],
+                enabled_extensions: DeviceExtensions {
+                    ext_private_data: physical_device.api_version() < Version::V1_3,
+                    ..DeviceExtensions::empty()
+                },
+                private_data_slot_request_count: 4,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(device.private_data_slot_request_count(), 4);
+    }
+
+    #[test]
+    fn create_private_data_slot() {
+        let (device, _) = gfx_dev_and_queue!(private_data);
+
+        let _slot = device.create_private_data_slot().unwrap();
+    }
+
+    #[test]
+    fn private_data_slot_supports_object() {
+        let (device, _) = gfx_dev_and_queue!(private_data);
+        let (other_device, _) = gfx_dev_and_queue!(private_data);
+
+        let slot = device.create_private_data_slot().unwrap();
+        let other_slot = other_device.create_private_data_slot().unwrap();
+        assert!(slot.supports_object(&slot));
+        assert!(!slot.supports_object(&other_slot));
+    }
+
     #[test]
     fn too_many_queues() {
         let instance = instance!();
@@ -17,6 +17,13 @@
 //! that needs to assign data to objects independently of the others. That way, different parts
 //! of a program manage their own private data and don't interfere with each other's data.
 //!
+//! Private data can be stored for every object that is created from a device, which are the
+//! types that implement [`DeviceOwned`]. Instance-level objects, such as physical devices and
+//! surfaces, don't implement it, so they can't be passed to
+//! [`set_private_data`](PrivateDataSlot::set_private_data). The object must have been created
+//! from the same device as the private data slot, which can be checked with
+//! [`PrivateDataSlot::supports_object`].
+//!
 //! When creating a device, it is possible to reserve private data slots ahead of time using
 //! [`DeviceCreateInfo::private_data_slot_request_count`]. This is not necessary, but it can
 //! speed up the use of data slots later.
@@ -131,6 +138,13 @@ impl PrivateDataSlot {
         }
     }
 
+    /// Returns whether `self` can store data for `object`, which is the case if `object` was
+    /// created from the same device as `self`.
+    #[inline]
+    pub fn supports_object<T: DeviceOwned>(&self, object: &T) -> bool {
+        self.device() == object.device()
+    }
+
     /// Sets the private data that is associated with `object` to `data`.
     ///
     /// If `self` already has data for `object`, that data is replaced with the new value.
@@ -149,7 +163,10 @@ impl PrivateDataSlot {
         &self,
         object: &T,
     ) -> Result<(), Box<ValidationError>> {
-        assert_eq!(self.device(), object.device());
+        assert!(
+            self.supports_object(object),
+            "`object` was not created from the same device as the private data slot",
+        );
 
         Ok(())
     }
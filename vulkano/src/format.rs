@@ -100,6 +100,8 @@ use crate::{
     shader::spirv::ImageFormat,
     DeviceSize, Requires, RequiresAllOf, RequiresOneOf, ValidationError,
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 // Generated by build.rs
 include!(concat!(env!("OUT_DIR"), "/formats.rs"));
@@ -319,6 +321,7 @@ impl NumericFormat {
 
 /// The numeric base type of a scalar value, in a format, a shader, or elsewhere.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum NumericType {
     Float,
     Int,
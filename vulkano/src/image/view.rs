@@ -1081,6 +1081,7 @@ vulkan_enum! {
     #[non_exhaustive]
 
     /// The geometry type of an image view.
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     ImageViewType = ImageViewType(i32);
 
     /// A one-dimensional image view with a single array layer.
@@ -96,11 +96,13 @@
 //!
 //! # Cargo features
 //!
-//! | Feature              | Description                                                    |
-//! |----------------------|----------------------------------------------------------------|
-//! | `macros`             | Include reexports from [`vulkano-macros`]. Enabled by default. |
-//! | `document_unchecked` | Include `_unchecked` functions in the generated documentation. |
-//! | `serde`              | Enables (de)serialization of certain types using [`serde`].    |
+//! | Feature              | Description                                                       |
+//! |----------------------|-------------------------------------------------------------------|
+//! | `macros`             | Include reexports from [`vulkano-macros`]. Enabled by default.    |
+//! | `document_unchecked` | Include `_unchecked` functions in the generated documentation.    |
+//! | `serde`              | Enables (de)serialization of certain types using [`serde`].       |
+//! | `shaderc`            | Enables compiling GLSL at runtime with `ShaderModule::from_glsl`. |
+//! | `spirv_builder`      | Enables `SpirvBuilder`, for building SPIR-V modules in tests.     |
 //!
 //! [`VulkanLibrary`]: crate::VulkanLibrary
 //! [`Instance`]: crate::instance::Instance
@@ -574,6 +574,7 @@ macro_rules! vulkan_bitflags {
 macro_rules! vulkan_enum {
     {
         $(#[doc = $ty_doc:literal])*
+        $(#[cfg_attr($($ty_attr:tt)*)])*
         $ty:ident
         $( impl { $($impls:item)* } )?
         = $ty_ffi:ident($repr:ty);
@@ -584,6 +585,7 @@ macro_rules! vulkan_enum {
         )+
     } => {
         $(#[doc = $ty_doc])*
+        $(#[cfg_attr($($ty_attr)*)])*
         #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
         #[repr($repr)]
         pub enum $ty {
@@ -630,6 +632,7 @@ macro_rules! vulkan_enum {
         #[non_exhaustive]
 
         $(#[doc = $ty_doc:literal])*
+        $(#[cfg_attr($($ty_attr:tt)*)])*
         $ty:ident
         $( impl { $($impls:item)* } )?
         = $ty_ffi:ident($repr:ty);
@@ -649,6 +652,7 @@ macro_rules! vulkan_enum {
         )+
     } => {
         $(#[doc = $ty_doc])*
+        $(#[cfg_attr($($ty_attr)*)])*
         #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
         #[non_exhaustive]
         #[repr($repr)]
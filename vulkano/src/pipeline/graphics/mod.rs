@@ -2416,14 +2416,47 @@ impl GraphicsPipelineCreateInfo {
             }
         }
 
-        if let (Some(_), Some(_)) = (tessellation_control_stage, tessellation_evaluation_stage) {
+        if let (Some(tessellation_control_stage), Some(tessellation_evaluation_stage)) =
+            (tessellation_control_stage, tessellation_evaluation_stage)
+        {
             // FIXME: must check that the control shader and evaluation shader are compatible
 
             // TODO:
             // VUID-VkGraphicsPipelineCreateInfo-pStages-00732
             // VUID-VkGraphicsPipelineCreateInfo-pStages-00733
-            // VUID-VkGraphicsPipelineCreateInfo-pStages-00734
-            // VUID-VkGraphicsPipelineCreateInfo-pStages-00735
+
+            match (
+                tessellation_control_stage
+                    .entry_point
+                    .info()
+                    .output_vertices,
+                tessellation_evaluation_stage
+                    .entry_point
+                    .info()
+                    .output_vertices,
+            ) {
+                (None, None) => {
+                    return Err(Box::new(ValidationError {
+                        problem: "neither the tessellation control shader nor the tessellation \
+                            evaluation shader specifies an output patch size"
+                            .into(),
+                        vuids: &["VUID-VkGraphicsPipelineCreateInfo-pStages-00734"],
+                        ..Default::default()
+                    }));
+                }
+                (Some(control_output_vertices), Some(evaluation_output_vertices))
+                    if control_output_vertices != evaluation_output_vertices =>
+                {
+                    return Err(Box::new(ValidationError {
+                        problem: "the tessellation control shader and the tessellation \
+                            evaluation shader specify a different output patch size"
+                            .into(),
+                        vuids: &["VUID-VkGraphicsPipelineCreateInfo-pStages-00735"],
+                        ..Default::default()
+                    }));
+                }
+                _ => (),
+            }
         }
 
         if let (Some(_), Some(_)) = (tessellation_evaluation_stage, geometry_stage) {
@@ -65,11 +65,8 @@ unsafe impl VertexDefinition for &[VertexBufferDescription] {
                     })
                 })?;
 
-            // TODO: ShaderInterfaceEntryType does not properly support 64bit.
-            //       Once it does the below logic around num_elements and num_locations
-            //       might have to be updated.
             if infos.num_components() != element.ty.num_components
-                || infos.num_elements != element.ty.num_locations()
+                || infos.num_elements != element.ty.num_elements
             {
                 return Err(Box::new(ValidationError {
                     problem: format!(
@@ -87,11 +84,8 @@ unsafe impl VertexDefinition for &[VertexBufferDescription] {
             let block_size = infos.format.block_size();
             // Double precision formats can exceed a single location.
             // R64B64G64A64_SFLOAT requires two locations, so we need to adapt how we bind
-            let location_range = if block_size > 16 {
-                (element.location..element.location + 2 * element.ty.num_locations()).step_by(2)
-            } else {
-                (element.location..element.location + element.ty.num_locations()).step_by(1)
-            };
+            let location_range = (element.location..element.location + element.ty.num_locations())
+                .step_by(if block_size > 16 { 2 } else { 1 });
 
             for location in location_range {
                 attributes.push((
@@ -0,0 +1,244 @@
+// Copyright (c) 2023 The Vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Compiling GLSL source code at runtime, using the `shaderc` feature.
+
+use super::{CreateShaderModuleError, ShaderModule, ShaderModuleCreateInfo, ShaderStage};
+use crate::{device::Device, Validated};
+use shaderc::{Compiler, ShaderKind};
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
+
+// The file name that is passed to the compiler, and that appears in its diagnostics.
+const SOURCE_NAME: &str = "shader.glsl";
+
+impl ShaderModule {
+    /// Compiles GLSL source code to SPIR-V, and creates a new shader module from it.
+    ///
+    /// This is intended for prototyping and examples. The `vulkano-shaders` crate compiles
+    /// shaders at build time instead, and also generates Rust types for the shader's interface.
+    pub fn from_glsl(
+        device: Arc<Device>,
+        source: &str,
+        stage: ShaderStage,
+        entry_point: &str,
+    ) -> Result<Arc<ShaderModule>, Validated<FromGlslError>> {
+        let words = compile_glsl(source, stage, entry_point).map_err(Validated::Error)?;
+
+        // SAFETY: The code was produced by the compiler.
+        unsafe { ShaderModule::new(device, ShaderModuleCreateInfo::new(&words)) }
+            .map_err(|err| err.map(FromGlslError::CreateShaderModule))
+    }
+}
+
+/// Compiles GLSL source code to SPIR-V words.
+fn compile_glsl(
+    source: &str,
+    stage: ShaderStage,
+    entry_point: &str,
+) -> Result<Vec<u32>, FromGlslError> {
+    let shader_kind = match stage {
+        ShaderStage::Vertex => ShaderKind::Vertex,
+        ShaderStage::TessellationControl => ShaderKind::TessControl,
+        ShaderStage::TessellationEvaluation => ShaderKind::TessEvaluation,
+        ShaderStage::Geometry => ShaderKind::Geometry,
+        ShaderStage::Fragment => ShaderKind::Fragment,
+        ShaderStage::Compute => ShaderKind::Compute,
+        ShaderStage::Raygen => ShaderKind::RayGeneration,
+        ShaderStage::AnyHit => ShaderKind::AnyHit,
+        ShaderStage::ClosestHit => ShaderKind::ClosestHit,
+        ShaderStage::Miss => ShaderKind::Miss,
+        ShaderStage::Intersection => ShaderKind::Intersection,
+        ShaderStage::Callable => ShaderKind::Callable,
+        ShaderStage::Task => ShaderKind::Task,
+        ShaderStage::Mesh => ShaderKind::Mesh,
+        _ => return Err(FromGlslError::UnsupportedStage(stage)),
+    };
+
+    let compiler = Compiler::new()
+        .ok_or_else(|| FromGlslError::Compiler("failed to create the GLSL compiler".to_owned()))?;
+    let artifact = compiler
+        .compile_into_spirv(source, shader_kind, SOURCE_NAME, entry_point, None)
+        .map_err(|err| match err {
+            shaderc::Error::CompilationError(_, messages) => FromGlslError::Compilation {
+                diagnostics: parse_diagnostics(&messages),
+            },
+            err => FromGlslError::Compiler(err.to_string()),
+        })?;
+
+    Ok(artifact.as_binary().to_vec())
+}
+
+// Each line of the compiler output has the form `shader.glsl:LINE: error: MESSAGE`. Lines that
+// don't refer to the source, such as the error count, are kept without a line number.
+fn parse_diagnostics(messages: &str) -> Vec<GlslDiagnostic> {
+    messages
+        .lines()
+        .map(str::trim)
+        .filter(|message| !message.is_empty())
+        .map(|message| {
+            message
+                .strip_prefix(SOURCE_NAME)
+                .and_then(|rest| rest.strip_prefix(':'))
+                .and_then(|rest| rest.split_once(':'))
+                .and_then(|(line, rest)| {
+                    Some(GlslDiagnostic {
+                        line: Some(line.parse().ok()?),
+                        message: rest.trim().to_owned(),
+                    })
+                })
+                .unwrap_or_else(|| GlslDiagnostic {
+                    line: None,
+                    message: message.to_owned(),
+                })
+        })
+        .collect()
+}
+
+/// Error that can happen when creating a shader module with [`ShaderModule::from_glsl`].
+#[derive(Clone, Debug)]
+pub enum FromGlslError {
+    /// The source code failed to compile.
+    Compilation { diagnostics: Vec<GlslDiagnostic> },
+
+    /// The compiler could not be created, or failed for a reason other than an error in the
+    /// source code.
+    Compiler(String),
+
+    /// The shader stage can't be compiled from GLSL.
+    UnsupportedStage(ShaderStage),
+
+    /// Creating the shader module from the compiled code failed.
+    CreateShaderModule(CreateShaderModuleError),
+}
+
+impl Display for FromGlslError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::Compilation { diagnostics } => {
+                write!(f, "the GLSL source code failed to compile")?;
+
+                for diagnostic in diagnostics {
+                    write!(f, "\n{}", diagnostic)?;
+                }
+
+                Ok(())
+            }
+            Self::Compiler(message) => write!(f, "the GLSL compiler failed: {}", message),
+            Self::UnsupportedStage(stage) => write!(
+                f,
+                "the `ShaderStage::{:?}` stage can't be compiled from GLSL",
+                stage,
+            ),
+            Self::CreateShaderModule(_) => write!(f, "creating the shader module failed"),
+        }
+    }
+}
+
+impl Error for FromGlslError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::CreateShaderModule(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// A message reported by the GLSL compiler.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GlslDiagnostic {
+    /// The line in the source code that the message refers to, if any.
+    pub line: Option<u32>,
+
+    /// The message, including its severity.
+    pub message: String,
+}
+
+impl Display for GlslDiagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compile_glsl, parse_diagnostics, FromGlslError, GlslDiagnostic};
+    use crate::shader::{reflect, spirv::Spirv, ShaderStage};
+
+    #[test]
+    fn compile_fragment_shader() {
+        let words = compile_glsl(
+            r#"
+                #version 450
+
+                layout(location = 0) out vec4 f_color;
+
+                void main() {
+                    f_color = vec4(1.0, 0.0, 0.0, 1.0);
+                }
+            "#,
+            ShaderStage::Fragment,
+            "main",
+        )
+        .unwrap();
+        let spirv = Spirv::new(&words).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert_eq!(info.name, "main");
+        assert_eq!(
+            ShaderStage::from(info.execution_model),
+            ShaderStage::Fragment
+        );
+        assert_eq!(info.output_interface.elements().len(), 1);
+    }
+
+    #[test]
+    fn compile_error_line() {
+        let err = compile_glsl(
+            "#version 450\n\nvoid main() {\n    undeclared = 1.0;\n}\n",
+            ShaderStage::Fragment,
+            "main",
+        )
+        .unwrap_err();
+
+        match err {
+            FromGlslError::Compilation { diagnostics } => {
+                assert!(diagnostics
+                    .iter()
+                    .any(|diagnostic| diagnostic.line == Some(4)));
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn diagnostics() {
+        assert_eq!(
+            parse_diagnostics(
+                "shader.glsl:4: error: 'undeclared' : undeclared identifier\n1 error generated.\n"
+            ),
+            [
+                GlslDiagnostic {
+                    line: Some(4),
+                    message: "error: 'undeclared' : undeclared identifier".to_owned(),
+                },
+                GlslDiagnostic {
+                    line: None,
+                    message: "1 error generated.".to_owned(),
+                },
+            ],
+        );
+    }
+}
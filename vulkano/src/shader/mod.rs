@@ -133,14 +133,23 @@
 
 use self::spirv::{Id, Instruction};
 use crate::{
-    descriptor_set::layout::DescriptorType,
-    device::{Device, DeviceOwned},
+    descriptor_set::layout::{DescriptorSetLayoutBinding, DescriptorType},
+    device::{
+        physical::{PhysicalDevice, SubgroupFeatures},
+        Device, DeviceExtensions, DeviceOwned, Features, Properties,
+    },
     format::{Format, NumericType},
     image::view::ImageViewType,
     instance::InstanceOwnedDebugWrapper,
     macros::{impl_id_counter, vulkan_bitflags_enum},
-    pipeline::layout::PushConstantRange,
-    shader::spirv::{Capability, Spirv},
+    pipeline::{
+        graphics::vertex_input::{
+            VertexInputAttributeDescription, VertexInputBindingDescription, VertexInputRate,
+            VertexInputState,
+        },
+        layout::PushConstantRange,
+    },
+    shader::spirv::{Capability, CooperativeMatrixUse, Scope, SourceLanguage, Spirv, SpirvError},
     sync::PipelineStages,
     Requires, RequiresAllOf, RequiresOneOf, Validated, ValidationError, Version, VulkanError,
     VulkanObject,
@@ -148,17 +157,29 @@ use crate::{
 use ahash::{HashMap, HashSet};
 use bytemuck::bytes_of;
 use half::f16;
+#[cfg(feature = "serde")]
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use smallvec::SmallVec;
 use spirv::ExecutionModel;
 use std::{
     borrow::Cow,
+    cmp::max,
     collections::hash_map::Entry,
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    hash::{Hash, Hasher},
     mem::{discriminant, size_of_val, MaybeUninit},
     num::NonZeroU64,
     ptr,
+    str::FromStr,
     sync::Arc,
 };
 
+#[cfg(feature = "shaderc")]
+pub use self::glsl::{FromGlslError, GlslDiagnostic};
+
+#[cfg(feature = "shaderc")]
+mod glsl;
 pub mod reflect;
 pub mod spirv;
 
@@ -166,6 +187,10 @@ pub mod spirv;
 include!(concat!(env!("OUT_DIR"), "/spirv_reqs.rs"));
 
 /// Contains SPIR-V code with one or more entry points.
+///
+/// A shader module is only needed while creating pipelines from it. Pipelines don't keep their
+/// shader modules alive, so a shader module can be dropped as soon as the pipelines that use it
+/// have been created, even while those pipelines are still in use by the device.
 #[derive(Debug)]
 pub struct ShaderModule {
     handle: ash::vk::ShaderModule,
@@ -174,11 +199,14 @@ pub struct ShaderModule {
 
     spirv: Spirv,
     specialization_constants: HashMap<u32, SpecializationConstant>,
+    content_hash: u64,
 }
 
 impl ShaderModule {
     /// Creates a new shader module.
     ///
+    /// If `create_info.code` can't be parsed, [`CreateShaderModuleError::SpirvError`] is returned.
+    ///
     /// # Safety
     ///
     /// - The SPIR-V code in `create_info.code` must be valid.
@@ -186,18 +214,41 @@ impl ShaderModule {
     pub unsafe fn new(
         device: Arc<Device>,
         create_info: ShaderModuleCreateInfo<'_>,
-    ) -> Result<Arc<ShaderModule>, Validated<VulkanError>> {
-        let spirv = Spirv::new(create_info.code).map_err(|err| {
-            Box::new(ValidationError {
-                context: "create_info.code".into(),
-                problem: format!("error while parsing: {}", err).into(),
-                ..Default::default()
-            })
-        })?;
+    ) -> Result<Arc<ShaderModule>, Validated<CreateShaderModuleError>> {
+        let spirv = Spirv::new(create_info.code).map_err(CreateShaderModuleError::SpirvError)?;
 
         Self::validate_new(&device, &create_info, &spirv)?;
 
-        Ok(Self::new_with_spirv_unchecked(device, create_info, spirv)?)
+        Ok(Self::new_with_spirv_unchecked(device, create_info, spirv)
+            .map_err(CreateShaderModuleError::VulkanError)?)
+    }
+
+    /// Creates a new shader module from SPIR-V code that was validated with
+    /// [`ValidatedSpirv::new`].
+    ///
+    /// Unlike [`new`](Self::new), this does not validate the code again. It only checks that
+    /// `device` has the capabilities that the code was validated against, so it can be used to
+    /// cheaply recreate shader modules, for example after the device is lost.
+    ///
+    /// # Safety
+    ///
+    /// - The SPIR-V code in `validated_spirv` must be valid.
+    #[inline]
+    pub unsafe fn new_validated(
+        device: Arc<Device>,
+        validated_spirv: ValidatedSpirv,
+    ) -> Result<Arc<ShaderModule>, Validated<VulkanError>> {
+        validated_spirv
+            .validate_device(&device)
+            .map_err(|err| err.add_context("validated_spirv"))?;
+
+        let ValidatedSpirv { code, spirv, .. } = validated_spirv;
+
+        Ok(Self::new_with_spirv_unchecked(
+            device,
+            ShaderModuleCreateInfo::new(&code),
+            spirv,
+        )?)
     }
 
     fn validate_new(
@@ -278,7 +329,7 @@ impl ShaderModule {
         create_info: ShaderModuleCreateInfo<'_>,
         spirv: Spirv,
     ) -> Arc<ShaderModule> {
-        let ShaderModuleCreateInfo { code: _, _ne: _ } = create_info;
+        let ShaderModuleCreateInfo { code, _ne: _ } = create_info;
         let specialization_constants = reflect::specialization_constants(&spirv);
 
         Arc::new(ShaderModule {
@@ -288,6 +339,7 @@ impl ShaderModule {
 
             spirv,
             specialization_constants,
+            content_hash: spirv_content_hash(code),
         })
     }
 
@@ -302,7 +354,7 @@ impl ShaderModule {
     pub unsafe fn from_words(
         device: Arc<Device>,
         words: &[u32],
-    ) -> Result<Arc<ShaderModule>, Validated<VulkanError>> {
+    ) -> Result<Arc<ShaderModule>, Validated<CreateShaderModuleError>> {
         Self::new(device, ShaderModuleCreateInfo::new(words))
     }
 
@@ -320,7 +372,7 @@ impl ShaderModule {
     pub unsafe fn from_bytes(
         device: Arc<Device>,
         bytes: &[u8],
-    ) -> Result<Arc<ShaderModule>, Validated<VulkanError>> {
+    ) -> Result<Arc<ShaderModule>, Validated<CreateShaderModuleError>> {
         let words = spirv::bytes_to_words(bytes).unwrap();
         Self::new(device, ShaderModuleCreateInfo::new(&words))
     }
@@ -335,6 +387,34 @@ impl ShaderModule {
         &self.specialization_constants
     }
 
+    /// Returns the number of entry points that are declared in the module.
+    ///
+    /// This only counts the `EntryPoint` instructions, so it is cheaper than reflecting the
+    /// module to get the entry points themselves.
+    #[inline]
+    pub fn num_entry_points(&self) -> usize {
+        self.spirv.iter_entry_point().len()
+    }
+
+    /// Returns a hash of the SPIR-V code that the module was created from.
+    ///
+    /// Unlike the identity of the module, the hash is the same for all modules that are created
+    /// from the same code, so it can be used as a key to deduplicate modules in a cache. The hash
+    /// is computed with 64-bit FNV-1a over the bytes of the code, and is stable between runs and
+    /// platforms.
+    #[inline]
+    pub fn content_hash(&self) -> u64 {
+        self.content_hash
+    }
+
+    /// Returns whether `self` and `other` were created from the same SPIR-V code.
+    ///
+    /// The modules may belong to different devices.
+    #[inline]
+    pub fn content_eq(&self, other: &ShaderModule) -> bool {
+        self.content_hash == other.content_hash && self.spirv.to_words() == other.spirv.to_words()
+    }
+
     /// Applies the specialization constants to the shader module,
     /// and returns a specialized version of the module.
     ///
@@ -352,6 +432,31 @@ impl ShaderModule {
         SpecializedShaderModule::new(self.clone(), specialization_info)
     }
 
+    /// Equivalent to calling [`specialize`] with a map built from `constants`.
+    ///
+    /// If the same `constant_id` appears more than once, the last value is used.
+    ///
+    /// [`specialize`]: Self::specialize
+    #[inline]
+    pub fn specialize_from(
+        self: &Arc<Self>,
+        constants: &[(u32, SpecializationConstant)],
+    ) -> Result<Arc<SpecializedShaderModule>, Box<ValidationError>> {
+        self.specialize(constants.iter().copied().collect())
+    }
+
+    /// Equivalent to calling [`specialize`] with a value for a single specialization constant.
+    ///
+    /// [`specialize`]: Self::specialize
+    #[inline]
+    pub fn specialize_one(
+        self: &Arc<Self>,
+        constant_id: u32,
+        value: impl Into<SpecializationConstant>,
+    ) -> Result<Arc<SpecializedShaderModule>, Box<ValidationError>> {
+        self.specialize_from(&[(constant_id, value.into())])
+    }
+
     #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
     #[inline]
     pub unsafe fn specialize_unchecked(
@@ -445,6 +550,53 @@ unsafe impl DeviceOwned for ShaderModule {
 
 impl_id_counter!(ShaderModule);
 
+/// Error that can happen when creating a shader module with [`ShaderModule::new`].
+#[derive(Clone, Debug)]
+pub enum CreateShaderModuleError {
+    /// The SPIR-V code could not be parsed. [`SpirvError::kind`] can be used to handle the
+    /// failure programmatically.
+    SpirvError(SpirvError),
+
+    /// Creating the shader module failed.
+    VulkanError(VulkanError),
+}
+
+impl Error for CreateShaderModuleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::SpirvError(err) => Some(err),
+            Self::VulkanError(err) => Some(err),
+        }
+    }
+}
+
+impl Display for CreateShaderModuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::SpirvError(_) => write!(f, "parsing the SPIR-V code failed"),
+            Self::VulkanError(_) => write!(f, "creating the shader module failed"),
+        }
+    }
+}
+
+impl From<CreateShaderModuleError> for Validated<CreateShaderModuleError> {
+    fn from(err: CreateShaderModuleError) -> Self {
+        Self::Error(err)
+    }
+}
+
+/// Hashes SPIR-V code with 64-bit FNV-1a, taking the bytes of each word in little-endian order.
+fn spirv_content_hash(code: &[u32]) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    code.iter()
+        .flat_map(|word| word.to_le_bytes())
+        .fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        })
+}
+
 pub struct ShaderModuleCreateInfo<'a> {
     /// The SPIR-V code, in the form of 32-bit words.
     ///
@@ -468,8 +620,42 @@ impl<'a> ShaderModuleCreateInfo<'a> {
         &self,
         device: &Device,
         spirv: &Spirv,
+    ) -> Result<(), Box<ValidationError>> {
+        self.validate_spirv(&DeviceCapabilities::from_device(device), spirv)
+    }
+
+    /// Validates the create info against the provided device capabilities, without needing a
+    /// `Device`.
+    ///
+    /// This performs the same checks on the SPIR-V version, capabilities and extensions of
+    /// `code` as [`ShaderModule::new`], which can be used to check whether a shader module could
+    /// be created on a hypothetical device.
+    pub fn validate_with_capabilities(
+        &self,
+        capabilities: &DeviceCapabilities<'_>,
+    ) -> Result<(), Box<ValidationError>> {
+        let spirv = Spirv::new(self.code).map_err(|err| {
+            Box::new(ValidationError {
+                context: "code".into(),
+                problem: format!("error while parsing: {}", err).into(),
+                ..Default::default()
+            })
+        })?;
+
+        self.validate_spirv(capabilities, &spirv)
+    }
+
+    fn validate_spirv(
+        &self,
+        capabilities: &DeviceCapabilities<'_>,
+        spirv: &Spirv,
     ) -> Result<(), Box<ValidationError>> {
         let &Self { code, _ne: _ } = self;
+        let &DeviceCapabilities {
+            api_version,
+            enabled_extensions,
+            ..
+        } = capabilities;
 
         if code.is_empty() {
             return Err(Box::new(ValidationError {
@@ -488,27 +674,22 @@ impl<'a> ShaderModuleCreateInfo<'a> {
         {
             match spirv_version {
                 Version::V1_0 => None,
-                Version::V1_1 | Version::V1_2 | Version::V1_3 => {
-                    (!(device.api_version() >= Version::V1_1)).then_some(RequiresOneOf(&[
-                        RequiresAllOf(&[Requires::APIVersion(Version::V1_1)]),
-                    ]))
-                }
-                Version::V1_4 => (!(device.api_version() >= Version::V1_2
-                    || device.enabled_extensions().khr_spirv_1_4))
+                Version::V1_1 | Version::V1_2 | Version::V1_3 => (!(api_version >= Version::V1_1))
+                    .then_some(RequiresOneOf(&[RequiresAllOf(&[Requires::APIVersion(
+                        Version::V1_1,
+                    )])])),
+                Version::V1_4 => (!(api_version >= Version::V1_2
+                    || enabled_extensions.khr_spirv_1_4))
                     .then_some(RequiresOneOf(&[
                         RequiresAllOf(&[Requires::APIVersion(Version::V1_2)]),
                         RequiresAllOf(&[Requires::DeviceExtension("khr_spirv_1_4")]),
                     ])),
-                Version::V1_5 => {
-                    (!(device.api_version() >= Version::V1_2)).then_some(RequiresOneOf(&[
-                        RequiresAllOf(&[Requires::APIVersion(Version::V1_2)]),
-                    ]))
-                }
-                Version::V1_6 => {
-                    (!(device.api_version() >= Version::V1_3)).then_some(RequiresOneOf(&[
-                        RequiresAllOf(&[Requires::APIVersion(Version::V1_3)]),
-                    ]))
-                }
+                Version::V1_5 => (!(api_version >= Version::V1_2)).then_some(RequiresOneOf(&[
+                    RequiresAllOf(&[Requires::APIVersion(Version::V1_2)]),
+                ])),
+                Version::V1_6 => (!(api_version >= Version::V1_3)).then_some(RequiresOneOf(&[
+                    RequiresAllOf(&[Requires::APIVersion(Version::V1_3)]),
+                ])),
                 _ => {
                     return Err(Box::new(ValidationError {
                         context: "code".into(),
@@ -536,6 +717,10 @@ impl<'a> ShaderModuleCreateInfo<'a> {
             }))
         })?;
 
+        spirv
+            .validate_version_against_capabilities()
+            .map_err(|err| err.add_context("code"))?;
+
         for &capability in spirv
             .iter_capability()
             .filter_map(|instruction| match instruction {
@@ -543,7 +728,8 @@ impl<'a> ShaderModuleCreateInfo<'a> {
                 _ => None,
             })
         {
-            validate_spirv_capability(device, capability).map_err(|err| err.add_context("code"))?;
+            validate_spirv_capability(capabilities, capability)
+                .map_err(|err| err.add_context("code"))?;
         }
 
         for extension in spirv
@@ -553,7 +739,8 @@ impl<'a> ShaderModuleCreateInfo<'a> {
                 _ => None,
             })
         {
-            validate_spirv_extension(device, extension).map_err(|err| err.add_context("code"))?;
+            validate_spirv_extension(capabilities, extension)
+                .map_err(|err| err.add_context("code"))?;
         }
 
         // VUID-VkShaderModuleCreateInfo-pCode-08736
@@ -565,8 +752,142 @@ impl<'a> ShaderModuleCreateInfo<'a> {
     }
 }
 
+/// The capabilities of a device that a shader module is validated against.
+///
+/// This can be obtained from an existing device with [`from_device`], or constructed manually to
+/// validate shader modules against a hypothetical device with
+/// [`ShaderModuleCreateInfo::validate_with_capabilities`].
+///
+/// [`from_device`]: DeviceCapabilities::from_device
+#[derive(Clone, Copy, Debug)]
+pub struct DeviceCapabilities<'a> {
+    /// The API version of the device.
+    pub api_version: Version,
+
+    /// The extensions that are enabled on the device.
+    pub enabled_extensions: &'a DeviceExtensions,
+
+    /// The features that are enabled on the device.
+    pub enabled_features: &'a Features,
+
+    /// The properties of the physical device.
+    pub properties: &'a Properties,
+}
+
+impl<'a> DeviceCapabilities<'a> {
+    /// Returns the capabilities of `device`.
+    #[inline]
+    pub fn from_device(device: &'a Device) -> Self {
+        Self {
+            api_version: device.api_version(),
+            enabled_extensions: device.enabled_extensions(),
+            enabled_features: device.enabled_features(),
+            properties: device.physical_device().properties(),
+        }
+    }
+}
+
+/// SPIR-V code that has been validated against the capabilities of a device, so that shader
+/// modules can be created from it with [`ShaderModule::new_validated`] without validating it
+/// again.
+///
+/// The code stays valid for devices that are created from the same physical device, with at
+/// least the API version, extensions and features of the device that it was validated against.
+#[derive(Clone, Debug)]
+pub struct ValidatedSpirv {
+    code: Vec<u32>,
+    spirv: Spirv,
+    physical_device: Arc<PhysicalDevice>,
+    api_version: Version,
+    enabled_extensions: DeviceExtensions,
+    enabled_features: Features,
+}
+
+impl ValidatedSpirv {
+    /// Parses and validates `create_info.code` for `device`, performing the same checks as
+    /// [`ShaderModule::new`].
+    pub fn new(
+        device: &Device,
+        create_info: ShaderModuleCreateInfo<'_>,
+    ) -> Result<Self, Box<ValidationError>> {
+        let spirv = Spirv::new(create_info.code).map_err(|err| {
+            Box::new(ValidationError {
+                context: "create_info.code".into(),
+                problem: format!("error while parsing: {}", err).into(),
+                ..Default::default()
+            })
+        })?;
+
+        ShaderModule::validate_new(device, &create_info, &spirv)?;
+
+        Ok(ValidatedSpirv {
+            code: create_info.code.to_vec(),
+            spirv,
+            physical_device: device.physical_device().clone(),
+            api_version: device.api_version(),
+            enabled_extensions: *device.enabled_extensions(),
+            enabled_features: *device.enabled_features(),
+        })
+    }
+
+    /// Returns the SPIR-V code, in the form of 32-bit words.
+    #[inline]
+    pub fn code(&self) -> &[u32] {
+        &self.code
+    }
+
+    /// Returns the parsed SPIR-V code.
+    #[inline]
+    pub fn spirv(&self) -> &Spirv {
+        &self.spirv
+    }
+
+    fn validate_device(&self, device: &Device) -> Result<(), Box<ValidationError>> {
+        if device.physical_device() != &self.physical_device {
+            return Err(Box::new(ValidationError {
+                problem: "was validated for a different physical device than `device`".into(),
+                ..Default::default()
+            }));
+        }
+
+        if device.api_version() < self.api_version {
+            return Err(Box::new(ValidationError {
+                problem: "was validated for a device with a higher API version than `device`"
+                    .into(),
+                ..Default::default()
+            }));
+        }
+
+        if !device
+            .enabled_extensions()
+            .contains(&self.enabled_extensions)
+        {
+            return Err(Box::new(ValidationError {
+                problem: "was validated for a device with extensions that are not enabled on \
+                    `device`"
+                    .into(),
+                ..Default::default()
+            }));
+        }
+
+        if !device.enabled_features().contains(&self.enabled_features) {
+            return Err(Box::new(ValidationError {
+                problem: "was validated for a device with features that are not enabled on \
+                    `device`"
+                    .into(),
+                ..Default::default()
+            }));
+        }
+
+        Ok(())
+    }
+}
+
 /// The value to provide for a specialization constant, when creating a pipeline.
+///
+/// With the `serde` feature, `F16` values are (de)serialized as 32-bit floats.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SpecializationConstant {
     Bool(bool),
     U8(u8),
@@ -577,13 +898,37 @@ pub enum SpecializationConstant {
     I16(i16),
     I32(i32),
     I64(i64),
-    F16(f16),
+    F16(#[cfg_attr(feature = "serde", serde(with = "serde_f16"))] f16),
     F32(f32),
     F64(f64),
 }
 
+#[cfg(feature = "serde")]
+mod serde_f16 {
+    use half::f16;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S>(value: &f16, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        value.to_f32().serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D>(deserializer: D) -> Result<f16, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        f32::deserialize(deserializer).map(f16::from_f32)
+    }
+}
+
 impl SpecializationConstant {
     /// Returns the value as a byte slice. Booleans are expanded to a `VkBool32` value.
+    ///
+    /// A composite specialization constant, such as an array of booleans, doesn't have a value of
+    /// its own. Each of its elements is a separate specialization constant with its own
+    /// `constant_id`, so a `bool[2]` takes up 8 bytes in total.
     #[inline]
     pub fn as_bytes(&self) -> &[u8] {
         match self {
@@ -608,6 +953,67 @@ impl SpecializationConstant {
     pub fn eq_type(&self, other: &Self) -> bool {
         discriminant(self) == discriminant(other)
     }
+
+    /// Returns the type of the value, and the bits of the value zero-extended to a `u64`.
+    /// Booleans are converted to 0 or 1.
+    ///
+    /// The value can be turned back into a `SpecializationConstant` with
+    /// [`from_u64_bits`](Self::from_u64_bits).
+    #[inline]
+    pub fn as_u64_bits(&self) -> (SpecializationConstantType, u64) {
+        match *self {
+            Self::Bool(value) => (SpecializationConstantType::Bool, value as u64),
+            Self::U8(value) => (SpecializationConstantType::U8, value as u64),
+            Self::U16(value) => (SpecializationConstantType::U16, value as u64),
+            Self::U32(value) => (SpecializationConstantType::U32, value as u64),
+            Self::U64(value) => (SpecializationConstantType::U64, value),
+            Self::I8(value) => (SpecializationConstantType::I8, value as u8 as u64),
+            Self::I16(value) => (SpecializationConstantType::I16, value as u16 as u64),
+            Self::I32(value) => (SpecializationConstantType::I32, value as u32 as u64),
+            Self::I64(value) => (SpecializationConstantType::I64, value as u64),
+            Self::F16(value) => (SpecializationConstantType::F16, value.to_bits() as u64),
+            Self::F32(value) => (SpecializationConstantType::F32, value.to_bits() as u64),
+            Self::F64(value) => (SpecializationConstantType::F64, value.to_bits()),
+        }
+    }
+
+    /// Creates a value of type `ty` from bits that were returned by
+    /// [`as_u64_bits`](Self::as_u64_bits). Bits beyond the size of `ty` are ignored, and a
+    /// boolean is `true` if any bit is set.
+    #[inline]
+    pub fn from_u64_bits(ty: SpecializationConstantType, bits: u64) -> Self {
+        match ty {
+            SpecializationConstantType::Bool => Self::Bool(bits != 0),
+            SpecializationConstantType::U8 => Self::U8(bits as u8),
+            SpecializationConstantType::U16 => Self::U16(bits as u16),
+            SpecializationConstantType::U32 => Self::U32(bits as u32),
+            SpecializationConstantType::U64 => Self::U64(bits),
+            SpecializationConstantType::I8 => Self::I8(bits as u8 as i8),
+            SpecializationConstantType::I16 => Self::I16(bits as u16 as i16),
+            SpecializationConstantType::I32 => Self::I32(bits as u32 as i32),
+            SpecializationConstantType::I64 => Self::I64(bits as i64),
+            SpecializationConstantType::F16 => Self::F16(f16::from_bits(bits as u16)),
+            SpecializationConstantType::F32 => Self::F32(f32::from_bits(bits as u32)),
+            SpecializationConstantType::F64 => Self::F64(f64::from_bits(bits)),
+        }
+    }
+}
+
+/// The type of a [`SpecializationConstant`], without its value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpecializationConstantType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    F16,
+    F32,
+    F64,
 }
 
 impl From<bool> for SpecializationConstant {
@@ -694,6 +1100,195 @@ impl From<f64> for SpecializationConstant {
     }
 }
 
+/// A helper for building the specialization info that is provided to
+/// [`ShaderModule::specialize`].
+///
+/// If the builder is created with [`for_module`](Self::for_module), every value is checked
+/// against the type of the specialization constant declared in the module as soon as it is set.
+#[derive(Clone, Debug, Default)]
+pub struct SpecializationInfoBuilder<'a> {
+    module: Option<&'a ShaderModule>,
+    specialization_info: HashMap<u32, SpecializationConstant>,
+}
+
+impl<'a> SpecializationInfoBuilder<'a> {
+    /// Returns a new builder that does not check the types of the provided values.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a new builder that checks the provided values against the specialization
+    /// constants of `module`.
+    #[inline]
+    pub fn for_module(module: &'a ShaderModule) -> Self {
+        Self {
+            module: Some(module),
+            specialization_info: HashMap::default(),
+        }
+    }
+
+    /// Sets the value of the specialization constant with the given `constant_id`.
+    ///
+    /// If the builder was created for a module, and the module declares a specialization
+    /// constant with this `constant_id`, then `value` must have the same type.
+    pub fn set(
+        &mut self,
+        constant_id: u32,
+        value: impl Into<SpecializationConstant>,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        let value = value.into();
+
+        if let Some(default_value) = self
+            .module
+            .and_then(|module| module.specialization_constants.get(&constant_id))
+        {
+            if !value.eq_type(default_value) {
+                return Err(Box::new(ValidationError {
+                    problem: format!(
+                        "the value provided for specialization constant {0} does not have the \
+                        same type as `module.specialization_constants()[{0}]`",
+                        constant_id
+                    )
+                    .into(),
+                    vuids: &["VUID-VkSpecializationMapEntry-constantID-00776"],
+                    ..Default::default()
+                }));
+            }
+        }
+
+        self.specialization_info.insert(constant_id, value);
+
+        Ok(self)
+    }
+
+    /// Sets the specialization constant `constant_id` to a `bool` value.
+    #[inline]
+    pub fn set_bool(
+        &mut self,
+        constant_id: u32,
+        value: bool,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.set(constant_id, value)
+    }
+
+    /// Sets the specialization constant `constant_id` to a `u8` value.
+    #[inline]
+    pub fn set_u8(
+        &mut self,
+        constant_id: u32,
+        value: u8,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.set(constant_id, value)
+    }
+
+    /// Sets the specialization constant `constant_id` to a `u16` value.
+    #[inline]
+    pub fn set_u16(
+        &mut self,
+        constant_id: u32,
+        value: u16,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.set(constant_id, value)
+    }
+
+    /// Sets the specialization constant `constant_id` to a `u32` value.
+    #[inline]
+    pub fn set_u32(
+        &mut self,
+        constant_id: u32,
+        value: u32,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.set(constant_id, value)
+    }
+
+    /// Sets the specialization constant `constant_id` to a `u64` value.
+    #[inline]
+    pub fn set_u64(
+        &mut self,
+        constant_id: u32,
+        value: u64,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.set(constant_id, value)
+    }
+
+    /// Sets the specialization constant `constant_id` to an `i8` value.
+    #[inline]
+    pub fn set_i8(
+        &mut self,
+        constant_id: u32,
+        value: i8,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.set(constant_id, value)
+    }
+
+    /// Sets the specialization constant `constant_id` to an `i16` value.
+    #[inline]
+    pub fn set_i16(
+        &mut self,
+        constant_id: u32,
+        value: i16,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.set(constant_id, value)
+    }
+
+    /// Sets the specialization constant `constant_id` to an `i32` value.
+    #[inline]
+    pub fn set_i32(
+        &mut self,
+        constant_id: u32,
+        value: i32,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.set(constant_id, value)
+    }
+
+    /// Sets the specialization constant `constant_id` to an `i64` value.
+    #[inline]
+    pub fn set_i64(
+        &mut self,
+        constant_id: u32,
+        value: i64,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.set(constant_id, value)
+    }
+
+    /// Sets the specialization constant `constant_id` to an `f16` value.
+    #[inline]
+    pub fn set_f16(
+        &mut self,
+        constant_id: u32,
+        value: f16,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.set(constant_id, value)
+    }
+
+    /// Sets the specialization constant `constant_id` to an `f32` value.
+    #[inline]
+    pub fn set_f32(
+        &mut self,
+        constant_id: u32,
+        value: f32,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.set(constant_id, value)
+    }
+
+    /// Sets the specialization constant `constant_id` to an `f64` value.
+    #[inline]
+    pub fn set_f64(
+        &mut self,
+        constant_id: u32,
+        value: f64,
+    ) -> Result<&mut Self, Box<ValidationError>> {
+        self.set(constant_id, value)
+    }
+
+    /// Returns the specialization info that was built.
+    #[inline]
+    pub fn build(self) -> HashMap<u32, SpecializationConstant> {
+        self.specialization_info
+    }
+}
+
 /// A shader module with specialization constants applied.
 #[derive(Debug)]
 pub struct SpecializedShaderModule {
@@ -777,7 +1372,71 @@ impl SpecializedShaderModule {
         &self.specialization_info
     }
 
-    /// Returns the SPIR-V code of this module.
+    /// Returns the SPIR-V words of the module with the specialization constants applied.
+    ///
+    /// This is intended for debugging. The words are re-encoded from the specialized module, or
+    /// from the base module if the code was not specialized, with [`Spirv::to_words`].
+    #[inline]
+    pub fn spirv_words(&self) -> Vec<u32> {
+        self.spirv().to_words()
+    }
+
+    /// Returns whether the SPIR-V code of the module was specialized.
+    ///
+    /// This is `true` if the base module defines specialization constants, even if
+    /// `specialization_info` is empty, because the constants are then replaced with their
+    /// default values. If this is `false`, the code is the same as that of the base module.
+    #[inline]
+    pub fn was_specialized(&self) -> bool {
+        self.spirv.is_some()
+    }
+
+    /// Returns whether the descriptor requirements of any entry point differ from those of the
+    /// base module with the default values of its specialization constants.
+    ///
+    /// This happens when a specialization constant determines the size of an array of
+    /// descriptors, for example. If this returns `true`, a descriptor set layout that was created
+    /// for the unspecialized module may not be compatible with this one.
+    pub fn descriptor_requirements_changed(&self) -> bool {
+        if self.specialization_info.is_empty() || self.spirv.is_none() {
+            return false;
+        }
+
+        let mut base_spirv = self.base_module.spirv.clone();
+        base_spirv.apply_specialization(&HashMap::default());
+
+        for ((_, base_info), (_, info)) in
+            reflect::entry_points(&base_spirv).zip(&self.entry_point_infos)
+        {
+            if base_info.descriptor_binding_requirements != info.descriptor_binding_requirements {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns the values of the constants that are derived from specialization constants with
+    /// `SpecConstantOp` instructions, after the specialization was applied.
+    ///
+    /// Derived constants have no `constant_id`, so the keys are the raw ids of the
+    /// `SpecConstantOp` instructions in the base module. Constants with a composite type are not
+    /// included.
+    pub fn resolved_derived_constants(&self) -> HashMap<u32, SpecializationConstant> {
+        let mut base_spirv;
+        let specialized_spirv = match self.spirv {
+            Some(ref spirv) => spirv,
+            None => {
+                base_spirv = self.base_module.spirv.clone();
+                base_spirv.apply_specialization(&HashMap::default());
+                &base_spirv
+            }
+        };
+
+        reflect::derived_constants(&self.base_module.spirv, specialized_spirv)
+    }
+
+    /// Returns the SPIR-V code of this module.
     #[inline]
     pub(crate) fn spirv(&self) -> &Spirv {
         self.spirv.as_ref().unwrap_or(&self.base_module.spirv)
@@ -860,14 +1519,232 @@ unsafe impl DeviceOwned for SpecializedShaderModule {
 }
 
 /// The information associated with a single entry point in a shader.
+///
+/// With the `serde` feature, this is not (de)serializable as a whole, because it holds types that
+/// are generated from the SPIR-V grammar, such as [`ExecutionModel`], which are not given a
+/// serialized form. The interfaces and the descriptor binding requirements can be (de)serialized
+/// individually.
 #[derive(Clone, Debug)]
 pub struct EntryPointInfo {
     pub name: String,
     pub execution_model: ExecutionModel,
     pub descriptor_binding_requirements: HashMap<(u32, u32), DescriptorBindingRequirements>,
+    /// The range of push constants that the entry point declares, or `None` if it doesn't use
+    /// push constants.
+    ///
+    /// The range is tight: it starts at the lowest offset of the push constant block's members,
+    /// and ends at the end of the last member. A single range is returned even if there are
+    /// unused gaps between the members.
     pub push_constant_requirements: Option<PushConstantRange>,
+    /// The members of the push constant block that the entry point declares, as pairs of their
+    /// byte offset and their type, sorted by offset. Members that are structs are flattened
+    /// into their own members, and members that are pointers are described as 64-bit unsigned
+    /// integers holding a device address. Members whose offset or type can't be determined,
+    /// such as arrays whose length is a specialization constant operation, are left out.
+    /// This is empty if the entry point doesn't use push constants.
+    pub push_constant_members: Vec<(u32, PushConstantMemberType)>,
+    /// The members of the `ShaderRecordBufferKHR` block that the entry point declares, in the same
+    /// form as `push_constant_members`. This can be used to lay out the shader records in a
+    /// shader binding table. This is empty if the entry point doesn't use a shader record.
+    pub shader_record_members: Vec<(u32, PushConstantMemberType)>,
     pub input_interface: ShaderInterface,
     pub output_interface: ShaderInterface,
+    /// For compute, task and mesh entry points, the size of the local workgroup.
+    ///
+    /// A constant decorated with the `WorkgroupSize` builtin takes precedence over the
+    /// `LocalSizeId` and `LocalSize` execution modes, as required by the SPIR-V specification.
+    pub local_size: Option<[u32; 3]>,
+    /// For tessellation entry points, the number of vertices in the output patch, as given by the
+    /// `OutputVertices` execution mode, or `None` if the entry point doesn't declare it.
+    ///
+    /// This is normally declared by the tessellation control shader, but the tessellation
+    /// evaluation shader may declare it as well. If both shaders of a pipeline declare it, the
+    /// values must be equal.
+    pub output_vertices: Option<u32>,
+    /// For compute, task and mesh entry points, the total size in bytes of the variables in
+    /// `Workgroup` storage (`shared` variables in GLSL) that the entry point uses. This must not
+    /// exceed the [`max_compute_shared_memory_size`] device property.
+    ///
+    /// Array lengths that are specialization constants are taken from the specialized module.
+    /// If the size of a variable is not known, for example because the length of an array is
+    /// given by a specialization constant operation, this is `None`.
+    ///
+    /// [`max_compute_shared_memory_size`]: crate::device::Properties::max_compute_shared_memory_size
+    pub workgroup_shared_size: Option<u32>,
+    /// The size of the `ClipDistance` builtin array that the entry point declares, or 0 if it
+    /// doesn't declare it.
+    ///
+    /// For fragment entry points, this is taken from the inputs, and for the other stages from the
+    /// outputs.
+    pub clip_distance_count: u32,
+    /// The size of the `CullDistance` builtin array that the entry point declares, or 0 if it
+    /// doesn't declare it.
+    ///
+    /// For fragment entry points, this is taken from the inputs, and for the other stages from the
+    /// outputs.
+    pub cull_distance_count: u32,
+    /// Whether the entry point writes the `PrimitiveShadingRateKHR` builtin or reads the
+    /// `ShadingRateKHR` builtin, which are provided by the `fragment_shading_rate` feature.
+    pub uses_fragment_shading_rate: bool,
+    /// Whether the entry point, or a function that it calls, uses an instruction from the
+    /// `NonSemantic.DebugPrintf` extended instruction set. The output of these instructions is
+    /// only visible if the debug printf feature of the validation layer is enabled.
+    pub uses_debug_printf: bool,
+    /// Whether the entry point is a fragment shader with an output that has an `index` of 1,
+    /// which requires the `dual_src_blend` feature and dual-source blend factors.
+    pub uses_dual_source_blend: bool,
+    /// Whether the entry point is a fragment shader that reads the `SampleId` or `SamplePosition`
+    /// builtin, or has an input that is decorated with `Sample` (`sample` in GLSL). This enables
+    /// sample shading, which requires the [`sample_rate_shading`] feature, and causes the fragment
+    /// shader to be invoked once per sample. Reading the `SampleMask` builtin doesn't enable it.
+    ///
+    /// [`sample_rate_shading`]: crate::device::Features::sample_rate_shading
+    pub uses_sample_rate_shading: bool,
+    /// Whether the entry point is a fragment shader that, directly or in a function that it
+    /// calls, may discard the fragment with `OpKill`, `OpTerminateInvocation` or
+    /// `OpDemoteToHelperInvocation` (`discard` in GLSL). Such a shader can't benefit from early
+    /// fragment tests unless it forces them with the `EarlyFragmentTests` execution mode.
+    pub may_discard: bool,
+    /// For fragment entry points, the origin of the fragment coordinates, as given by the
+    /// `OriginUpperLeft` or `OriginLowerLeft` execution mode. Vulkan requires `OriginUpperLeft`;
+    /// a shader that was compiled for `OriginLowerLeft`, as in OpenGL, renders upside down.
+    pub fragment_origin: Option<FragmentOrigin>,
+    /// Whether the entry point is a fragment shader with an input with the `FragCoord` builtin
+    /// (`gl_FragCoord` in GLSL), which holds the framebuffer coordinates of the fragment.
+    pub uses_frag_coord: bool,
+    /// Whether the entry point is a fragment shader with the `PixelCenterInteger` execution mode
+    /// (`pixel_center_integer` in GLSL). If so, the fragment coordinates are at the integer
+    /// corners of pixels rather than at their centers. Vulkan does not allow this execution mode.
+    pub pixel_center_integer: bool,
+    /// Whether the entry point is a fragment shader with the `SampleInterlockOrderedEXT` or
+    /// `SampleInterlockUnorderedEXT` execution mode (`sample_interlock_ordered` or
+    /// `sample_interlock_unordered` in GLSL). If so, its critical sections are interlocked per
+    /// sample, which requires the [`fragment_shader_sample_interlock`] feature.
+    ///
+    /// [`fragment_shader_sample_interlock`]: crate::device::Features::fragment_shader_sample_interlock
+    pub sample_interlock: bool,
+    /// Whether the entry point is a fragment shader with the `StencilRefReplacingEXT` execution
+    /// mode, which it declares when it writes the `FragStencilRefEXT` builtin
+    /// (`gl_FragStencilRefARB` in GLSL). The written value replaces the stencil reference value
+    /// of the stencil test, which requires the [`ext_shader_stencil_export`] extension.
+    ///
+    /// [`ext_shader_stencil_export`]: crate::device::DeviceExtensions::ext_shader_stencil_export
+    pub exports_stencil_ref: bool,
+    /// Whether the entry point has an output with the `Layer` builtin (`gl_Layer` in GLSL), which
+    /// selects the layer of the framebuffer that a primitive is rendered to. This is normally
+    /// written by a geometry or mesh shader, to render to the layers of an image array or cube
+    /// map in a single pass.
+    pub writes_layer: bool,
+    /// Whether the entry point has an output with the `Position` builtin (`gl_Position` in GLSL)
+    /// that is decorated with `Invariant`. If so, the position is computed identically by every
+    /// shader that uses the same expression, which is needed when multiple passes must produce
+    /// exactly the same depth values.
+    pub position_invariant: bool,
+    /// The cooperative matrix types, declared with `OpTypeCooperativeMatrixKHR`, that the entry
+    /// point, or a function that it calls, uses. This is empty if the entry point doesn't use
+    /// cooperative matrices.
+    pub cooperative_matrix_types: Vec<CooperativeMatrixType>,
+    /// The categories of subgroup operations that the entry point, or a function that it calls,
+    /// uses through `OpGroupNonUniform*` instructions. These must be included in the
+    /// [`subgroup_supported_operations`] device property.
+    ///
+    /// [`subgroup_supported_operations`]: crate::device::Properties::subgroup_supported_operations
+    pub subgroup_operations: SubgroupFeatures,
+    /// Whether the entry point, or a function that it calls, performs a ray query with
+    /// `OpRayQueryInitializeKHR` (`rayQueryInitializeEXT` in GLSL). This requires the
+    /// [`ray_query`] feature. The acceleration structure that is queried is reported in
+    /// `descriptor_binding_requirements`.
+    ///
+    /// [`ray_query`]: crate::device::Features::ray_query
+    pub uses_ray_query: bool,
+    /// Whether the entry point, or a function that it calls, computes a result that is decorated
+    /// with `NoContraction` (`precise` in GLSL). Such results must not be computed with contracted
+    /// operations, such as a fused multiply-add, so that they are bit-exact across shaders.
+    pub uses_no_contraction: bool,
+}
+
+impl EntryPointInfo {
+    /// Returns the descriptor binding requirements of the entry point, sorted by
+    /// `(set, binding)`.
+    ///
+    /// Unlike iterating over `descriptor_binding_requirements` directly, the order is
+    /// deterministic.
+    pub fn descriptor_bindings_sorted(&self) -> Vec<((u32, u32), &DescriptorBindingRequirements)> {
+        let mut bindings: Vec<_> = self
+            .descriptor_binding_requirements
+            .iter()
+            .map(|(&key, reqs)| (key, reqs))
+            .collect();
+        bindings.sort_unstable_by_key(|&(key, _)| key);
+
+        bindings
+    }
+
+    /// Checks that the push constant range of the entry point doesn't exceed the
+    /// [`max_push_constants_size`] limit of `device`.
+    ///
+    /// This lets the error be caught before creating a pipeline layout for the entry point.
+    ///
+    /// [`max_push_constants_size`]: crate::device::Properties::max_push_constants_size
+    #[inline]
+    pub fn validate_push_constants(&self, device: &Device) -> Result<(), Box<ValidationError>> {
+        self.validate_push_constants_with_properties(device.physical_device().properties())
+    }
+
+    /// Checks that the inputs of a fragment entry point that have an integer or 64-bit type are
+    /// decorated with `Flat`, as they can't be interpolated.
+    ///
+    /// Returns `Ok` for entry points of other execution models.
+    pub fn validate_fragment_inputs(&self) -> Result<(), Box<ValidationError>> {
+        if self.execution_model != ExecutionModel::Fragment {
+            return Ok(());
+        }
+
+        for element in self.input_interface.elements() {
+            if (matches!(element.ty.base_type, NumericType::Int | NumericType::Uint)
+                || element.ty.is_64bit)
+                && !element.flat
+            {
+                return Err(Box::new(ValidationError {
+                    context: "input_interface".into(),
+                    problem: format!(
+                        "the input at location {} has an integer or 64-bit type, but is not \
+                        decorated with `Flat`",
+                        element.location,
+                    )
+                    .into(),
+                    vuids: &["VUID-StandaloneSpirv-Flat-04744"],
+                    ..Default::default()
+                }));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_push_constants_with_properties(
+        &self,
+        properties: &Properties,
+    ) -> Result<(), Box<ValidationError>> {
+        if let Some(range) = &self.push_constant_requirements {
+            if range.offset + range.size > properties.max_push_constants_size {
+                return Err(Box::new(ValidationError {
+                    context: "push_constant_requirements".into(),
+                    problem: format!(
+                        "the push constant range ends at byte {}, which is greater than the \
+                        `max_push_constants_size` limit ({})",
+                        range.offset + range.size,
+                        properties.max_push_constants_size,
+                    )
+                    .into(),
+                    vuids: &["VUID-VkPushConstantRange-size-00298"],
+                    ..Default::default()
+                }));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Represents a shader entry point in a shader module.
@@ -897,11 +1774,261 @@ impl EntryPoint {
     pub fn info(&self) -> &EntryPointInfo {
         &self.module.entry_point_infos[self.info_index].1
     }
+
+    /// Returns the number of bytes of push constant data that the entry point uses, counted from
+    /// the start of the push constant block.
+    ///
+    /// This is the end of the push constant range of the entry point, or 0 if it does not use
+    /// push constants.
+    #[inline]
+    pub fn push_constant_size(&self) -> u32 {
+        self.info()
+            .push_constant_requirements
+            .map_or(0, |range| range.offset + range.size)
+    }
+
+    /// Returns the ids of the entry point function and of all the functions that it calls,
+    /// directly or indirectly.
+    ///
+    /// The entry point function comes first, followed by the other functions in the order in
+    /// which they are first called.
+    #[inline]
+    pub fn reachable_functions(&self) -> Vec<Id> {
+        reflect::reachable_functions(self.module.spirv(), self.id)
+    }
+
+    /// Checks whether the output interface of this entry point matches the input interface of
+    /// `next`.
+    ///
+    /// `self` is the producing stage and `next` is the consuming stage, such as a vertex and a
+    /// fragment entry point respectively. This is equivalent to calling
+    /// [`ShaderInterface::matches`] on the input interface of `next` with the output interface of
+    /// `self`. See also [`link_with`](Self::link_with), which checks more than the interfaces.
+    #[inline]
+    pub fn interface_matches(&self, next: &EntryPoint) -> Result<(), Box<ValidationError>> {
+        next.info()
+            .input_interface
+            .matches(&self.info().output_interface)
+    }
+
+    /// Checks whether `next` can follow this entry point in a pipeline, and returns the combined
+    /// requirements of the two entry points.
+    ///
+    /// The output interface of `self` must match the input interface of `next`, and the
+    /// descriptor binding requirements of both entry points must be compatible. If both entry
+    /// points are tessellation shaders that declare an output patch size, the sizes must be equal.
+    pub fn link_with(&self, next: &EntryPoint) -> Result<LinkedStageInfo, Box<ValidationError>> {
+        let info = self.info();
+        let next_info = next.info();
+
+        if let Err(err) = (next_info.input_interface).matches(&info.output_interface) {
+            return Err(Box::new(ValidationError {
+                context: "next".into(),
+                problem: format!(
+                    "the output interface of the `ShaderStage::{:?}` stage does not \
+                    match the input interface of the `ShaderStage::{:?}` stage: {}",
+                    ShaderStage::from(info.execution_model),
+                    ShaderStage::from(next_info.execution_model),
+                    err
+                )
+                .into(),
+                vuids: &[
+                    "VUID-VkGraphicsPipelineCreateInfo-pStages-00742",
+                    "VUID-VkGraphicsPipelineCreateInfo-None-04889",
+                ],
+                ..Default::default()
+            }));
+        }
+
+        if let (Some(output_vertices), Some(next_output_vertices)) =
+            (info.output_vertices, next_info.output_vertices)
+        {
+            if output_vertices != next_output_vertices {
+                return Err(Box::new(ValidationError {
+                    context: "next".into(),
+                    problem: format!(
+                        "the `ShaderStage::{:?}` stage has an output patch size of {} vertices, \
+                        but the `ShaderStage::{:?}` stage has an output patch size of {} vertices",
+                        ShaderStage::from(info.execution_model),
+                        output_vertices,
+                        ShaderStage::from(next_info.execution_model),
+                        next_output_vertices,
+                    )
+                    .into(),
+                    vuids: &["VUID-VkGraphicsPipelineCreateInfo-pStages-00735"],
+                    ..Default::default()
+                }));
+            }
+        }
+
+        let mut descriptor_binding_requirements = info.descriptor_binding_requirements.clone();
+
+        for (&(set_num, binding_num), reqs) in &next_info.descriptor_binding_requirements {
+            match descriptor_binding_requirements.entry((set_num, binding_num)) {
+                Entry::Occupied(entry) => {
+                    if let Err(err) = entry.into_mut().merge(reqs) {
+                        return Err(Box::new(ValidationError {
+                            context: "next".into(),
+                            problem: format!(
+                                "the descriptor binding requirements of set {} binding {} \
+                                are not compatible between the two stages: {}",
+                                set_num, binding_num, err
+                            )
+                            .into(),
+                            ..Default::default()
+                        }));
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(reqs.clone());
+                }
+            }
+        }
+
+        let mut push_constant_ranges: Vec<PushConstantRange> = Vec::new();
+
+        for range in [
+            &info.push_constant_requirements,
+            &next_info.push_constant_requirements,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if let Some(existing_range) = push_constant_ranges.iter_mut().find(|existing_range| {
+                existing_range.offset == range.offset && existing_range.size == range.size
+            }) {
+                existing_range.stages |= range.stages;
+            } else {
+                push_constant_ranges.push(*range);
+            }
+        }
+
+        Ok(LinkedStageInfo {
+            descriptor_binding_requirements,
+            push_constant_ranges,
+        })
+    }
+
+    /// Returns a `VertexInputState` that could be used with the input interface of this entry
+    /// point, which must be a vertex entry point.
+    ///
+    /// The state has a single binding, with all inputs interleaved in order of location and
+    /// tightly packed, using the format returned by [`ShaderInterfaceEntryType::format`]. Array
+    /// and matrix inputs get one attribute per element, which is every second location for
+    /// 64-bit vectors with more than 2 components. Inputs that share a location, by starting at
+    /// different components, get a single attribute that covers all of them. If they don't have
+    /// the same numeric type, no attribute is generated for the location.
+    ///
+    /// This is only a convenience, for example to get started or to tweak further. It can't
+    /// know how the vertex data is actually laid out, so it is not guaranteed to match your
+    /// vertex buffers.
+    pub fn guess_vertex_input_state(&self) -> VertexInputState {
+        self.info().input_interface.guess_vertex_input_state()
+    }
+}
+
+/// The combined requirements of two entry points that were linked with
+/// [`EntryPoint::link_with`].
+#[derive(Clone, Debug)]
+pub struct LinkedStageInfo {
+    /// The merged descriptor binding requirements of both entry points.
+    pub descriptor_binding_requirements: HashMap<(u32, u32), DescriptorBindingRequirements>,
+
+    /// The push constant ranges of both entry points. If both entry points use the same range,
+    /// it is included once, with the stages of both entry points.
+    pub push_constant_ranges: Vec<PushConstantRange>,
+}
+
+/// Combines the push constant ranges of several entry points, such as the stages of a pipeline.
+///
+/// Ranges that overlap are merged into a single range that covers all of them, and whose stages
+/// are the union of their stages. Ranges that don't overlap are kept separate. The returned
+/// ranges are sorted by offset.
+pub fn merge_push_constant_ranges(infos: &[&EntryPointInfo]) -> Vec<PushConstantRange> {
+    let mut ranges: Vec<PushConstantRange> = infos
+        .iter()
+        .filter_map(|info| info.push_constant_requirements)
+        .collect();
+    ranges.sort_unstable_by_key(|range| range.offset);
+
+    let mut merged_ranges: Vec<PushConstantRange> = Vec::with_capacity(ranges.len());
+
+    for range in ranges {
+        match merged_ranges.last_mut() {
+            Some(last_range) if range.offset < last_range.offset + last_range.size => {
+                let end = max(
+                    last_range.offset + last_range.size,
+                    range.offset + range.size,
+                );
+                last_range.size = end - last_range.offset;
+                last_range.stages |= range.stages;
+            }
+            _ => merged_ranges.push(range),
+        }
+    }
+
+    merged_ranges
+}
+
+/// A summary of the reflection information of a whole SPIR-V module, returned by
+/// [`Spirv::reflect_module`].
+#[derive(Clone, Debug)]
+pub struct ModuleReflection {
+    /// The SPIR-V version that the module is compiled for.
+    pub version: Version,
+
+    /// The capabilities that are declared by the module.
+    pub capabilities: Vec<Capability>,
+
+    /// The extensions that are declared by the module.
+    pub extensions: Vec<String>,
+
+    /// The source language that the module was compiled from and its version, as declared by the
+    /// `OpSource` instruction. See [`Spirv::source_language`].
+    pub source_language: Option<(SourceLanguage, u32)>,
+
+    /// Whether the module uses the `StorageBuffer` storage class for storage buffers, rather than
+    /// the older convention of the `Uniform` storage class with a `BufferBlock` decoration.
+    ///
+    /// The `StorageBuffer` storage class requires SPIR-V 1.3 or the
+    /// `SPV_KHR_storage_buffer_storage_class` extension, which in turn requires Vulkan 1.1 or the
+    /// [`khr_storage_buffer_storage_class`] device extension.
+    ///
+    /// [`khr_storage_buffer_storage_class`]: crate::device::DeviceExtensions::khr_storage_buffer_storage_class
+    pub uses_storage_buffer_class: bool,
+
+    /// Information about each entry point in the module.
+    pub entry_points: Vec<EntryPointInfo>,
+
+    /// The descriptor binding requirements of all entry points, keyed by set number and then by
+    /// binding number. If multiple entry points use the same binding, their requirements are
+    /// merged with [`DescriptorBindingRequirements::merge`]. If the requirements of two entry
+    /// points conflict, the requirements that conflict are relaxed to what both entry points
+    /// allow, and the binding is listed in `conflicting_bindings`.
+    pub descriptor_binding_requirements: HashMap<u32, HashMap<u32, DescriptorBindingRequirements>>,
+
+    /// The set and binding numbers of the bindings whose requirements conflict between entry
+    /// points, sorted. A single descriptor set layout can't satisfy all the entry points that use
+    /// such a binding, so they can't be used in the same pipeline.
+    pub conflicting_bindings: Vec<(u32, u32)>,
+
+    /// The formats that are declared for the storage images used by the entry points.
+    ///
+    /// Storage images with a format that is not in the list of formats that are always supported
+    /// for storage images, such as `Rg16f`, require the
+    /// [`shader_storage_image_extended_formats`] feature to be enabled on the device.
+    ///
+    /// [`shader_storage_image_extended_formats`]: crate::device::Features::shader_storage_image_extended_formats
+    pub storage_image_formats: HashSet<Format>,
 }
 
 /// The requirements imposed by a shader on a binding within a descriptor set layout, and on any
 /// resource that is bound to that binding.
-#[derive(Clone, Debug, Default)]
+///
+/// With the `serde` feature, the `descriptors` map is (de)serialized as a list of key-value
+/// pairs, sorted by key.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DescriptorBindingRequirements {
     /// The descriptor types that are allowed.
     pub descriptor_types: Vec<DescriptorType>,
@@ -913,21 +2040,78 @@ pub struct DescriptorBindingRequirements {
     /// access every array element provided in the descriptor set.
     pub descriptor_count: Option<u32>,
 
+    /// Whether the shader indexes into this binding with an index that is decorated with
+    /// `NonUniform`. If so, the `*_array_non_uniform_indexing` feature that corresponds to the
+    /// descriptor type must be enabled on the device.
+    pub nonuniform_indexing: bool,
+
     /// The image format that is required for image views bound to this binding. If this is
     /// `None`, then any image format is allowed.
+    ///
+    /// If different descriptors in the binding require different formats, this is `None`, and
+    /// the format for each descriptor is given by [`DescriptorRequirements::image_format`].
     pub image_format: Option<Format>,
 
     /// Whether image views bound to this binding must have multisampling enabled or disabled.
+    ///
+    /// This is taken from the `MS` operand of the image type, for sampled images, storage images
+    /// and combined image samplers alike.
     pub image_multisampled: bool,
 
+    /// Whether the image type of this binding is declared as a depth image, as given by the
+    /// `Depth` operand of the image type. For combined image samplers, such as a GLSL
+    /// `sampler2DShadow`, this is taken from the image type that the sampled image type wraps.
+    ///
+    /// Depth images are normally used with depth comparison, see
+    /// [`DescriptorRequirements::sampler_compare`].
+    pub image_depth: bool,
+
     /// The base scalar type required for the format of image views bound to this binding.
     /// This is `None` for non-image bindings.
+    ///
+    /// For storage images, this is taken from the texel type of the `OpImageRead` and
+    /// `OpImageWrite` instructions that access the image, if there are any.
     pub image_scalar_type: Option<NumericType>,
 
+    /// The width in bits of the scalar type in `image_scalar_type`, as given by the sampled type
+    /// of the image type. This is `None` for non-image bindings.
+    ///
+    /// This is normally 32, but can be 64 for images with a 64-bit integer sampled type, or 16 for
+    /// images with a 16-bit float sampled type.
+    pub image_scalar_width: Option<u32>,
+
     /// The view type that is required for image views bound to this binding.
     /// This is `None` for non-image bindings.
     pub image_view_type: Option<ImageViewType>,
 
+    /// For input attachment bindings, the index of the input attachment in the subpass that the
+    /// binding refers to, as given by the `InputAttachmentIndex` decoration.
+    /// This is `None` for other bindings.
+    pub input_attachment_index: Option<u32>,
+
+    /// For storage buffer bindings whose block ends in a runtime-sized array, the `ArrayStride`
+    /// of that array. This can be used to calculate how many elements of the array fit in the
+    /// buffer that is bound. This is `None` for other bindings.
+    pub runtime_array_stride: Option<u32>,
+
+    /// For storage image and storage texel buffer bindings whose image format is `Unknown`,
+    /// whether the shader reads from the image with `OpImageRead`. If so, the
+    /// [`shader_storage_image_read_without_format`] feature must be enabled on the device, or the
+    /// format of the image view must support [`FormatFeatures::STORAGE_READ_WITHOUT_FORMAT`].
+    ///
+    /// [`shader_storage_image_read_without_format`]: crate::device::Features::shader_storage_image_read_without_format
+    /// [`FormatFeatures::STORAGE_READ_WITHOUT_FORMAT`]: crate::format::FormatFeatures::STORAGE_READ_WITHOUT_FORMAT
+    pub storage_read_without_format: bool,
+
+    /// For storage image and storage texel buffer bindings whose image format is `Unknown`,
+    /// whether the shader writes to the image with `OpImageWrite`. If so, the
+    /// [`shader_storage_image_write_without_format`] feature must be enabled on the device, or the
+    /// format of the image view must support [`FormatFeatures::STORAGE_WRITE_WITHOUT_FORMAT`].
+    ///
+    /// [`shader_storage_image_write_without_format`]: crate::device::Features::shader_storage_image_write_without_format
+    /// [`FormatFeatures::STORAGE_WRITE_WITHOUT_FORMAT`]: crate::format::FormatFeatures::STORAGE_WRITE_WITHOUT_FORMAT
+    pub storage_write_without_format: bool,
+
     /// The shader stages that the binding must be declared for.
     pub stages: ShaderStages,
 
@@ -936,12 +2120,20 @@ pub struct DescriptorBindingRequirements {
     /// Keys with `Some` hold requirements for a specific descriptor index, if it is statically
     /// known in the shader (a constant). The key `None` holds requirements for indices that are
     /// not statically known, but determined only at runtime (calculated from an input variable).
+    #[cfg_attr(feature = "serde", serde(with = "serde_descriptors"))]
     pub descriptors: HashMap<Option<u32>, DescriptorRequirements>,
 }
 
 /// The requirements imposed by a shader on resources bound to a descriptor.
-#[derive(Clone, Debug, Default)]
+///
+/// With the `serde` feature, the `sampler_with_images` set is (de)serialized as a sorted list.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DescriptorRequirements {
+    /// For image bindings, the image format that is required for the image view bound to this
+    /// descriptor. If this is `None`, then any image format is allowed.
+    pub image_format: Option<Format>,
+
     /// For buffers and images, which shader stages perform read operations.
     pub memory_read: ShaderStages,
 
@@ -961,21 +2153,96 @@ pub struct DescriptorRequirements {
     /// instructions or with an offset.
     pub sampler_no_ycbcr_conversion: bool,
 
+    /// For sampler bindings, whether the shader performs sampling operations with an implicit
+    /// level of detail. These use `ImplicitLod` SPIR-V instructions, which compute the LOD from
+    /// derivatives, and are only valid in stages where derivatives are available.
+    pub sampler_uses_implicit_lod: bool,
+
     /// For sampler bindings, the sampled image descriptors that are used in combination with this
     /// sampler.
+    #[cfg_attr(feature = "serde", serde(with = "serde_descriptor_identifiers"))]
     pub sampler_with_images: HashSet<DescriptorIdentifier>,
 
     /// For storage image bindings, whether the shader performs atomic operations.
     pub storage_image_atomic: bool,
+
+    /// For storage buffer and storage image bindings, whether the variable, or a member of the
+    /// buffer block, is decorated with `Coherent`.
+    pub coherent: bool,
+
+    /// For storage buffer and storage image bindings, whether the variable, or a member of the
+    /// buffer block, is decorated with `Volatile`.
+    pub volatile: bool,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DescriptorIdentifier {
     pub set: u32,
     pub binding: u32,
     pub index: u32,
 }
 
+#[cfg(feature = "serde")]
+mod serde_descriptors {
+    use super::DescriptorRequirements;
+    use ahash::HashMap;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S>(
+        value: &HashMap<Option<u32>, DescriptorRequirements>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut descriptors: Vec<_> = value.iter().collect();
+        descriptors.sort_unstable_by_key(|&(&index, _)| index);
+        descriptors.serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<HashMap<Option<u32>, DescriptorRequirements>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::<(Option<u32>, DescriptorRequirements)>::deserialize(deserializer)
+            .map(|descriptors| descriptors.into_iter().collect())
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_descriptor_identifiers {
+    use super::DescriptorIdentifier;
+    use ahash::HashSet;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S>(
+        value: &HashSet<DescriptorIdentifier>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut identifiers: Vec<_> = value.iter().collect();
+        identifiers.sort_unstable_by_key(|identifier| {
+            (identifier.set, identifier.binding, identifier.index)
+        });
+        identifiers.serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<HashSet<DescriptorIdentifier>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::<DescriptorIdentifier>::deserialize(deserializer)
+            .map(|identifiers| identifiers.into_iter().collect())
+    }
+}
+
 impl DescriptorBindingRequirements {
     /// Merges `other` into `self`, so that `self` satisfies the requirements of both.
     /// An error is returned if the requirements conflict.
@@ -984,10 +2251,17 @@ impl DescriptorBindingRequirements {
         let Self {
             descriptor_types,
             descriptor_count,
+            nonuniform_indexing,
             image_format,
             image_multisampled,
+            image_depth,
             image_scalar_type,
+            image_scalar_width,
             image_view_type,
+            input_attachment_index,
+            runtime_array_stride,
+            storage_read_without_format,
+            storage_write_without_format,
             stages,
             descriptors,
         } = self;
@@ -1005,19 +2279,35 @@ impl DescriptorBindingRequirements {
             }));
         }
 
-        if let (Some(first), Some(second)) = (*image_format, other.image_format) {
+        for (index, desc_reqs) in &other.descriptors {
+            if let (Some(first), Some(second)) = (
+                descriptors
+                    .get(index)
+                    .and_then(|desc_reqs| desc_reqs.image_format),
+                desc_reqs.image_format,
+            ) {
+                if first != second {
+                    return Err(Box::new(ValidationError {
+                        problem: "the descriptors require different formats".into(),
+                        ..Default::default()
+                    }));
+                }
+            }
+        }
+
+        if let (Some(first), Some(second)) = (*image_scalar_type, other.image_scalar_type) {
             if first != second {
                 return Err(Box::new(ValidationError {
-                    problem: "the descriptors require different formats".into(),
+                    problem: "the descriptors require different scalar types".into(),
                     ..Default::default()
                 }));
             }
         }
 
-        if let (Some(first), Some(second)) = (*image_scalar_type, other.image_scalar_type) {
+        if let (Some(first), Some(second)) = (*image_scalar_width, other.image_scalar_width) {
             if first != second {
                 return Err(Box::new(ValidationError {
-                    problem: "the descriptors require different scalar types".into(),
+                    problem: "the descriptors require different scalar widths".into(),
                     ..Default::default()
                 }));
             }
@@ -1032,6 +2322,16 @@ impl DescriptorBindingRequirements {
             }
         }
 
+        if let (Some(first), Some(second)) = (*input_attachment_index, other.input_attachment_index)
+        {
+            if first != second {
+                return Err(Box::new(ValidationError {
+                    problem: "the descriptors refer to different input attachment indices".into(),
+                    ..Default::default()
+                }));
+            }
+        }
+
         if *image_multisampled != other.image_multisampled {
             return Err(Box::new(ValidationError {
                 problem: "the multisampling requirements of the descriptors differ".into(),
@@ -1044,9 +2344,19 @@ impl DescriptorBindingRequirements {
         descriptor_types.retain(|ty| other.descriptor_types.contains(ty));
 
         *descriptor_count = (*descriptor_count).max(other.descriptor_count);
-        *image_format = image_format.or(other.image_format);
+        *nonuniform_indexing |= other.nonuniform_indexing;
+        *image_format = match (*image_format, other.image_format) {
+            (Some(first), Some(second)) if first != second => None,
+            (first, second) => first.or(second),
+        };
+        *image_depth |= other.image_depth;
         *image_scalar_type = image_scalar_type.or(other.image_scalar_type);
+        *image_scalar_width = image_scalar_width.or(other.image_scalar_width);
         *image_view_type = image_view_type.or(other.image_view_type);
+        *input_attachment_index = input_attachment_index.or(other.input_attachment_index);
+        *runtime_array_stride = runtime_array_stride.or(other.runtime_array_stride);
+        *storage_read_without_format |= other.storage_read_without_format;
+        *storage_write_without_format |= other.storage_write_without_format;
         *stages |= other.stages;
 
         for (&index, other) in &other.descriptors {
@@ -1060,8 +2370,73 @@ impl DescriptorBindingRequirements {
             }
         }
 
+        // There is no common format if any descriptor requires a different one.
+        if descriptors.values().any(|desc_reqs| {
+            desc_reqs
+                .image_format
+                .map_or(false, |format| Some(format) != *image_format)
+        }) {
+            *image_format = None;
+        }
+
         Ok(())
     }
+
+    /// Returns the number of descriptors in the binding that can be used with an immutable
+    /// sampler that has a sampler YCbCr conversion.
+    ///
+    /// SPIR-V does not mark which descriptors are meant to be used with a sampler YCbCr
+    /// conversion, so this is an upper bound: it counts the combined image sampler descriptors
+    /// that the shader does not access in a way that is forbidden with a conversion, as given by
+    /// [`DescriptorRequirements::sampler_no_ycbcr_conversion`]. The immutable samplers of the
+    /// other descriptors must not have a conversion.
+    ///
+    /// Returns `None` if the binding is a runtime-sized array.
+    pub fn sampler_ycbcr_conversion_compatible_count(&self) -> Option<u32> {
+        if !self
+            .descriptor_types
+            .contains(&DescriptorType::CombinedImageSampler)
+        {
+            return Some(0);
+        }
+
+        // If the shader indexes into the binding dynamically, any descriptor can be accessed.
+        if self
+            .descriptors
+            .get(&None)
+            .map_or(false, |desc_reqs| desc_reqs.sampler_no_ycbcr_conversion)
+        {
+            return Some(0);
+        }
+
+        let descriptor_count = self.descriptor_count?;
+        let incompatible_count = self
+            .descriptors
+            .iter()
+            .filter(|&(&index, desc_reqs)| {
+                index.map_or(false, |index| index < descriptor_count)
+                    && desc_reqs.sampler_no_ycbcr_conversion
+            })
+            .count() as u32;
+
+        Some(descriptor_count - incompatible_count)
+    }
+
+    /// Returns a descriptor set layout binding that satisfies these requirements.
+    ///
+    /// The descriptor type is the first of `descriptor_types`, and the binding is accessible from
+    /// `stages`. If `descriptor_count` is `None`, because the shader declares a runtime-sized
+    /// array, then `runtime_array_count` is used as the number of descriptors instead. The binding
+    /// flags are left empty, so to use a variable number of descriptors, you must add
+    /// [`DescriptorBindingFlags::VARIABLE_DESCRIPTOR_COUNT`] yourself.
+    ///
+    /// [`DescriptorBindingFlags::VARIABLE_DESCRIPTOR_COUNT`]: crate::descriptor_set::layout::DescriptorBindingFlags::VARIABLE_DESCRIPTOR_COUNT
+    pub fn to_layout_binding(&self, runtime_array_count: u32) -> DescriptorSetLayoutBinding {
+        DescriptorSetLayoutBinding {
+            descriptor_count: self.descriptor_count.unwrap_or(runtime_array_count),
+            ..DescriptorSetLayoutBinding::from(self)
+        }
+    }
 }
 
 impl DescriptorRequirements {
@@ -1069,27 +2444,38 @@ impl DescriptorRequirements {
     #[inline]
     pub fn merge(&mut self, other: &Self) {
         let Self {
+            image_format,
             memory_read,
             memory_write,
             sampler_compare,
             sampler_no_unnormalized_coordinates,
             sampler_no_ycbcr_conversion,
+            sampler_uses_implicit_lod,
             sampler_with_images,
             storage_image_atomic,
+            coherent,
+            volatile,
         } = self;
 
+        *image_format = image_format.or(other.image_format);
         *memory_read |= other.memory_read;
         *memory_write |= other.memory_write;
         *sampler_compare |= other.sampler_compare;
         *sampler_no_unnormalized_coordinates |= other.sampler_no_unnormalized_coordinates;
         *sampler_no_ycbcr_conversion |= other.sampler_no_ycbcr_conversion;
+        *sampler_uses_implicit_lod |= other.sampler_uses_implicit_lod;
         sampler_with_images.extend(&other.sampler_with_images);
         *storage_image_atomic |= other.storage_image_atomic;
+        *coherent |= other.coherent;
+        *volatile |= other.volatile;
     }
 }
 
 /// Type that contains the definition of an interface between two shader stages, or between
 /// the outside and a shader stage.
+///
+/// Two interfaces compare equal if they contain the same elements, regardless of their order and
+/// of the names of the elements.
 #[derive(Clone, Debug)]
 pub struct ShaderInterface {
     elements: Vec<ShaderInterfaceEntry>,
@@ -1100,54 +2486,368 @@ impl ShaderInterface {
     ///
     /// # Safety
     ///
-    /// - Must only provide one entry per location.
-    /// - The format of each element must not be larger than 128 bits.
-    // TODO: 4x64 bit formats are possible, but they require special handling.
-    // TODO: could this be made safe?
+    /// - Elements must not occupy the same component of the same location.
+    /// - Elements with a 64-bit type must start at an even component.
+    /// - Elements with a 64-bit type and more than 2 components must start at component 0.
     #[inline]
     pub unsafe fn new_unchecked(elements: Vec<ShaderInterfaceEntry>) -> ShaderInterface {
         ShaderInterface { elements }
     }
 
-    /// Creates a description of an empty shader interface.
-    #[inline]
-    pub const fn empty() -> ShaderInterface {
-        ShaderInterface {
-            elements: Vec::new(),
+    /// Constructs a new `ShaderInterface`, checking that the elements are valid.
+    pub fn from_entries(
+        elements: Vec<ShaderInterfaceEntry>,
+    ) -> Result<ShaderInterface, Box<ValidationError>> {
+        for (index, element) in elements.iter().enumerate() {
+            element
+                .validate()
+                .map_err(|err| err.add_context(format!("elements[{}]", index)))?;
         }
-    }
 
-    /// Returns a slice containing the elements of the interface.
-    #[inline]
-    pub fn elements(&self) -> &[ShaderInterfaceEntry] {
-        self.elements.as_ref()
-    }
+        let mut occupied_slots: HashMap<(bool, u32, u32, u32), usize> = HashMap::default();
 
-    /// Checks whether the interface is potentially compatible with another one.
+        for (index2, element) in elements.iter().enumerate() {
+            for (location, component) in element.component_slots() {
+                if let Some(&index1) =
+                    occupied_slots.get(&(element.is_patch, element.index, location, component))
+                {
+                    return Err(Box::new(ValidationError {
+                        context: "elements".into(),
+                        problem: format!(
+                            "elements {} and {} both occupy component {} of location {}",
+                            index1, index2, component, location,
+                        )
+                        .into(),
+                        ..Default::default()
+                    }));
+                }
+
+                occupied_slots.insert(
+                    (element.is_patch, element.index, location, component),
+                    index2,
+                );
+            }
+        }
+
+        Ok(ShaderInterface { elements })
+    }
+
+    /// Creates a description of an empty shader interface.
+    #[inline]
+    pub const fn empty() -> ShaderInterface {
+        ShaderInterface {
+            elements: Vec::new(),
+        }
+    }
+
+    /// Returns a slice containing the elements of the interface.
+    #[inline]
+    pub fn elements(&self) -> &[ShaderInterfaceEntry] {
+        self.elements.as_ref()
+    }
+
+    /// Returns a copy of the interface, with `offset` added to the location of every element.
+    ///
+    /// This can be used to avoid location collisions when combining the interfaces of
+    /// separately compiled modules. An error is returned if an element would end past the
+    /// largest possible location.
+    pub fn with_location_offset(
+        &self,
+        offset: u32,
+    ) -> Result<ShaderInterface, Box<ValidationError>> {
+        let elements = self
+            .elements
+            .iter()
+            .map(|element| {
+                element
+                    .location
+                    .checked_add(offset)
+                    .filter(|location| location.checked_add(element.ty.num_elements).is_some())
+                    .map(|location| ShaderInterfaceEntry {
+                        location,
+                        ..element.clone()
+                    })
+                    .ok_or_else(|| {
+                        Box::new(ValidationError {
+                            context: "offset".into(),
+                            problem: format!(
+                                "moves the interface element at location {} past the largest \
+                                possible location",
+                                element.location,
+                            )
+                            .into(),
+                            ..Default::default()
+                        })
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(ShaderInterface { elements })
+    }
+
+    /// Returns a `VertexInputState` for the input interface of a vertex entry point.
+    /// See [`EntryPoint::guess_vertex_input_state`].
+    pub(crate) fn guess_vertex_input_state(&self) -> VertexInputState {
+        // The type of the attribute at each location, or `None` if the inputs at the location
+        // can't be covered by a single attribute.
+        let mut location_types: HashMap<u32, Option<ShaderInterfaceEntryType>> = HashMap::default();
+
+        for element in &self.elements {
+            let locations_per_element = element.ty.num_locations() / element.ty.num_elements;
+            let first_component = if element.ty.is_64bit {
+                element.component / 2
+            } else {
+                element.component
+            };
+            let ty = ShaderInterfaceEntryType {
+                num_components: first_component + element.ty.num_components,
+                num_elements: 1,
+                ..element.ty
+            };
+
+            for index in 0..element.ty.num_elements {
+                let location = element.location + index * locations_per_element;
+
+                match location_types.entry(location) {
+                    Entry::Occupied(entry) => {
+                        let location_type = entry.into_mut();
+                        *location_type = location_type
+                            .filter(|other| {
+                                other.base_type == ty.base_type && other.is_64bit == ty.is_64bit
+                            })
+                            .map(|other| ShaderInterfaceEntryType {
+                                num_components: other.num_components.max(ty.num_components),
+                                ..other
+                            });
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(Some(ty));
+                    }
+                }
+
+                // The attribute at `location` also consumes the next location.
+                if locations_per_element == 2 {
+                    location_types.insert(location + 1, None);
+                }
+            }
+        }
+
+        let mut location_types: Vec<_> = location_types
+            .into_iter()
+            .filter_map(|(location, ty)| Some((location, ty?.format()?)))
+            .collect();
+        location_types.sort_unstable_by_key(|&(location, _)| location);
+
+        let mut attributes = Vec::new();
+        let mut offset = 0;
+
+        for (location, format) in location_types {
+            attributes.push((
+                location,
+                VertexInputAttributeDescription {
+                    binding: 0,
+                    format,
+                    offset: offset as u32,
+                },
+            ));
+            offset += format.block_size();
+        }
+
+        if attributes.is_empty() {
+            return VertexInputState::new();
+        }
+
+        VertexInputState::new()
+            .binding(
+                0,
+                VertexInputBindingDescription {
+                    stride: offset as u32,
+                    input_rate: VertexInputRate::Vertex,
+                },
+            )
+            .attributes(attributes)
+    }
+
+    /// Returns the location and numeric type of each color output of a fragment shader, sorted
+    /// by location.
     ///
-    /// Returns `Ok` if the two interfaces are compatible.
+    /// `self` must be the output interface of a fragment entry point. Arrays are expanded into
+    /// one entry per location. Outputs with an `index` of 1, which are the second source of
+    /// dual-source blending, are not included. The numeric type of each output must match the
+    /// numeric type of the format of the color attachment that it is written to.
+    pub fn color_output_numeric_types(&self) -> Vec<(u32, NumericType)> {
+        let mut numeric_types: Vec<_> = self
+            .elements
+            .iter()
+            .filter(|element| element.index == 0)
+            .flat_map(|element| {
+                (element.location..element.location + element.ty.num_elements)
+                    .map(|location| (location, element.ty.base_type))
+            })
+            .collect();
+        numeric_types.sort_unstable_by_key(|&(location, _)| location);
+        numeric_types.dedup();
+
+        numeric_types
+    }
+
+    /// Returns the components that are occupied in each location of the interface, as a map from
+    /// the location to a 4-bit mask with bit `n` set if component `n` is used.
+    ///
+    /// Elements that are packed into the same location with the `Component` decoration are
+    /// merged into one mask. Arrays and matrices occupy one location per element, and 64-bit
+    /// types occupy two components per vector component. Per-patch elements are not included,
+    /// as they are counted separately from the per-vertex elements.
+    ///
+    /// The number of set bits can be compared with limits that are given in components, such as
+    /// [`max_vertex_output_components`].
+    ///
+    /// [`max_vertex_output_components`]: crate::device::Properties::max_vertex_output_components
+    pub fn component_usage(&self) -> HashMap<u32, u8> {
+        let mut usage = HashMap::default();
+
+        for element in self.elements.iter().filter(|element| !element.is_patch) {
+            let component_width = if element.ty.is_64bit { 2 } else { 1 };
+            let num_slots = element.ty.num_components * component_width;
+            let locations_per_element = if element.component + num_slots > 4 {
+                2
+            } else {
+                1
+            };
+
+            for array_index in 0..element.ty.num_elements {
+                let base_location = element.location + array_index * locations_per_element;
+
+                for slot in element.component..element.component + num_slots {
+                    *usage.entry(base_location + slot / 4).or_insert(0) |= 1 << (slot % 4);
+                }
+            }
+        }
+
+        usage
+    }
+
+    /// Checks whether the interface is potentially compatible with another one.
+    ///
+    /// Returns `Ok` if the two interfaces are compatible. Otherwise, returns the first
+    /// incompatibility that was found.
     #[inline]
     pub fn matches(&self, other: &ShaderInterface) -> Result<(), Box<ValidationError>> {
-        if self.elements().len() != other.elements().len() {
-            return Err(Box::new(ValidationError {
+        match self.mismatches(other).next() {
+            None => Ok(()),
+            Some(err) => Err(err),
+        }
+    }
+
+    /// Checks whether the interface is potentially compatible with another one.
+    ///
+    /// Returns `Ok` if the two interfaces are compatible. Otherwise, returns every
+    /// incompatibility that was found, unlike [`matches`](Self::matches).
+    #[inline]
+    pub fn matches_all(&self, other: &ShaderInterface) -> Result<(), Vec<Box<ValidationError>>> {
+        let errors: Vec<_> = self.mismatches(other).collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Returns the locations of the elements whose precision differs between the two interfaces:
+    /// one of them is decorated with `RelaxedPrecision` and the other is not.
+    ///
+    /// A precision mismatch doesn't make the interfaces incompatible, so this is not checked by
+    /// [`matches`](Self::matches). However, the value may lose precision when it is passed from
+    /// one stage to the next, which may be worth a warning.
+    pub fn relaxed_precision_mismatches(&self, other: &ShaderInterface) -> Vec<u32> {
+        let mut locations: Vec<_> = self
+            .elements
+            .iter()
+            .filter(|a| {
+                other.elements.iter().any(|b| {
+                    a.location == b.location
+                        && a.component == b.component
+                        && a.index == b.index
+                        && a.relaxed_precision != b.relaxed_precision
+                })
+            })
+            .map(|a| a.location)
+            .collect();
+        locations.sort_unstable();
+        locations.dedup();
+
+        locations
+    }
+
+    /// Returns the elements sorted by their location, ignoring their names.
+    fn canonical_elements(&self) -> Vec<(bool, u32, u32, u32, ShaderInterfaceEntryType)> {
+        let mut elements: Vec<_> = self
+            .elements
+            .iter()
+            .map(|element| {
+                (
+                    element.is_patch,
+                    element.location,
+                    element.component,
+                    element.index,
+                    element.ty,
+                )
+            })
+            .collect();
+        elements.sort_unstable_by_key(|&(is_patch, location, component, index, _)| {
+            (is_patch, location, component, index)
+        });
+        elements
+    }
+
+    fn mismatches<'a>(
+        &'a self,
+        other: &'a ShaderInterface,
+    ) -> impl Iterator<Item = Box<ValidationError>> + 'a {
+        let len_error = (self.elements().len() != other.elements().len()).then(|| {
+            Box::new(ValidationError {
                 problem: "the number of elements in the shader interfaces are not equal".into(),
                 ..Default::default()
-            }));
-        }
+            })
+        });
+
+        let component_errors = self
+            .elements()
+            .iter()
+            .chain(other.elements())
+            .filter(|element| element.ty.is_64bit && element.component % 2 != 0)
+            .map(|element| {
+                Box::new(ValidationError {
+                    problem: format!(
+                        "the 64-bit interface element at location {} starts at component {}, \
+                        which is not even",
+                        element.location, element.component,
+                    )
+                    .into(),
+                    ..Default::default()
+                })
+            });
 
-        for a in self.elements() {
+        let location_errors = self.elements().iter().flat_map(move |a| {
             let location_range = a.location..a.location + a.ty.num_locations();
-            for loc in location_range {
-                let b = match other
-                    .elements()
-                    .iter()
-                    .find(|e| loc >= e.location && loc < e.location + e.ty.num_locations())
-                {
+            location_range.filter_map(move |loc| {
+                let kind = if a.is_patch {
+                    "per-patch"
+                } else {
+                    "per-vertex"
+                };
+                let b = match other.elements().iter().find(|e| {
+                    e.is_patch == a.is_patch
+                        && loc >= e.location
+                        && loc < e.location + e.ty.num_locations()
+                }) {
                     None => {
-                        return Err(Box::new(ValidationError {
+                        return Some(Box::new(ValidationError {
                             problem: format!(
-                                "the second shader is missing an interface element at location {}",
-                                loc
+                                "the second shader is missing a {} interface element at location \
+                                {}",
+                                kind, loc
                             )
                             .into(),
                             ..Default::default()
@@ -1157,11 +2857,11 @@ impl ShaderInterface {
                 };
 
                 if a.ty != b.ty {
-                    return Err(Box::new(ValidationError {
+                    return Some(Box::new(ValidationError {
                         problem: format!(
-                            "the interface element at location {} does not have the same type \
+                            "the {} interface element at location {} does not have the same type \
                             in both shaders",
-                            loc
+                            kind, loc
                         )
                         .into(),
                         ..Default::default()
@@ -1173,18 +2873,74 @@ impl ShaderInterface {
                     (Some(ref an), Some(ref bn)) => if an != bn { return false },
                     _ => ()
                 };*/
-            }
-        }
+
+                None
+            })
+        });
 
         // NOTE: since we check that the number of elements is the same, we don't need to iterate
         // over b's elements.
 
-        Ok(())
+        len_error
+            .into_iter()
+            .chain(component_errors)
+            .chain(location_errors)
+    }
+}
+
+impl PartialEq for ShaderInterface {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.canonical_elements() == other.canonical_elements()
+    }
+}
+
+impl Eq for ShaderInterface {}
+
+impl Hash for ShaderInterface {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical_elements().hash(state);
+    }
+}
+
+// An interface is (de)serialized as a list of its elements. The elements are serialized in the
+// same order that is used for comparison, so that equal interfaces serialize identically, and
+// they are validated when deserializing.
+#[cfg(feature = "serde")]
+impl Serialize for ShaderInterface {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut elements: Vec<_> = self.elements.iter().collect();
+        elements.sort_by_key(|element| {
+            (
+                element.is_patch,
+                element.location,
+                element.component,
+                element.index,
+            )
+        });
+        serializer.collect_seq(elements)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ShaderInterface {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let elements = Vec::<ShaderInterfaceEntry>::deserialize(deserializer)?;
+
+        ShaderInterface::from_entries(elements).map_err(D::Error::custom)
     }
 }
 
 /// Entry of a shader interface definition.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ShaderInterfaceEntry {
     /// The location slot that the variable starts at.
     pub location: u32,
@@ -1201,10 +2957,130 @@ pub struct ShaderInterfaceEntry {
 
     /// The type of the variable.
     pub ty: ShaderInterfaceEntryType,
+
+    /// Whether the variable is decorated with `RelaxedPrecision` (`mediump` or `lowp` in GLSL),
+    /// which allows it to be stored and computed with 16-bit precision.
+    pub relaxed_precision: bool,
+
+    /// Whether the variable is decorated with `Flat` (`flat` in GLSL), which disables
+    /// interpolation. Fragment shader inputs with an integer or 64-bit type must be `Flat`.
+    pub flat: bool,
+
+    /// Whether the variable is decorated with `Patch` (`patch` in GLSL), which makes it a
+    /// per-patch variable of a tessellation shader rather than a per-vertex one.
+    ///
+    /// Per-patch and per-vertex variables have separate locations, so a per-patch variable only
+    /// matches a per-patch variable of the other shader.
+    pub is_patch: bool,
+}
+
+impl ShaderInterfaceEntry {
+    fn validate(&self) -> Result<(), Box<ValidationError>> {
+        let &Self {
+            location: _,
+            index,
+            component,
+            name: _,
+            ref ty,
+            relaxed_precision: _,
+            flat: _,
+            is_patch: _,
+        } = self;
+
+        if index > 1 {
+            return Err(Box::new(ValidationError {
+                context: "index".into(),
+                problem: "is greater than 1".into(),
+                ..Default::default()
+            }));
+        }
+
+        if component > 3 {
+            return Err(Box::new(ValidationError {
+                context: "component".into(),
+                problem: "is greater than 3".into(),
+                ..Default::default()
+            }));
+        }
+
+        if !(1..=4).contains(&ty.num_components) {
+            return Err(Box::new(ValidationError {
+                context: "ty.num_components".into(),
+                problem: "is not in the range 1..=4".into(),
+                ..Default::default()
+            }));
+        }
+
+        if ty.num_elements == 0 {
+            return Err(Box::new(ValidationError {
+                context: "ty.num_elements".into(),
+                problem: "is zero".into(),
+                ..Default::default()
+            }));
+        }
+
+        if ty.is_64bit {
+            if component % 2 != 0 {
+                return Err(Box::new(ValidationError {
+                    problem: "`ty.is_64bit` is `true`, but `component` is not even".into(),
+                    ..Default::default()
+                }));
+            }
+
+            // Three- and four-component 64-bit vectors spill into the next location.
+            if ty.num_components > 2 {
+                if component != 0 {
+                    return Err(Box::new(ValidationError {
+                        problem: "`ty.is_64bit` is `true` and `ty.num_components` is greater \
+                            than 2, but `component` is not 0"
+                            .into(),
+                        ..Default::default()
+                    }));
+                }
+            } else if component + ty.num_components * 2 > 4 {
+                return Err(Box::new(ValidationError {
+                    problem: "`ty.is_64bit` is `true`, and `component` plus twice \
+                        `ty.num_components` is greater than 4"
+                        .into(),
+                    ..Default::default()
+                }));
+            }
+        } else if component + ty.num_components > 4 {
+            return Err(Box::new(ValidationError {
+                problem: "`component` plus `ty.num_components` is greater than 4".into(),
+                ..Default::default()
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the location and component of each component slot that the entry occupies.
+    fn component_slots(&self) -> impl Iterator<Item = (u32, u32)> {
+        let &Self {
+            location,
+            component,
+            ref ty,
+            ..
+        } = self;
+        let slot_count = if ty.is_64bit {
+            ty.num_components * 2
+        } else {
+            ty.num_components
+        };
+        let locations_per_element = ty.num_locations() / ty.num_elements;
+
+        (0..ty.num_elements).flat_map(move |element| {
+            let location = location + element * locations_per_element;
+
+            (component..component + slot_count).map(move |slot| (location + slot / 4, slot % 4))
+        })
+    }
 }
 
 /// The type of a variable in a shader interface.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ShaderInterfaceEntryType {
     /// The base numeric type.
     pub base_type: NumericType,
@@ -1216,17 +3092,114 @@ pub struct ShaderInterfaceEntryType {
     pub num_elements: u32,
 
     /// Whether the base type is 64 bits wide. If true, each item of the base type takes up two
-    /// component slots instead of one.
+    /// component slots instead of one. A vector with more than 2 components then takes up two
+    /// locations.
     pub is_64bit: bool,
 }
 
 impl ShaderInterfaceEntryType {
     pub(crate) fn num_locations(&self) -> u32 {
-        assert!(!self.is_64bit); // TODO: implement
-        self.num_elements
+        if self.is_64bit && self.num_components > 2 {
+            self.num_elements * 2
+        } else {
+            self.num_elements
+        }
+    }
+
+    /// Returns the format whose components match this type, such as
+    /// [`Format::R32G32B32A32_SFLOAT`] for a `vec4`. This can be used as the format of a vertex
+    /// attribute that is bound to a vertex shader input of this type.
+    ///
+    /// Returns `None` if the type is an array or a matrix, which span multiple locations.
+    pub fn format(&self) -> Option<Format> {
+        if self.num_elements != 1 {
+            return None;
+        }
+
+        Some(match (self.base_type, self.is_64bit, self.num_components) {
+            (NumericType::Float, false, 1) => Format::R32_SFLOAT,
+            (NumericType::Float, false, 2) => Format::R32G32_SFLOAT,
+            (NumericType::Float, false, 3) => Format::R32G32B32_SFLOAT,
+            (NumericType::Float, false, 4) => Format::R32G32B32A32_SFLOAT,
+            (NumericType::Int, false, 1) => Format::R32_SINT,
+            (NumericType::Int, false, 2) => Format::R32G32_SINT,
+            (NumericType::Int, false, 3) => Format::R32G32B32_SINT,
+            (NumericType::Int, false, 4) => Format::R32G32B32A32_SINT,
+            (NumericType::Uint, false, 1) => Format::R32_UINT,
+            (NumericType::Uint, false, 2) => Format::R32G32_UINT,
+            (NumericType::Uint, false, 3) => Format::R32G32B32_UINT,
+            (NumericType::Uint, false, 4) => Format::R32G32B32A32_UINT,
+            (NumericType::Float, true, 1) => Format::R64_SFLOAT,
+            (NumericType::Float, true, 2) => Format::R64G64_SFLOAT,
+            (NumericType::Float, true, 3) => Format::R64G64B64_SFLOAT,
+            (NumericType::Float, true, 4) => Format::R64G64B64A64_SFLOAT,
+            (NumericType::Int, true, 1) => Format::R64_SINT,
+            (NumericType::Int, true, 2) => Format::R64G64_SINT,
+            (NumericType::Int, true, 3) => Format::R64G64B64_SINT,
+            (NumericType::Int, true, 4) => Format::R64G64B64A64_SINT,
+            (NumericType::Uint, true, 1) => Format::R64_UINT,
+            (NumericType::Uint, true, 2) => Format::R64G64_UINT,
+            (NumericType::Uint, true, 3) => Format::R64G64B64_UINT,
+            (NumericType::Uint, true, 4) => Format::R64G64B64A64_UINT,
+            _ => return None,
+        })
     }
 }
 
+/// The type of a member of a push constant block or a shader record block.
+///
+/// A pointer member is described as a 64-bit unsigned integer, which holds the device address
+/// that it points to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PushConstantMemberType {
+    /// The base numeric type.
+    pub base_type: NumericType,
+
+    /// The width of the base type in bits.
+    pub width: u32,
+
+    /// The number of vector components. Must be in the range 1..=4.
+    pub num_components: u32,
+
+    /// The number of array elements or matrix columns.
+    pub num_elements: u32,
+}
+
+/// A cooperative matrix type, declared with `OpTypeCooperativeMatrixKHR`.
+///
+/// The scope, dimensions and use of the matrix are given by constants in the SPIR-V code. If one
+/// of them is a specialization constant, it is `None` until the module is specialized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CooperativeMatrixType {
+    /// The numeric type of the components of the matrix.
+    pub component_type: NumericType,
+
+    /// The width of the components in bits.
+    pub component_width: u32,
+
+    /// The scope that the operations on the matrix are performed in.
+    pub scope: Option<Scope>,
+
+    /// The number of rows of the matrix.
+    pub rows: Option<u32>,
+
+    /// The number of columns of the matrix.
+    pub columns: Option<u32>,
+
+    /// Which operand of a multiply-add the matrix is used as.
+    pub usage: Option<CooperativeMatrixUse>,
+}
+
+/// The origin of the fragment coordinates of a fragment shader.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FragmentOrigin {
+    /// The origin is at the upper left of the framebuffer, which is required by Vulkan.
+    UpperLeft,
+
+    /// The origin is at the lower left of the framebuffer, as in OpenGL.
+    LowerLeft,
+}
+
 vulkan_bitflags_enum! {
     #[non_exhaustive]
 
@@ -1241,6 +3214,28 @@ vulkan_bitflags_enum! {
                 .union(ShaderStages::GEOMETRY)
                 .union(ShaderStages::FRAGMENT)
         }
+
+        /// Returns the stages in `self` that can access descriptors: the graphics, compute, ray
+        /// tracing, task and mesh stages.
+        ///
+        /// `SUBPASS_SHADING` is not included, as the only descriptors that it can access are input
+        /// attachments. This can be used to find the stages that need a pipeline barrier for the
+        /// shader read and write accesses of a descriptor.
+        #[inline]
+        pub const fn descriptor_accessing_stages(self) -> ShaderStages {
+            self.intersection(
+                ShaderStages::all_graphics()
+                    .union(ShaderStages::COMPUTE)
+                    .union(ShaderStages::RAYGEN)
+                    .union(ShaderStages::ANY_HIT)
+                    .union(ShaderStages::CLOSEST_HIT)
+                    .union(ShaderStages::MISS)
+                    .union(ShaderStages::INTERSECTION)
+                    .union(ShaderStages::CALLABLE)
+                    .union(ShaderStages::TASK)
+                    .union(ShaderStages::MESH),
+            )
+        }
     },
 
     /// A shader stage within a pipeline.
@@ -1329,6 +3324,103 @@ vulkan_bitflags_enum! {
     ]),
 }
 
+// Stages are named by their lowercase flag names, such as `subpass_shading`. A `ShaderStage` is
+// serialized as its name. A `ShaderStages` is serialized as a list of these names, and parsed
+// from and displayed as a comma-separated list of them, such as `vertex,fragment`.
+impl ShaderStage {
+    fn name(self) -> String {
+        format!("{:?}", ShaderStages::from(self)).to_ascii_lowercase()
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        ShaderStages(ShaderStages::all_raw())
+            .into_iter()
+            .find(|stage| stage.name().eq_ignore_ascii_case(name))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for ShaderStage {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.name())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ShaderStage {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+
+        ShaderStage::from_name(&name)
+            .ok_or_else(|| D::Error::custom(format!("unknown shader stage `{}`", name)))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for ShaderStages {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.into_iter().map(ShaderStage::name))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ShaderStages {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|name| {
+                ShaderStage::from_name(name)
+                    .ok_or_else(|| D::Error::custom(format!("unknown shader stage `{}`", name)))
+            })
+            .collect()
+    }
+}
+
+impl FromStr for ShaderStages {
+    type Err = Box<ValidationError>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                ShaderStage::from_name(name).ok_or_else(|| {
+                    Box::new(ValidationError {
+                        problem: format!("`{}` is not the name of a shader stage", name).into(),
+                        ..Default::default()
+                    })
+                })
+            })
+            .collect()
+    }
+}
+
+impl Display for ShaderStages {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        for (index, stage) in self.into_iter().enumerate() {
+            if index != 0 {
+                write!(f, ",")?;
+            }
+
+            f.write_str(&stage.name())?;
+        }
+
+        Ok(())
+    }
+}
+
 impl From<ExecutionModel> for ShaderStage {
     #[inline]
     fn from(value: ExecutionModel) -> Self {
@@ -1354,6 +3446,26 @@ impl From<ExecutionModel> for ShaderStage {
     }
 }
 
+impl ShaderStage {
+    /// Returns the shader stage that corresponds to `execution_model`.
+    ///
+    /// Unlike the `From` implementation, this returns an error instead of panicking if
+    /// `execution_model` is `Kernel`, which is not supported by Vulkan.
+    #[inline]
+    pub fn try_from_execution_model(
+        execution_model: ExecutionModel,
+    ) -> Result<Self, Box<ValidationError>> {
+        match execution_model {
+            ExecutionModel::Kernel => Err(Box::new(ValidationError {
+                context: "execution_model".into(),
+                problem: "is `ExecutionModel::Kernel`, which is not supported by Vulkan".into(),
+                ..Default::default()
+            })),
+            _ => Ok(Self::from(execution_model)),
+        }
+    }
+}
+
 impl From<ShaderStages> for PipelineStages {
     #[inline]
     fn from(stages: ShaderStages) -> PipelineStages {
@@ -1409,3 +3521,1713 @@ impl From<ShaderStages> for PipelineStages {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        spirv::{Id, Spirv},
+        DescriptorBindingRequirements, DeviceCapabilities, EntryPointInfo, ShaderInterface,
+        ShaderInterfaceEntry, ShaderInterfaceEntryType, ShaderModule, ShaderModuleCreateInfo,
+        ShaderStages, SpecializationInfoBuilder, ValidatedSpirv,
+    };
+    use crate::{
+        descriptor_set::layout::DescriptorType,
+        device::{DeviceExtensions, Features, Properties},
+        format::{Format, NumericType},
+        pipeline::layout::PushConstantRange,
+        shader::{reflect, SpecializationConstant, SpecializationConstantType},
+        Version,
+    };
+    use std::{
+        collections::{hash_map::DefaultHasher, HashMap},
+        hash::{Hash, Hasher},
+        sync::Arc,
+    };
+
+    /*
+    #version 450
+
+    layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+    layout(constant_id = 83) const int VALUE = 0xdeadbeef;
+
+    layout(set = 0, binding = 0) buffer Output {
+        int write;
+    } write;
+
+    void main() {
+        write.write = VALUE;
+    }
+    */
+    const SPEC_CONSTANT_MODULE: [u32; 120] = [
+        119734787, 65536, 524289, 14, 0, 131089, 1, 393227, 1, 1280527431, 1685353262, 808793134,
+        0, 196622, 0, 1, 327695, 5, 4, 1852399981, 0, 393232, 4, 17, 1, 1, 1, 196611, 2, 450,
+        262149, 4, 1852399981, 0, 262149, 7, 1886680399, 29813, 327686, 7, 0, 1953067639, 101,
+        262149, 9, 1953067639, 101, 262149, 11, 1431060822, 69, 327752, 7, 0, 35, 0, 196679, 7, 3,
+        262215, 9, 34, 0, 262215, 9, 33, 0, 262215, 11, 1, 83, 131091, 2, 196641, 3, 2, 262165, 6,
+        32, 1, 196638, 7, 6, 262176, 8, 2, 7, 262203, 8, 9, 2, 262187, 6, 10, 0, 262194, 6, 11,
+        3735928559, 262176, 12, 2, 6, 327734, 2, 4, 0, 3, 131320, 5, 327745, 12, 13, 9, 10, 196670,
+        13, 11, 65789, 65592,
+    ];
+
+    fn spec_constant_module(device: Arc<crate::device::Device>) -> Arc<ShaderModule> {
+        unsafe {
+            ShaderModule::new(device, ShaderModuleCreateInfo::new(&SPEC_CONSTANT_MODULE)).unwrap()
+        }
+    }
+
+    #[test]
+    fn specialization_info_builder() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let module = spec_constant_module(device);
+
+        let mut builder = SpecializationInfoBuilder::for_module(&module);
+        builder
+            .set_i32(83, 0x12345678)
+            .unwrap()
+            .set_f32(5, 1.0)
+            .unwrap();
+        let specialization_info = builder.build();
+
+        let specialized = module.specialize(specialization_info).unwrap();
+        assert_eq!(
+            specialized.specialization_info()[&83],
+            SpecializationConstant::I32(0x12345678),
+        );
+        assert_eq!(
+            specialized.specialization_info()[&5],
+            SpecializationConstant::F32(1.0),
+        );
+    }
+
+    #[test]
+    fn specialization_info_builder_type_mismatch() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let module = spec_constant_module(device);
+
+        let mut builder = SpecializationInfoBuilder::for_module(&module);
+        assert!(builder.set_u32(83, 0x12345678).is_err());
+
+        // Without a module, the type isn't checked.
+        let mut builder = SpecializationInfoBuilder::new();
+        assert!(builder.set_u32(83, 0x12345678).is_ok());
+        assert!(module.specialize(builder.build()).is_err());
+    }
+
+    #[test]
+    fn specialization_constant_bool_array() {
+        /*
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+        OpDecorate %flag_a SpecId 0
+        OpDecorate %flag_b SpecId 1
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %bool = OpTypeBool
+        %uint = OpTypeInt 32 0
+        %uint_2 = OpConstant %uint 2
+        %bool_2 = OpTypeArray %bool %uint_2
+        %flag_a = OpSpecConstantFalse %bool
+        %flag_b = OpSpecConstantTrue %bool
+        %flags = OpSpecConstantComposite %bool_2 %flag_a %flag_b
+        %main = OpFunction %void None %fn
+        %label = OpLabel
+        OpReturn
+        OpFunctionEnd
+        */
+        const MODULE: [u32; 68] = [
+            119734787, 65536, 0, 12, 0, 131089, 1, 196622, 0, 1, 327695, 5, 1, 1852399981, 0,
+            393232, 1, 17, 1, 1, 1, 262215, 2, 1, 0, 262215, 3, 1, 1, 131091, 4, 196641, 5, 4,
+            131092, 6, 262165, 7, 32, 0, 262187, 7, 8, 2, 262172, 9, 6, 8, 196657, 6, 2, 196656, 6,
+            3, 327731, 9, 10, 2, 3, 327734, 4, 1, 0, 5, 131320, 11, 65789, 65592,
+        ];
+
+        // Each element of the composite is a separate specialization constant.
+        let spirv = Spirv::new(&MODULE).unwrap();
+        let constants = reflect::specialization_constants(&spirv);
+        assert_eq!(constants.len(), 2);
+
+        let bytes: Vec<u8> = (0..2)
+            .flat_map(|constant_id| constants[&constant_id].as_bytes().to_vec())
+            .collect();
+        assert_eq!(bytes, [0, 0, 0, 0, 1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn specialize_from() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let module = spec_constant_module(device);
+
+        let from_map = module
+            .specialize(
+                [(83, SpecializationConstant::I32(0x12345678))]
+                    .into_iter()
+                    .collect(),
+            )
+            .unwrap();
+        let from_slice = module
+            .specialize_from(&[(83, SpecializationConstant::I32(0x12345678))])
+            .unwrap();
+        let from_one = module.specialize_one(83, 0x12345678i32).unwrap();
+
+        assert_eq!(
+            from_map.specialization_info(),
+            from_slice.specialization_info(),
+        );
+        assert_eq!(
+            from_map.specialization_info(),
+            from_one.specialization_info(),
+        );
+        assert!(module
+            .specialize_from(&[(83, SpecializationConstant::U32(0x12345678))])
+            .is_err());
+    }
+
+    #[test]
+    fn specialized_spirv_words() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let module = spec_constant_module(device.clone());
+
+        let default = module.specialize(Default::default()).unwrap();
+        let overridden = module.specialize_one(83, 0x12345678i32).unwrap();
+
+        let default_words = default.spirv_words();
+        let overridden_words = overridden.spirv_words();
+        assert_ne!(default_words, overridden_words);
+        assert!(overridden_words.contains(&0x12345678));
+
+        // The emitted words can be parsed again.
+        let spirv = Spirv::new(&overridden_words).unwrap();
+        assert_eq!(spirv.to_words(), overridden_words);
+
+        // Without specialization constants, the code of the base module is returned.
+        let module =
+            unsafe { ShaderModule::new(device, ShaderModuleCreateInfo::new(&LINK_VS)) }.unwrap();
+        let unspecialized = module.specialize(Default::default()).unwrap();
+        assert!(!unspecialized.was_specialized());
+        assert_eq!(unspecialized.spirv_words(), LINK_VS);
+    }
+
+    #[test]
+    fn descriptor_requirements_changed() {
+        /*
+        #version 450
+
+        layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+        layout(constant_id = 0) const uint COUNT = 2;
+
+        layout(set = 0, binding = 0, rgba8) uniform writeonly image2D images[COUNT];
+
+        void main() {
+            imageStore(images[0], ivec2(0), vec4(0.0));
+        }
+        */
+        const MODULE: [u32; 131] = [
+            119734787, 65536, 0, 22, 0, 131089, 1, 196622, 0, 1, 327695, 5, 1, 1852399981, 0,
+            393232, 1, 17, 1, 1, 1, 262215, 2, 1, 0, 262215, 3, 34, 0, 262215, 3, 33, 0, 196679, 3,
+            25, 131091, 4, 196641, 5, 4, 262165, 6, 32, 0, 262165, 7, 32, 1, 196630, 8, 32, 262167,
+            9, 8, 4, 262167, 10, 7, 2, 262194, 6, 2, 2, 589849, 11, 8, 1, 0, 0, 0, 2, 4, 262172,
+            12, 11, 2, 262176, 13, 0, 12, 262176, 14, 0, 11, 262203, 13, 3, 0, 262187, 7, 15, 0,
+            327724, 10, 16, 15, 15, 262187, 8, 17, 0, 458796, 9, 18, 17, 17, 17, 17, 327734, 4, 1,
+            0, 5, 131320, 19, 327745, 14, 20, 3, 15, 262205, 11, 21, 20, 262243, 21, 16, 18, 65789,
+            65592,
+        ];
+
+        let (device, _queue) = gfx_dev_and_queue!();
+        let module =
+            unsafe { ShaderModule::new(device, ShaderModuleCreateInfo::new(&MODULE)).unwrap() };
+
+        let default = module.specialize(Default::default()).unwrap();
+        assert!(!default.descriptor_requirements_changed());
+
+        let same = module.specialize_one(0, 2u32).unwrap();
+        assert!(!same.descriptor_requirements_changed());
+
+        let larger = module.specialize_one(0, 4u32).unwrap();
+        assert!(larger.descriptor_requirements_changed());
+
+        let entry_point = larger.single_entry_point().unwrap();
+        assert_eq!(
+            entry_point.info().descriptor_binding_requirements[&(0, 0)].descriptor_count,
+            Some(4),
+        );
+    }
+
+    #[test]
+    fn was_specialized() {
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        let module = |words: &[u32]| unsafe {
+            ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(words)).unwrap()
+        };
+
+        let without_constants = module(&LINK_VS).specialize(Default::default()).unwrap();
+        assert!(!without_constants.was_specialized());
+
+        let with_constants = module(&SPEC_CONSTANT_MODULE)
+            .specialize(Default::default())
+            .unwrap();
+        assert!(with_constants.was_specialized());
+    }
+
+    #[test]
+    fn resolved_derived_constants() {
+        /*
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+        OpDecorate %base SpecId 0
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %uint = OpTypeInt 32 0
+        %uint_2 = OpConstant %uint 2
+        %base = OpSpecConstant %uint 1
+        %derived = OpSpecConstantOp %uint IMul %base %uint_2
+        %main = OpFunction %void None %fn
+        %l = OpLabel
+        OpReturn
+        OpFunctionEnd
+        */
+        const DERIVED_CONSTANT: [u32; 57] = [
+            119734787, 65536, 0, 9, 0, 131089, 1, 196622, 0, 1, 327695, 5, 1, 1852399981, 0,
+            393232, 1, 17, 1, 1, 1, 262215, 2, 1, 0, 131091, 3, 196641, 4, 3, 262165, 5, 32, 0,
+            262187, 5, 6, 2, 262194, 5, 2, 1, 393268, 5, 7, 132, 2, 6, 327734, 3, 1, 0, 4, 131320,
+            8, 65789, 65592,
+        ];
+
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        let module = unsafe {
+            ShaderModule::new(device, ShaderModuleCreateInfo::new(&DERIVED_CONSTANT)).unwrap()
+        };
+
+        let default = module.specialize(Default::default()).unwrap();
+        assert_eq!(
+            default.resolved_derived_constants()[&7],
+            SpecializationConstant::U32(2),
+        );
+
+        let specialized = module
+            .specialize_from(&[(0, SpecializationConstant::U32(4))])
+            .unwrap();
+        let derived = specialized.resolved_derived_constants();
+        assert_eq!(derived.len(), 1);
+        assert_eq!(derived[&7], SpecializationConstant::U32(8));
+    }
+
+    #[test]
+    fn num_entry_points() {
+        /*
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main_a "main_a"
+        OpEntryPoint GLCompute %main_b "main_b"
+        OpExecutionMode %main_a LocalSize 1 1 1
+        OpExecutionMode %main_b LocalSize 1 1 1
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %main_a = OpFunction %void None %fn
+        %label_a = OpLabel
+        OpReturn
+        OpFunctionEnd
+        %main_b = OpFunction %void None %fn
+        %label_b = OpLabel
+        OpReturn
+        OpFunctionEnd
+        */
+        const TWO_ENTRY_POINTS: [u32; 55] = [
+            119734787, 65536, 0, 7, 0, 131089, 1, 196622, 0, 1, 327695, 5, 1, 1852399981, 24927,
+            327695, 5, 2, 1852399981, 25183, 393232, 1, 17, 1, 1, 1, 393232, 2, 17, 1, 1, 1,
+            131091, 3, 196641, 4, 3, 327734, 3, 1, 0, 4, 131320, 5, 65789, 65592, 327734, 3, 2, 0,
+            4, 131320, 6, 65789, 65592,
+        ];
+
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        for words in [&LINK_VS[..], &SPEC_CONSTANT_MODULE, &TWO_ENTRY_POINTS] {
+            let module =
+                unsafe { ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(words)) }
+                    .unwrap();
+            let spirv = Spirv::new(words).unwrap();
+            assert_eq!(
+                module.num_entry_points(),
+                reflect::entry_points(&spirv).count(),
+            );
+        }
+    }
+
+    #[test]
+    fn guess_vertex_input_state() {
+        /*
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint Vertex %main "main" %position %normal %uv
+        OpDecorate %position Location 0
+        OpDecorate %normal Location 1
+        OpDecorate %uv Location 2
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %float = OpTypeFloat 32
+        %v2 = OpTypeVector %float 2
+        %v3 = OpTypeVector %float 3
+        %pv2 = OpTypePointer Input %v2
+        %pv3 = OpTypePointer Input %v3
+        %position = OpVariable %pv3 Input
+        %normal = OpVariable %pv3 Input
+        %uv = OpVariable %pv2 Input
+        %main = OpFunction %void None %fn
+        %l = OpLabel
+        OpReturn
+        OpFunctionEnd
+        */
+        const VERTEX_ATTRIBUTES: [u32; 75] = [
+            119734787, 65536, 0, 13, 0, 131089, 1, 196622, 0, 1, 524303, 0, 1, 1852399981, 0, 2, 3,
+            4, 262215, 2, 30, 0, 262215, 3, 30, 1, 262215, 4, 30, 2, 131091, 5, 196641, 6, 5,
+            196630, 7, 32, 262167, 8, 7, 2, 262167, 9, 7, 3, 262176, 10, 1, 8, 262176, 11, 1, 9,
+            262203, 11, 2, 1, 262203, 11, 3, 1, 262203, 10, 4, 1, 327734, 5, 1, 0, 6, 131320, 12,
+            65789, 65592,
+        ];
+
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        let module = unsafe {
+            ShaderModule::new(device, ShaderModuleCreateInfo::new(&VERTEX_ATTRIBUTES)).unwrap()
+        };
+        let vertex_input_state = module
+            .single_entry_point()
+            .unwrap()
+            .guess_vertex_input_state();
+
+        assert_eq!(vertex_input_state.bindings.len(), 1);
+        assert_eq!(vertex_input_state.bindings[&0].stride, 32);
+
+        let attribute = |location: u32| {
+            let attribute = &vertex_input_state.attributes[&location];
+            (attribute.binding, attribute.format, attribute.offset)
+        };
+        assert_eq!(attribute(0), (0, Format::R32G32B32_SFLOAT, 0));
+        assert_eq!(attribute(1), (0, Format::R32G32B32_SFLOAT, 12));
+        assert_eq!(attribute(2), (0, Format::R32G32_SFLOAT, 24));
+    }
+
+    #[test]
+    fn guess_vertex_input_state_packed() {
+        let entry =
+            |location, component, base_type, num_components, is_64bit| ShaderInterfaceEntry {
+                location,
+                index: 0,
+                component,
+                name: None,
+                ty: ShaderInterfaceEntryType {
+                    base_type,
+                    num_components,
+                    num_elements: 1,
+                    is_64bit,
+                },
+                relaxed_precision: false,
+                flat: false,
+                is_patch: false,
+            };
+
+        let interface = ShaderInterface::from_entries(vec![
+            // Two floats packed into location 0.
+            entry(0, 0, NumericType::Float, 1, false),
+            entry(0, 1, NumericType::Float, 1, false),
+            // A `dvec3` in locations 1 and 2.
+            entry(1, 0, NumericType::Float, 3, true),
+            // A float and an int packed into location 3.
+            entry(3, 0, NumericType::Float, 1, false),
+            entry(3, 1, NumericType::Int, 1, false),
+            entry(4, 0, NumericType::Float, 2, false),
+        ])
+        .unwrap();
+        let vertex_input_state = interface.guess_vertex_input_state();
+
+        let attribute = |location: u32| {
+            let attribute = &vertex_input_state.attributes[&location];
+            (attribute.binding, attribute.format, attribute.offset)
+        };
+        assert_eq!(vertex_input_state.attributes.len(), 3);
+        assert_eq!(attribute(0), (0, Format::R32G32_SFLOAT, 0));
+        assert_eq!(attribute(1), (0, Format::R64G64B64_SFLOAT, 8));
+        assert_eq!(attribute(4), (0, Format::R32G32_SFLOAT, 32));
+        assert_eq!(vertex_input_state.bindings[&0].stride, 40);
+    }
+
+    #[test]
+    fn validate_push_constants() {
+        /*
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+        OpDecorate %arr ArrayStride 16
+        OpDecorate %pcs Block
+        OpMemberDecorate %pcs 0 Offset 0
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %float = OpTypeFloat 32
+        %v4 = OpTypeVector %float 4
+        %uint = OpTypeInt 32 0
+        %uint_8 = OpConstant %uint 8
+        %arr = OpTypeArray %v4 %uint_8
+        %pcs = OpTypeStruct %arr
+        %ppc = OpTypePointer PushConstant %pcs
+        %pc = OpVariable %ppc PushConstant
+        %main = OpFunction %void None %fn
+        %l = OpLabel
+        OpReturn
+        OpFunctionEnd
+        */
+        const MODULE: [u32; 77] = [
+            119734787, 65536, 0, 13, 0, 131089, 1, 196622, 0, 1, 327695, 5, 1, 1852399981, 0,
+            393232, 1, 17, 1, 1, 1, 262215, 2, 6, 16, 196679, 3, 2, 327752, 3, 0, 35, 0, 131091, 4,
+            196641, 5, 4, 196630, 6, 32, 262167, 7, 6, 4, 262165, 8, 32, 0, 262187, 8, 9, 8,
+            262172, 2, 7, 9, 196638, 3, 2, 262176, 10, 9, 3, 262203, 10, 11, 9, 327734, 4, 1, 0, 5,
+            131320, 12, 65789, 65592,
+        ];
+
+        let spirv = Spirv::new(&MODULE).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+        assert_eq!(info.push_constant_requirements.unwrap().size, 128);
+
+        let properties = Properties {
+            max_push_constants_size: 128,
+            ..Default::default()
+        };
+        assert!(info
+            .validate_push_constants_with_properties(&properties)
+            .is_ok());
+
+        let properties = Properties {
+            max_push_constants_size: 64,
+            ..Default::default()
+        };
+        assert!(info
+            .validate_push_constants_with_properties(&properties)
+            .is_err());
+    }
+
+    /*
+    #version 450
+
+    layout(set = 0, binding = 0) uniform Data {
+        vec4 color;
+    } data;
+
+    layout(location = 0) out vec4 v_color;
+
+    void main() {
+        v_color = data.color;
+    }
+    */
+    const LINK_VS: [u32; 100] = [
+        119734787, 65536, 0, 17, 0, 131089, 1, 196622, 0, 1, 393231, 0, 1, 1852399981, 0, 2,
+        262215, 2, 30, 0, 196679, 3, 2, 327752, 3, 0, 35, 0, 262215, 4, 34, 0, 262215, 4, 33, 0,
+        131091, 5, 196641, 6, 5, 196630, 7, 32, 262167, 8, 7, 4, 262165, 9, 32, 1, 196638, 3, 8,
+        262176, 10, 2, 3, 262203, 10, 4, 2, 262176, 11, 2, 8, 262176, 12, 3, 8, 262203, 12, 2, 3,
+        262187, 9, 13, 0, 327734, 5, 1, 0, 6, 131320, 14, 327745, 11, 15, 4, 13, 262205, 8, 16, 15,
+        196670, 2, 16, 65789, 65592,
+    ];
+
+    /*
+    #version 450
+
+    layout(set = 0, binding = 0) uniform Data {
+        vec4 color;
+    } data;
+
+    layout(location = 0) in vec4 v_color;
+    layout(location = 0) out vec4 f_color;
+
+    void main() {
+        f_color = v_color * data.color;
+    }
+    */
+    const LINK_FS: [u32; 125] = [
+        119734787, 65536, 0, 21, 0, 131089, 1, 196622, 0, 1, 458767, 4, 1, 1852399981, 0, 2, 3,
+        196624, 1, 7, 262215, 2, 30, 0, 262215, 3, 30, 0, 196679, 4, 2, 327752, 4, 0, 35, 0,
+        262215, 5, 34, 0, 262215, 5, 33, 0, 131091, 6, 196641, 7, 6, 196630, 8, 32, 262167, 9, 8,
+        4, 262165, 10, 32, 1, 196638, 4, 9, 262176, 11, 2, 4, 262203, 11, 5, 2, 262176, 12, 2, 9,
+        262176, 13, 1, 9, 262203, 13, 2, 1, 262176, 14, 3, 9, 262203, 14, 3, 3, 262187, 10, 15, 0,
+        327734, 6, 1, 0, 7, 131320, 16, 262205, 9, 17, 2, 327745, 12, 18, 5, 15, 262205, 9, 19, 18,
+        327813, 9, 20, 17, 19, 196670, 3, 20, 65789, 65592,
+    ];
+
+    /*
+    #version 450
+
+    layout(location = 0) in float v_value;
+    layout(location = 0) out vec4 f_color;
+
+    void main() {
+        f_color = vec4(v_value);
+    }
+    */
+    const LINK_FS_MISMATCHED: [u32; 79] = [
+        119734787, 65536, 0, 13, 0, 131089, 1, 196622, 0, 1, 458767, 4, 1, 1852399981, 0, 2, 3,
+        196624, 1, 7, 262215, 2, 30, 0, 262215, 3, 30, 0, 131091, 4, 196641, 5, 4, 196630, 6, 32,
+        262167, 7, 6, 4, 262176, 8, 1, 6, 262203, 8, 2, 1, 262176, 9, 3, 7, 262203, 9, 3, 3,
+        327734, 4, 1, 0, 5, 131320, 10, 262205, 6, 11, 2, 458832, 7, 12, 11, 11, 11, 11, 196670, 3,
+        12, 65789, 65592,
+    ];
+
+    #[test]
+    fn validate_fragment_inputs() {
+        /*
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint Fragment %main "main" %color %index
+        OpExecutionMode %main OriginUpperLeft
+        OpDecorate %color Location 0
+        OpDecorate %index Location 1
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %float = OpTypeFloat 32
+        %int = OpTypeInt 32 1
+        %v4 = OpTypeVector %float 4
+        %pv4 = OpTypePointer Input %v4
+        %pint = OpTypePointer Input %int
+        %color = OpVariable %pv4 Input
+        %index = OpVariable %pint Input
+        %main = OpFunction %void None %fn
+        %l = OpLabel
+        OpReturn
+        OpFunctionEnd
+        */
+        const INT_INPUT_WITHOUT_FLAT: [u32; 69] = [
+            119734787, 65536, 0, 12, 0, 131089, 1, 196622, 0, 1, 458767, 4, 1, 1852399981, 0, 2, 3,
+            196624, 1, 7, 262215, 2, 30, 0, 262215, 3, 30, 1, 131091, 4, 196641, 5, 4, 196630, 6,
+            32, 262165, 7, 32, 1, 262167, 8, 6, 4, 262176, 9, 1, 8, 262176, 10, 1, 7, 262203, 9, 2,
+            1, 262203, 10, 3, 1, 327734, 4, 1, 0, 5, 131320, 11, 65789, 65592,
+        ];
+
+        let spirv = Spirv::new(&INT_INPUT_WITHOUT_FLAT).unwrap();
+        let (_, mut info) = reflect::entry_points(&spirv).next().unwrap();
+        assert!(info.validate_fragment_inputs().is_err());
+
+        // With `flat` on the integer input, the inputs are valid.
+        let mut elements = info.input_interface.elements().to_vec();
+        elements
+            .iter_mut()
+            .filter(|element| element.location == 1)
+            .for_each(|element| element.flat = true);
+        info.input_interface = ShaderInterface::from_entries(elements).unwrap();
+        assert!(info.validate_fragment_inputs().is_ok());
+    }
+
+    #[test]
+    fn entry_point_link_with() {
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        let entry_point = |words: &[u32]| unsafe {
+            ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(words))
+                .unwrap()
+                .entry_point("main")
+                .unwrap()
+        };
+        let vs = entry_point(&LINK_VS);
+        let fs = entry_point(&LINK_FS);
+        let fs_mismatched = entry_point(&LINK_FS_MISMATCHED);
+
+        let linked = vs.link_with(&fs).unwrap();
+        assert_eq!(
+            linked.descriptor_binding_requirements[&(0, 0)].stages,
+            ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+        );
+        assert!(linked.push_constant_ranges.is_empty());
+
+        assert!(vs.link_with(&fs_mismatched).is_err());
+    }
+
+    #[test]
+    fn entry_point_interface_matches() {
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        let entry_point = |words: &[u32]| unsafe {
+            ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(words))
+                .unwrap()
+                .entry_point("main")
+                .unwrap()
+        };
+        let vs = entry_point(&LINK_VS);
+        let fs = entry_point(&LINK_FS);
+        let fs_mismatched = entry_point(&LINK_FS_MISMATCHED);
+
+        assert!(vs.interface_matches(&fs).is_ok());
+        assert!(vs.interface_matches(&fs_mismatched).is_err());
+    }
+
+    /*
+    ; SPIR-V 1.0
+    OpCapability Shader
+    OpMemoryModel Logical GLSL450
+    OpEntryPoint GLCompute %main "main"
+    OpExecutionMode %main LocalSize 1 1 1
+    OpDecorate %PushConstants Block
+    OpMemberDecorate %PushConstants 0 Offset 16
+    %void = OpTypeVoid
+    %fn = OpTypeFunction %void
+    %float = OpTypeFloat 32
+    %PushConstants = OpTypeStruct %float
+    %ptr = OpTypePointer PushConstant %PushConstants
+    %push_constants = OpVariable %ptr PushConstant
+    %main = OpFunction %void None %fn
+    %label = OpLabel
+    OpReturn
+    OpFunctionEnd
+    */
+    const PUSH_CONSTANT_OFFSET_CS: [u32; 57] = [
+        119734787, 65536, 0, 9, 0, 131089, 1, 196622, 0, 1, 327695, 5, 1, 1852399981, 0, 393232, 1,
+        17, 1, 1, 1, 196679, 2, 2, 327752, 2, 0, 35, 16, 131091, 3, 196641, 4, 3, 196630, 5, 32,
+        196638, 2, 5, 262176, 6, 9, 2, 262203, 6, 7, 9, 327734, 3, 1, 0, 4, 131320, 8, 65789,
+        65592,
+    ];
+
+    #[test]
+    fn entry_point_push_constant_size() {
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        let entry_point = |words: &[u32]| unsafe {
+            ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(words))
+                .unwrap()
+                .entry_point("main")
+                .unwrap()
+        };
+
+        assert_eq!(entry_point(&LINK_VS).push_constant_size(), 0);
+        assert_eq!(
+            entry_point(&PUSH_CONSTANT_OFFSET_CS).push_constant_size(),
+            20,
+        );
+    }
+
+    #[test]
+    fn entry_point_reachable_functions() {
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        let module =
+            unsafe { ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&CALL_CS)) }
+                .unwrap();
+        let entry_point = module.entry_point("main").unwrap();
+
+        let functions: Vec<_> = (entry_point.reachable_functions().into_iter())
+            .map(Id::as_raw)
+            .collect();
+        assert_eq!(functions, [1, 6]);
+    }
+
+    /*
+    ; SPIR-V 1.0
+    OpCapability Shader
+    OpMemoryModel Logical GLSL450
+    OpEntryPoint GLCompute %main "main"
+    OpExecutionMode %main LocalSize 1 1 1
+    %void = OpTypeVoid
+    %fn = OpTypeFunction %void
+    %main = OpFunction %void None %fn
+    %label = OpLabel
+    %call = OpFunctionCall %void %helper
+    OpReturn
+    OpFunctionEnd
+    %helper = OpFunction %void None %fn
+    %helper_label = OpLabel
+    OpReturn
+    OpFunctionEnd
+    */
+    const CALL_CS: [u32; 48] = [
+        119734787, 65536, 0, 8, 0, 131089, 1, 196622, 0, 1, 327695, 5, 1, 1852399981, 0, 393232, 1,
+        17, 1, 1, 1, 131091, 2, 196641, 3, 2, 327734, 2, 1, 0, 3, 131320, 4, 262201, 2, 5, 6,
+        65789, 65592, 327734, 2, 6, 0, 3, 131320, 7, 65789, 65592,
+    ];
+
+    #[test]
+    fn shader_module_new_validated() {
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        let validated_spirv =
+            ValidatedSpirv::new(&device, ShaderModuleCreateInfo::new(&CALL_CS)).unwrap();
+        let module =
+            unsafe { ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&CALL_CS)) }
+                .unwrap();
+
+        for _ in 0..2 {
+            let recreated =
+                unsafe { ShaderModule::new_validated(device.clone(), validated_spirv.clone()) }
+                    .unwrap();
+            assert!(recreated.content_eq(&module));
+
+            let entry_point = module.entry_point("main").unwrap();
+            let recreated_entry_point = recreated.entry_point("main").unwrap();
+            let (info, recreated_info) = (entry_point.info(), recreated_entry_point.info());
+            assert_eq!(info.name, recreated_info.name);
+            assert_eq!(info.execution_model, recreated_info.execution_model);
+            assert_eq!(info.local_size, recreated_info.local_size);
+            assert_eq!(
+                entry_point.reachable_functions(),
+                recreated_entry_point.reachable_functions(),
+            );
+        }
+    }
+
+    /*
+    ; SPIR-V 1.0
+    OpCapability Tessellation
+    OpMemoryModel Logical GLSL450
+    OpEntryPoint TessellationControl %main "main"
+    OpExecutionMode %main OutputVertices 3
+    %void = OpTypeVoid
+    %fn = OpTypeFunction %void
+    %main = OpFunction %void None %fn
+    %label = OpLabel
+    OpReturn
+    OpFunctionEnd
+    */
+    const LINK_TCS: [u32; 33] = [
+        119734787, 65536, 0, 5, 0, 131089, 3, 196622, 0, 1, 327695, 1, 1, 1852399981, 0, 262160, 1,
+        26, 3, 131091, 2, 196641, 3, 2, 327734, 2, 1, 0, 3, 131320, 4, 65789, 65592,
+    ];
+
+    /*
+    ; SPIR-V 1.0
+    OpCapability Tessellation
+    OpMemoryModel Logical GLSL450
+    OpEntryPoint TessellationEvaluation %main "main"
+    OpExecutionMode %main Triangles
+    OpExecutionMode %main OutputVertices 3
+    %void = OpTypeVoid
+    %fn = OpTypeFunction %void
+    %main = OpFunction %void None %fn
+    %label = OpLabel
+    OpReturn
+    OpFunctionEnd
+    */
+    const LINK_TES: [u32; 36] = [
+        119734787, 65536, 0, 5, 0, 131089, 3, 196622, 0, 1, 327695, 2, 1, 1852399981, 0, 196624, 1,
+        22, 262160, 1, 26, 3, 131091, 2, 196641, 3, 2, 327734, 2, 1, 0, 3, 131320, 4, 65789, 65592,
+    ];
+
+    // Same as `LINK_TES`, but with `OutputVertices 4`.
+    const LINK_TES_MISMATCHED: [u32; 36] = [
+        119734787, 65536, 0, 5, 0, 131089, 3, 196622, 0, 1, 327695, 2, 1, 1852399981, 0, 196624, 1,
+        22, 262160, 1, 26, 4, 131091, 2, 196641, 3, 2, 327734, 2, 1, 0, 3, 131320, 4, 65789, 65592,
+    ];
+
+    #[test]
+    fn content_hash() {
+        use super::spirv_content_hash;
+
+        assert_eq!(spirv_content_hash(&[]), 0xcbf29ce484222325);
+        assert_eq!(spirv_content_hash(&[0x07230203]), 0xdb6aa387e9e0200a);
+        assert_ne!(spirv_content_hash(&LINK_VS), spirv_content_hash(&LINK_FS));
+
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        let module = |words: &[u32]| unsafe {
+            ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(words)).unwrap()
+        };
+        let vs = module(&LINK_VS);
+        let vs_again = module(&LINK_VS);
+        let fs = module(&LINK_FS);
+
+        assert_ne!(vs.id(), vs_again.id());
+        assert_eq!(vs.content_hash(), vs_again.content_hash());
+        assert!(vs.content_eq(&vs_again));
+        assert_ne!(vs.content_hash(), fs.content_hash());
+        assert!(!vs.content_eq(&fs));
+    }
+
+    #[test]
+    fn descriptor_binding_requirements_to_layout_binding() {
+        let vertex = DescriptorBindingRequirements {
+            descriptor_types: vec![
+                DescriptorType::StorageBuffer,
+                DescriptorType::StorageBufferDynamic,
+            ],
+            descriptor_count: Some(1),
+            stages: ShaderStages::VERTEX,
+            ..Default::default()
+        };
+        let fragment = DescriptorBindingRequirements {
+            stages: ShaderStages::FRAGMENT,
+            ..vertex.clone()
+        };
+
+        let mut reqs = vertex;
+        reqs.merge(&fragment).unwrap();
+
+        let binding = reqs.to_layout_binding(16);
+        assert_eq!(binding.descriptor_type, DescriptorType::StorageBuffer);
+        assert_eq!(binding.descriptor_count, 1);
+        assert_eq!(
+            binding.stages,
+            ShaderStages::VERTEX | ShaderStages::FRAGMENT
+        );
+
+        // A runtime-sized array uses the provided count.
+        reqs.descriptor_count = None;
+        assert_eq!(reqs.to_layout_binding(16).descriptor_count, 16);
+    }
+
+    #[test]
+    fn merge_push_constant_ranges() {
+        use super::merge_push_constant_ranges;
+
+        let spirv = Spirv::new(&LINK_VS).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+        let with_range = |offset, size, stages| EntryPointInfo {
+            push_constant_requirements: Some(PushConstantRange {
+                stages,
+                offset,
+                size,
+            }),
+            ..info.clone()
+        };
+
+        let vertex = with_range(0, 64, ShaderStages::VERTEX);
+        let fragment = with_range(64, 16, ShaderStages::FRAGMENT);
+        assert_eq!(
+            merge_push_constant_ranges(&[&fragment, &vertex]),
+            [
+                PushConstantRange {
+                    stages: ShaderStages::VERTEX,
+                    offset: 0,
+                    size: 64,
+                },
+                PushConstantRange {
+                    stages: ShaderStages::FRAGMENT,
+                    offset: 64,
+                    size: 16,
+                },
+            ],
+        );
+
+        let fragment_overlapping = with_range(32, 48, ShaderStages::FRAGMENT);
+        assert_eq!(
+            merge_push_constant_ranges(&[&vertex, &fragment_overlapping, &info]),
+            [PushConstantRange {
+                stages: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                offset: 0,
+                size: 80,
+            }],
+        );
+    }
+
+    #[test]
+    fn entry_point_link_with_output_vertices() {
+        let output_vertices = |words: &[u32]| {
+            let spirv = Spirv::new(words).unwrap();
+            let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+            info.output_vertices
+        };
+        assert_eq!(output_vertices(&LINK_TCS), Some(3));
+        assert_eq!(output_vertices(&LINK_TES), Some(3));
+        assert_eq!(output_vertices(&LINK_TES_MISMATCHED), Some(4));
+        assert_eq!(output_vertices(&LINK_VS), None);
+
+        let (device, _queue) = gfx_dev_and_queue!(tessellation_shader);
+
+        let entry_point = |words: &[u32]| unsafe {
+            ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(words))
+                .unwrap()
+                .entry_point("main")
+                .unwrap()
+        };
+        let tcs = entry_point(&LINK_TCS);
+        let tes = entry_point(&LINK_TES);
+        let tes_mismatched = entry_point(&LINK_TES_MISMATCHED);
+
+        assert!(tcs.link_with(&tes).is_ok());
+        assert!(tcs.link_with(&tes_mismatched).is_err());
+    }
+
+    /*
+    ; SPIR-V 1.0
+    OpCapability Tessellation
+    OpMemoryModel Logical GLSL450
+    OpEntryPoint TessellationControl %main "main" %patch_out %vertex_out
+    OpExecutionMode %main OutputVertices 3
+    OpDecorate %patch_out Patch
+    OpDecorate %patch_out Location 0
+    OpDecorate %vertex_out Location 0
+    %void = OpTypeVoid
+    %fn = OpTypeFunction %void
+    %float = OpTypeFloat 32
+    %v4float = OpTypeVector %float 4
+    %uint = OpTypeInt 32 0
+    %uint_3 = OpConstant %uint 3
+    %arr = OpTypeArray %v4float %uint_3
+    %ptr_float = OpTypePointer Output %float
+    %ptr_arr = OpTypePointer Output %arr
+    %patch_out = OpVariable %ptr_float Output
+    %vertex_out = OpVariable %ptr_arr Output
+    %main = OpFunction %void None %fn
+    %label = OpLabel
+    OpReturn
+    OpFunctionEnd
+    */
+    const LINK_PATCH_TCS: [u32; 81] = [
+        119734787, 65536, 0, 14, 0, 131089, 3, 196622, 0, 1, 458767, 1, 1, 1852399981, 0, 2, 3,
+        262160, 1, 26, 3, 196679, 2, 15, 262215, 2, 30, 0, 262215, 3, 30, 0, 131091, 4, 196641, 5,
+        4, 196630, 6, 32, 262167, 7, 6, 4, 262165, 8, 32, 0, 262187, 8, 9, 3, 262172, 10, 7, 9,
+        262176, 11, 3, 6, 262176, 12, 3, 10, 262203, 11, 2, 3, 262203, 12, 3, 3, 327734, 4, 1, 0,
+        5, 131320, 13, 65789, 65592,
+    ];
+
+    /*
+    ; SPIR-V 1.0
+    OpCapability Tessellation
+    OpMemoryModel Logical GLSL450
+    OpEntryPoint TessellationEvaluation %main "main" %patch_in %vertex_in
+    OpExecutionMode %main Triangles
+    OpDecorate %patch_in Patch
+    OpDecorate %patch_in Location 0
+    OpDecorate %vertex_in Location 0
+    %void = OpTypeVoid
+    %fn = OpTypeFunction %void
+    %float = OpTypeFloat 32
+    %v4float = OpTypeVector %float 4
+    %uint = OpTypeInt 32 0
+    %uint_32 = OpConstant %uint 32
+    %arr = OpTypeArray %v4float %uint_32
+    %ptr_float = OpTypePointer Input %float
+    %ptr_arr = OpTypePointer Input %arr
+    %patch_in = OpVariable %ptr_float Input
+    %vertex_in = OpVariable %ptr_arr Input
+    %main = OpFunction %void None %fn
+    %label = OpLabel
+    OpReturn
+    OpFunctionEnd
+    */
+    const LINK_PATCH_TES: [u32; 80] = [
+        119734787, 65536, 0, 14, 0, 131089, 3, 196622, 0, 1, 458767, 2, 1, 1852399981, 0, 2, 3,
+        196624, 1, 22, 196679, 2, 15, 262215, 2, 30, 0, 262215, 3, 30, 0, 131091, 4, 196641, 5, 4,
+        196630, 6, 32, 262167, 7, 6, 4, 262165, 8, 32, 0, 262187, 8, 9, 32, 262172, 10, 7, 9,
+        262176, 11, 1, 6, 262176, 12, 1, 10, 262203, 11, 2, 1, 262203, 12, 3, 1, 327734, 4, 1, 0,
+        5, 131320, 13, 65789, 65592,
+    ];
+
+    // The same as `LINK_PATCH_TES`, but with the per-patch input at location 1.
+    /*
+    ; SPIR-V 1.0
+    OpCapability Tessellation
+    OpMemoryModel Logical GLSL450
+    OpEntryPoint TessellationEvaluation %main "main" %patch_in %vertex_in
+    OpExecutionMode %main Triangles
+    OpDecorate %patch_in Patch
+    OpDecorate %patch_in Location 1
+    OpDecorate %vertex_in Location 0
+    %void = OpTypeVoid
+    %fn = OpTypeFunction %void
+    %float = OpTypeFloat 32
+    %v4float = OpTypeVector %float 4
+    %uint = OpTypeInt 32 0
+    %uint_32 = OpConstant %uint 32
+    %arr = OpTypeArray %v4float %uint_32
+    %ptr_float = OpTypePointer Input %float
+    %ptr_arr = OpTypePointer Input %arr
+    %patch_in = OpVariable %ptr_float Input
+    %vertex_in = OpVariable %ptr_arr Input
+    %main = OpFunction %void None %fn
+    %label = OpLabel
+    OpReturn
+    OpFunctionEnd
+    */
+    const LINK_PATCH_TES_MISMATCHED: [u32; 80] = [
+        119734787, 65536, 0, 14, 0, 131089, 3, 196622, 0, 1, 458767, 2, 1, 1852399981, 0, 2, 3,
+        196624, 1, 22, 196679, 2, 15, 262215, 2, 30, 1, 262215, 3, 30, 0, 131091, 4, 196641, 5, 4,
+        196630, 6, 32, 262167, 7, 6, 4, 262165, 8, 32, 0, 262187, 8, 9, 32, 262172, 10, 7, 9,
+        262176, 11, 1, 6, 262176, 12, 1, 10, 262203, 11, 2, 1, 262203, 12, 3, 1, 327734, 4, 1, 0,
+        5, 131320, 13, 65789, 65592,
+    ];
+
+    #[test]
+    fn entry_point_link_with_patch() {
+        let info = |words: &[u32]| {
+            let spirv = Spirv::new(words).unwrap();
+            let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+            info
+        };
+        let tcs_info = info(&LINK_PATCH_TCS);
+        let tes_info = info(&LINK_PATCH_TES);
+
+        // Both interfaces have a per-patch and a per-vertex element at location 0.
+        let output = tcs_info.output_interface.elements();
+        assert_eq!(output.len(), 2);
+        assert!(output.iter().any(|element| element.is_patch));
+        assert!(output.iter().any(|element| !element.is_patch));
+        assert!(tes_info
+            .input_interface
+            .matches(&tcs_info.output_interface)
+            .is_ok());
+        assert!(info(&LINK_PATCH_TES_MISMATCHED)
+            .input_interface
+            .matches(&tcs_info.output_interface)
+            .is_err());
+
+        let (device, _queue) = gfx_dev_and_queue!(tessellation_shader);
+
+        let entry_point = |words: &[u32]| unsafe {
+            ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(words))
+                .unwrap()
+                .entry_point("main")
+                .unwrap()
+        };
+        let tcs = entry_point(&LINK_PATCH_TCS);
+        let tes = entry_point(&LINK_PATCH_TES);
+        let tes_mismatched = entry_point(&LINK_PATCH_TES_MISMATCHED);
+
+        assert!(tcs.link_with(&tes).is_ok());
+        assert!(tcs.link_with(&tes_mismatched).is_err());
+    }
+
+    /*
+    ; SPIR-V 1.3
+    OpCapability Shader
+    OpMemoryModel Logical GLSL450
+    OpEntryPoint GLCompute %main "main"
+    OpExecutionModeId %main LocalSizeId %uint_8 %uint_4 %uint_1
+    OpDecorate %workgroup_size BuiltIn WorkgroupSize
+    %void = OpTypeVoid
+    %fn = OpTypeFunction %void
+    %uint = OpTypeInt 32 0
+    %v3uint = OpTypeVector %uint 3
+    %uint_1 = OpConstant %uint 1
+    %uint_2 = OpConstant %uint 2
+    %uint_4 = OpConstant %uint 4
+    %uint_8 = OpConstant %uint 8
+    %workgroup_size = OpConstantComposite %v3uint %uint_2 %uint_2 %uint_2
+    %main = OpFunction %void None %fn
+    %label = OpLabel
+    OpReturn
+    OpFunctionEnd
+    */
+    const LOCAL_SIZE_ID_AND_WORKGROUP_SIZE: [u32; 69] = [
+        119734787, 66304, 0, 12, 0, 131089, 1, 196622, 0, 1, 327695, 5, 1, 1852399981, 0, 393547,
+        1, 38, 2, 3, 4, 262215, 5, 11, 25, 131091, 6, 196641, 7, 6, 262165, 8, 32, 0, 262167, 9, 8,
+        3, 262187, 8, 4, 1, 262187, 8, 10, 2, 262187, 8, 3, 4, 262187, 8, 2, 8, 393260, 9, 5, 10,
+        10, 10, 327734, 6, 1, 0, 7, 131320, 11, 65789, 65592,
+    ];
+
+    /*
+    ; SPIR-V 1.0
+    OpCapability Shader
+    OpMemoryModel Logical GLSL450
+    OpEntryPoint GLCompute %main "main"
+    OpExecutionMode %main LocalSize 8 4 1
+    OpDecorate %workgroup_size BuiltIn WorkgroupSize
+    %void = OpTypeVoid
+    %fn = OpTypeFunction %void
+    %uint = OpTypeInt 32 0
+    %v3uint = OpTypeVector %uint 3
+    %uint_2 = OpConstant %uint 2
+    %workgroup_size = OpConstantComposite %v3uint %uint_2 %uint_2 %uint_2
+    %main = OpFunction %void None %fn
+    %label = OpLabel
+    OpReturn
+    OpFunctionEnd
+    */
+    const LOCAL_SIZE_AND_WORKGROUP_SIZE: [u32; 57] = [
+        119734787, 65536, 0, 9, 0, 131089, 1, 196622, 0, 1, 327695, 5, 1, 1852399981, 0, 393232, 1,
+        17, 8, 4, 1, 262215, 2, 11, 25, 131091, 3, 196641, 4, 3, 262165, 5, 32, 0, 262167, 6, 5, 3,
+        262187, 5, 7, 2, 393260, 6, 2, 7, 7, 7, 327734, 3, 1, 0, 4, 131320, 8, 65789, 65592,
+    ];
+
+    #[test]
+    fn workgroup_size_precedence() {
+        // The `WorkgroupSize` builtin overrides both `LocalSizeId` and `LocalSize`.
+        let spirv = Spirv::new(&LOCAL_SIZE_ID_AND_WORKGROUP_SIZE).unwrap();
+        assert_eq!(spirv.version(), Version::V1_3);
+
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+        assert_eq!(info.local_size, Some([2, 2, 2]));
+
+        let spirv = Spirv::new(&LOCAL_SIZE_AND_WORKGROUP_SIZE).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+        assert_eq!(info.local_size, Some([2, 2, 2]));
+    }
+
+    /*
+    ; SPIR-V 1.3
+    OpCapability Shader
+    OpCapability CooperativeMatrixKHR
+    OpExtension "SPV_KHR_cooperative_matrix"
+    OpMemoryModel Logical GLSL450
+    OpEntryPoint GLCompute %main "main"
+    OpEntryPoint GLCompute %other "other"
+    OpExecutionMode %main LocalSize 32 1 1
+    OpExecutionMode %other LocalSize 32 1 1
+    %void = OpTypeVoid
+    %fn = OpTypeFunction %void
+    %half = OpTypeFloat 16
+    %uint = OpTypeInt 32 0
+    %uint_0 = OpConstant %uint 0
+    %uint_2 = OpConstant %uint 2
+    %uint_3 = OpConstant %uint 3
+    %uint_16 = OpConstant %uint 16
+    %rows = OpSpecConstant %uint 8
+    %mat_a = OpTypeCooperativeMatrixKHR %half %uint_3 %uint_16 %uint_16 %uint_0
+    %mat_acc = OpTypeCooperativeMatrixKHR %uint %uint_3 %rows %uint_16 %uint_2
+    %ptr_a = OpTypePointer Function %mat_a
+    %ptr_acc = OpTypePointer Function %mat_acc
+    %main = OpFunction %void None %fn
+    %label = OpLabel
+    %a = OpVariable %ptr_a Function
+    %acc = OpVariable %ptr_acc Function
+    OpReturn
+    OpFunctionEnd
+    %other = OpFunction %void None %fn
+    %other_label = OpLabel
+    OpReturn
+    OpFunctionEnd
+    */
+    const COOPERATIVE_MATRIX: [u32; 122] = [
+        119734787, 66304, 0, 20, 0, 131089, 1, 131089, 6022, 524298, 1599492179, 1599227979,
+        1886351203, 1952543333, 1600484969, 1920229741, 30825, 196622, 0, 1, 327695, 5, 1,
+        1852399981, 0, 327695, 5, 2, 1701344367, 114, 393232, 1, 17, 32, 1, 1, 393232, 2, 17, 32,
+        1, 1, 131091, 3, 196641, 4, 3, 196630, 5, 16, 262165, 6, 32, 0, 262187, 6, 7, 0, 262187, 6,
+        8, 2, 262187, 6, 9, 3, 262187, 6, 10, 16, 262194, 6, 11, 8, 463208, 12, 5, 9, 10, 10, 7,
+        463208, 13, 6, 9, 11, 10, 8, 262176, 14, 7, 12, 262176, 15, 7, 13, 327734, 3, 1, 0, 4,
+        131320, 16, 262203, 14, 17, 7, 262203, 15, 18, 7, 65789, 65592, 327734, 3, 2, 0, 4, 131320,
+        19, 65789, 65592,
+    ];
+
+    #[test]
+    fn cooperative_matrix_types() {
+        use super::{
+            spirv::{CooperativeMatrixUse, Scope},
+            CooperativeMatrixType,
+        };
+
+        let spirv = Spirv::new(&COOPERATIVE_MATRIX).unwrap();
+        let infos: HashMap<_, _> = reflect::entry_points(&spirv)
+            .map(|(_, info)| (info.name.clone(), info))
+            .collect();
+        assert_eq!(
+            infos["main"].cooperative_matrix_types,
+            [
+                CooperativeMatrixType {
+                    component_type: NumericType::Float,
+                    component_width: 16,
+                    scope: Some(Scope::Subgroup),
+                    rows: Some(16),
+                    columns: Some(16),
+                    usage: Some(CooperativeMatrixUse::MatrixAKHR),
+                },
+                // The number of rows is a specialization constant.
+                CooperativeMatrixType {
+                    component_type: NumericType::Uint,
+                    component_width: 32,
+                    scope: Some(Scope::Subgroup),
+                    rows: None,
+                    columns: Some(16),
+                    usage: Some(CooperativeMatrixUse::MatrixAccumulatorKHR),
+                },
+            ],
+        );
+
+        // The types are only reported for the entry point that uses them.
+        assert!(infos["other"].cooperative_matrix_types.is_empty());
+
+        let spirv = Spirv::new(&LOCAL_SIZE_ID_AND_WORKGROUP_SIZE).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+        assert!(info.cooperative_matrix_types.is_empty());
+    }
+
+    #[test]
+    fn ext_inst_imports() {
+        let spirv = Spirv::new(&SPEC_CONSTANT_MODULE).unwrap();
+        assert_eq!(spirv.ext_inst_imports(), ["GLSL.std.450"]);
+    }
+
+    #[test]
+    fn validate_with_capabilities() {
+        /*
+        ; SPIR-V 1.3
+        OpCapability Shader
+        OpCapability Int64
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %long = OpTypeInt 64 1
+        %main = OpFunction %void None %fn
+        %l = OpLabel
+        OpReturn
+        OpFunctionEnd
+        */
+        const MODULE: [u32; 41] = [
+            119734787, 66304, 0, 6, 0, 131089, 1, 131089, 11, 196622, 0, 1, 327695, 5, 1,
+            1852399981, 0, 393232, 1, 17, 1, 1, 1, 131091, 2, 196641, 3, 2, 262165, 4, 64, 1,
+            327734, 2, 1, 0, 3, 131320, 5, 65789, 65592,
+        ];
+
+        let create_info = ShaderModuleCreateInfo::new(&MODULE);
+        let enabled_extensions = DeviceExtensions::empty();
+        let properties = Properties::default();
+        let no_features = Features::empty();
+        let int64_features = Features {
+            shader_int64: true,
+            ..Features::empty()
+        };
+
+        // SPIR-V 1.3 requires Vulkan 1.1.
+        let capabilities = DeviceCapabilities {
+            api_version: Version::V1_0,
+            enabled_extensions: &enabled_extensions,
+            enabled_features: &int64_features,
+            properties: &properties,
+        };
+        assert!(create_info
+            .validate_with_capabilities(&capabilities)
+            .is_err());
+
+        // The `Int64` capability requires the `shader_int64` feature.
+        let capabilities = DeviceCapabilities {
+            api_version: Version::V1_1,
+            enabled_features: &no_features,
+            ..capabilities
+        };
+        assert!(create_info
+            .validate_with_capabilities(&capabilities)
+            .is_err());
+
+        let capabilities = DeviceCapabilities {
+            enabled_features: &int64_features,
+            ..capabilities
+        };
+        assert!(create_info
+            .validate_with_capabilities(&capabilities)
+            .is_ok());
+    }
+
+    #[test]
+    fn unknown_sampled_image() {
+        /*
+        OpCapability Shader
+        OpCapability StorageImageReadWithoutFormat
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main" %read_image %unused_image
+        OpExecutionMode %main LocalSize 1 1 1
+        OpDecorate %read_image DescriptorSet 0
+        OpDecorate %read_image Binding 0
+        OpDecorate %unused_image DescriptorSet 0
+        OpDecorate %unused_image Binding 1
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %float = OpTypeFloat 32
+        %v4float = OpTypeVector %float 4
+        %int = OpTypeInt 32 1
+        %v2int = OpTypeVector %int 2
+        %image = OpTypeImage %float 2D 0 0 0 0 Unknown
+        %ptr = OpTypePointer UniformConstant %image
+        %read_image = OpVariable %ptr UniformConstant
+        %unused_image = OpVariable %ptr UniformConstant
+        %int_0 = OpConstant %int 0
+        %coord = OpConstantComposite %v2int %int_0 %int_0
+        %main = OpFunction %void None %fn
+        %label = OpLabel
+        %loaded = OpLoad %image %read_image
+        %texel = OpImageRead %v4float %loaded %coord
+        %loaded_unused = OpLoad %image %unused_image
+        OpReturn
+        OpFunctionEnd
+        */
+        const MODULE: [u32; 113] = [
+            119734787, 65536, 0, 18, 0, 131089, 1, 131089, 55, 196622, 0, 1, 458767, 5, 1,
+            1852399981, 0, 2, 3, 393232, 1, 17, 1, 1, 1, 262215, 2, 34, 0, 262215, 2, 33, 0,
+            262215, 3, 34, 0, 262215, 3, 33, 1, 131091, 4, 196641, 5, 4, 196630, 6, 32, 262167, 7,
+            6, 4, 262165, 8, 32, 1, 262167, 9, 8, 2, 589849, 10, 6, 1, 0, 0, 0, 0, 0, 262176, 11,
+            0, 10, 262203, 11, 2, 0, 262203, 11, 3, 0, 262187, 8, 12, 0, 327724, 9, 13, 12, 12,
+            327734, 4, 1, 0, 5, 131320, 14, 262205, 10, 15, 2, 327778, 7, 16, 15, 13, 262205, 10,
+            17, 3, 65789, 65592,
+        ];
+
+        let spirv = Spirv::new(&MODULE).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        // Used by `OpImageRead`, so it must be a storage image.
+        assert_eq!(
+            info.descriptor_binding_requirements[&(0, 0)].descriptor_types,
+            [DescriptorType::StorageImage],
+        );
+
+        // Not used in a way that determines the type, so both are allowed.
+        assert_eq!(
+            info.descriptor_binding_requirements[&(0, 1)].descriptor_types,
+            [DescriptorType::SampledImage, DescriptorType::StorageImage],
+        );
+    }
+
+    fn interface(entries: &[(u32, NumericType, u32)]) -> ShaderInterface {
+        let elements = entries
+            .iter()
+            .map(
+                |&(location, base_type, num_components)| ShaderInterfaceEntry {
+                    location,
+                    index: 0,
+                    component: 0,
+                    name: None,
+                    ty: ShaderInterfaceEntryType {
+                        base_type,
+                        num_components,
+                        num_elements: 1,
+                        is_64bit: false,
+                    },
+                    relaxed_precision: false,
+                    flat: false,
+                    is_patch: false,
+                },
+            )
+            .collect();
+
+        unsafe { ShaderInterface::new_unchecked(elements) }
+    }
+
+    #[test]
+    fn shader_interface_entry_type_format() {
+        let ty = |base_type, num_components, num_elements| ShaderInterfaceEntryType {
+            base_type,
+            num_components,
+            num_elements,
+            is_64bit: false,
+        };
+
+        assert_eq!(
+            ty(NumericType::Float, 2, 1).format(),
+            Some(Format::R32G32_SFLOAT),
+        );
+        assert_eq!(
+            ty(NumericType::Int, 4, 1).format(),
+            Some(Format::R32G32B32A32_SINT),
+        );
+        assert_eq!(
+            ty(NumericType::Float, 1, 1).format(),
+            Some(Format::R32_SFLOAT)
+        );
+
+        // A `mat4` spans four locations.
+        assert_eq!(ty(NumericType::Float, 4, 4).format(), None);
+    }
+
+    #[test]
+    fn shader_interface_eq_ignores_names() {
+        let mut a = interface(&[(0, NumericType::Float, 4), (1, NumericType::Uint, 1)]);
+        let mut b = interface(&[(1, NumericType::Uint, 1), (0, NumericType::Float, 4)]);
+        a.elements[0].name = Some("v_color".into());
+        b.elements[1].name = Some("color".into());
+
+        let hash = |interface: &ShaderInterface| {
+            let mut hasher = DefaultHasher::new();
+            interface.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+        assert_ne!(a, interface(&[(0, NumericType::Float, 4)]));
+    }
+
+    #[test]
+    fn shader_interface_with_location_offset() {
+        let a = interface(&[(0, NumericType::Float, 4), (1, NumericType::Uint, 1)]);
+
+        let offset = a.with_location_offset(3).unwrap();
+        assert_eq!(
+            offset
+                .elements()
+                .iter()
+                .map(|element| element.location)
+                .collect::<Vec<_>>(),
+            [3, 4],
+        );
+        assert_eq!(
+            offset,
+            interface(&[(3, NumericType::Float, 4), (4, NumericType::Uint, 1)]),
+        );
+
+        assert!(a.with_location_offset(u32::MAX).is_err());
+        assert!(a.with_location_offset(u32::MAX - 2).is_ok());
+    }
+
+    #[test]
+    fn shader_interface_matches_all() {
+        let a = interface(&[
+            (0, NumericType::Float, 4),
+            (1, NumericType::Float, 2),
+            (2, NumericType::Int, 1),
+            (3, NumericType::Uint, 1),
+        ]);
+        let b = interface(&[
+            (0, NumericType::Float, 3),
+            (1, NumericType::Uint, 2),
+            (2, NumericType::Int, 1),
+            (4, NumericType::Uint, 1),
+        ]);
+
+        assert!(a.matches(&a).is_ok());
+        assert!(a.matches_all(&a).is_ok());
+        assert!(a.matches(&b).is_err());
+
+        // Locations 0 and 1 have different types, location 3 is missing.
+        let errors = a.matches_all(&b).unwrap_err();
+        assert_eq!(errors.len(), 3);
+    }
+
+    #[test]
+    fn shader_interface_64bit_component() {
+        let double = |component| ShaderInterfaceEntry {
+            location: 0,
+            index: 0,
+            component,
+            name: None,
+            ty: ShaderInterfaceEntryType {
+                base_type: NumericType::Float,
+                num_components: 1,
+                num_elements: 1,
+                is_64bit: true,
+            },
+            relaxed_precision: false,
+            flat: false,
+            is_patch: false,
+        };
+
+        let valid = ShaderInterface::from_entries(vec![double(2)]).unwrap();
+        assert!(valid.matches(&valid).is_ok());
+        assert!(ShaderInterface::from_entries(vec![double(1)]).is_err());
+
+        let invalid = unsafe { ShaderInterface::new_unchecked(vec![double(1)]) };
+        assert!(invalid.matches(&invalid).is_err());
+    }
+
+    #[test]
+    fn shader_interface_component_overlap() {
+        let entry = |location, component, num_components, is_64bit| ShaderInterfaceEntry {
+            location,
+            index: 0,
+            component,
+            name: None,
+            ty: ShaderInterfaceEntryType {
+                base_type: NumericType::Float,
+                num_components,
+                num_elements: 1,
+                is_64bit,
+            },
+            relaxed_precision: false,
+            flat: false,
+            is_patch: false,
+        };
+
+        // Two `vec2`s packed into the same location.
+        assert!(
+            ShaderInterface::from_entries(vec![entry(0, 0, 2, false), entry(0, 2, 2, false)])
+                .is_ok()
+        );
+        assert!(
+            ShaderInterface::from_entries(vec![entry(0, 0, 2, false), entry(0, 1, 2, false)])
+                .is_err()
+        );
+
+        // A `dvec3` occupies all of location 0 and components 0 and 1 of location 1.
+        assert_eq!(entry(0, 0, 3, true).ty.num_locations(), 2);
+        assert!(
+            ShaderInterface::from_entries(vec![entry(0, 0, 3, true), entry(1, 2, 2, false)])
+                .is_ok()
+        );
+        assert!(
+            ShaderInterface::from_entries(vec![entry(0, 0, 3, true), entry(1, 1, 1, false)])
+                .is_err()
+        );
+        assert!(ShaderInterface::from_entries(vec![entry(0, 2, 3, true)]).is_err());
+
+        // A `dvec4` occupies all of locations 0 and 1.
+        assert!(ShaderInterface::from_entries(vec![entry(0, 0, 4, true)]).is_ok());
+        assert!(
+            ShaderInterface::from_entries(vec![entry(0, 0, 4, true), entry(1, 3, 1, false)])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn capability_requirements() {
+        use super::{spirv::Capability, spirv_capability_requirements};
+        use crate::{Requires, RequiresAllOf, RequiresOneOf};
+
+        assert_eq!(
+            spirv_capability_requirements(Capability::Geometry),
+            Some(RequiresOneOf(&[RequiresAllOf(&[Requires::Feature(
+                "geometry_shader"
+            )])])),
+        );
+        assert_eq!(
+            spirv_capability_requirements(Capability::Shader),
+            Some(RequiresOneOf(&[])),
+        );
+        assert_eq!(spirv_capability_requirements(Capability::Kernel), None);
+    }
+
+    #[test]
+    fn shader_stages_from_str() {
+        assert_eq!(
+            "vertex,fragment".parse::<ShaderStages>().unwrap(),
+            ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+        );
+        assert_eq!(
+            " Compute , tessellation_control"
+                .parse::<ShaderStages>()
+                .unwrap(),
+            ShaderStages::COMPUTE | ShaderStages::TESSELLATION_CONTROL,
+        );
+        assert_eq!("".parse::<ShaderStages>().unwrap(), ShaderStages::empty());
+
+        assert!("vertex,pixel".parse::<ShaderStages>().is_err());
+        assert!("tessellation control".parse::<ShaderStages>().is_err());
+
+        let stages = ShaderStages::VERTEX | ShaderStages::MESH | ShaderStages::SUBPASS_SHADING;
+        assert_eq!(stages.to_string(), "vertex,mesh,subpass_shading");
+        assert_eq!(stages.to_string().parse::<ShaderStages>().unwrap(), stages);
+        assert_eq!(ShaderStages::empty().to_string(), "");
+    }
+
+    #[test]
+    fn shader_stages_descriptor_accessing_stages() {
+        let descriptor_accessing = ShaderStages::all_graphics()
+            | ShaderStages::COMPUTE
+            | ShaderStages::RAYGEN
+            | ShaderStages::ANY_HIT
+            | ShaderStages::CLOSEST_HIT
+            | ShaderStages::MISS
+            | ShaderStages::INTERSECTION
+            | ShaderStages::CALLABLE
+            | ShaderStages::TASK
+            | ShaderStages::MESH;
+
+        assert_eq!(
+            (descriptor_accessing | ShaderStages::SUBPASS_SHADING).descriptor_accessing_stages(),
+            descriptor_accessing,
+        );
+        assert_eq!(
+            (ShaderStages::FRAGMENT | ShaderStages::SUBPASS_SHADING).descriptor_accessing_stages(),
+            ShaderStages::FRAGMENT,
+        );
+        assert!(ShaderStages::SUBPASS_SHADING
+            .descriptor_accessing_stages()
+            .is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn shader_stages_serde() {
+        use super::ShaderStage;
+
+        let stages = ShaderStages::VERTEX | ShaderStages::FRAGMENT | ShaderStages::SUBPASS_SHADING;
+        let json = serde_json::to_string(&stages).unwrap();
+        assert_eq!(json, r#"["vertex","fragment","subpass_shading"]"#);
+        assert_eq!(serde_json::from_str::<ShaderStages>(&json).unwrap(), stages);
+        assert!(serde_json::from_str::<ShaderStages>(r#"["pixel"]"#).is_err());
+        assert_eq!(
+            serde_json::from_str::<ShaderStages>("[]").unwrap(),
+            ShaderStages::empty(),
+        );
+
+        for stage in ShaderStages(ShaderStages::all_raw()) {
+            let json = serde_json::to_string(&stage).unwrap();
+            assert_eq!(serde_json::from_str::<ShaderStage>(&json).unwrap(), stage);
+        }
+
+        assert_eq!(
+            serde_json::to_string(&ShaderStage::SubpassShading).unwrap(),
+            r#""subpass_shading""#,
+        );
+        assert!(serde_json::from_str::<ShaderStage>(r#""pixel""#).is_err());
+    }
+
+    #[test]
+    fn specialization_constant_u64_bits() {
+        for value in [
+            SpecializationConstant::F64(-1e100),
+            SpecializationConstant::I32(-1),
+            SpecializationConstant::I32(i32::MIN),
+            SpecializationConstant::Bool(true),
+            SpecializationConstant::Bool(false),
+        ] {
+            let (ty, bits) = value.as_u64_bits();
+            assert_eq!(SpecializationConstant::from_u64_bits(ty, bits), value);
+        }
+
+        assert_eq!(
+            SpecializationConstant::F64(-1e100).as_u64_bits(),
+            (SpecializationConstantType::F64, (-1e100f64).to_bits()),
+        );
+        // Negative integers are zero-extended, not sign-extended.
+        assert_eq!(
+            SpecializationConstant::I32(-1).as_u64_bits(),
+            (SpecializationConstantType::I32, 0xffff_ffff),
+        );
+        assert_eq!(
+            SpecializationConstant::Bool(true).as_u64_bits(),
+            (SpecializationConstantType::Bool, 1),
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn specialization_constant_serde() {
+        use half::f16;
+
+        for value in [
+            SpecializationConstant::Bool(true),
+            SpecializationConstant::U8(u8::MAX),
+            SpecializationConstant::U16(u16::MAX),
+            SpecializationConstant::U32(u32::MAX),
+            SpecializationConstant::U64(u64::MAX),
+            SpecializationConstant::I8(i8::MIN),
+            SpecializationConstant::I16(i16::MIN),
+            SpecializationConstant::I32(i32::MIN),
+            SpecializationConstant::I64(i64::MIN),
+            SpecializationConstant::F16(f16::from_f32(1.5)),
+            SpecializationConstant::F32(-2.25),
+            SpecializationConstant::F64(1e100),
+        ] {
+            let json = serde_json::to_string(&value).unwrap();
+            assert_eq!(
+                serde_json::from_str::<SpecializationConstant>(&json).unwrap(),
+                value,
+            );
+        }
+
+        assert_eq!(
+            serde_json::to_string(&SpecializationConstant::F16(f16::from_f32(1.5))).unwrap(),
+            r#"{"F16":1.5}"#,
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn reflection_serde() {
+        let spirv = Spirv::new(&LINK_VS).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        let json = serde_json::to_string(&info.output_interface).unwrap();
+        let output_interface: ShaderInterface = serde_json::from_str(&json).unwrap();
+        assert_eq!(output_interface, info.output_interface);
+        assert_eq!(serde_json::to_string(&output_interface).unwrap(), json);
+
+        let binding_reqs = &info.descriptor_binding_requirements[&(0, 0)];
+        let json = serde_json::to_string(binding_reqs).unwrap();
+        assert_eq!(
+            serde_json::from_str::<DescriptorBindingRequirements>(&json).unwrap(),
+            *binding_reqs,
+        );
+
+        // Invalid interfaces are rejected when deserializing.
+        let overlapping = interface(&[(0, NumericType::Float, 4), (0, NumericType::Int, 1)]);
+        let json = serde_json::to_string(&overlapping).unwrap();
+        assert!(serde_json::from_str::<ShaderInterface>(&json).is_err());
+    }
+}
@@ -12,22 +12,53 @@
 use super::DescriptorBindingRequirements;
 use crate::{
     descriptor_set::layout::DescriptorType,
+    device::physical::SubgroupFeatures,
     image::view::ImageViewType,
     pipeline::layout::PushConstantRange,
     shader::{
-        spirv::{Decoration, Dim, ExecutionModel, Id, Instruction, Spirv, StorageClass},
-        DescriptorIdentifier, DescriptorRequirements, EntryPointInfo, NumericType, ShaderInterface,
-        ShaderInterfaceEntry, ShaderInterfaceEntryType, ShaderStage, SpecializationConstant,
+        spirv::{
+            BuiltIn, CooperativeMatrixUse, Decoration, Dim, ExecutionMode, ExecutionModel,
+            GroupOperation, Id, Instruction, Scope, Spirv, StorageClass,
+        },
+        CooperativeMatrixType, DescriptorIdentifier, DescriptorRequirements, EntryPointInfo,
+        FragmentOrigin, NumericType, PushConstantMemberType, ShaderInterface, ShaderInterfaceEntry,
+        ShaderInterfaceEntryType, ShaderStage, SpecializationConstant,
     },
-    DeviceSize,
+    DeviceSize, ValidationError, Version,
 };
 use ahash::{HashMap, HashSet};
 use half::f16;
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::hash_map::Entry};
 
 /// Returns an iterator over all entry points in `spirv`, with information about the entry point.
+///
+/// Entry points with the `Kernel` execution model are skipped, as they can't be used with Vulkan.
+/// [`Spirv::unsupported_entry_points`] returns the entry points that were skipped. Creating a
+/// [`ShaderModule`](crate::shader::ShaderModule) from such code returns an error, because it
+/// requires the `Kernel` capability.
+///
+/// The descriptor binding requirements of an entry point include the bindings that are used by
+/// the functions that it calls with `OpFunctionCall`, directly or indirectly. Shaders that are
+/// invoked with `OpTraceRayKHR` or `OpExecuteCallableKHR` are not followed, even if they are
+/// entry points in the same module, because the shader binding table decides which shaders are
+/// invoked. Combining the requirements of all the shaders in a ray tracing pipeline is the
+/// responsibility of the pipeline.
 #[inline]
 pub fn entry_points(spirv: &Spirv) -> impl Iterator<Item = (Id, EntryPointInfo)> + '_ {
+    entry_points_with_unused_bindings(spirv, false)
+}
+
+/// Returns an iterator over all entry points in `spirv`, like [`entry_points`].
+///
+/// If `include_unused_bindings` is `true`, the descriptor binding requirements of each entry
+/// point also include every descriptor binding that is declared in the module, but not used by
+/// the entry point. These bindings have no requirements for individual descriptors, but they do
+/// include the stage of the entry point, so that a descriptor set layout created from the
+/// requirements matches the layouts of other stages that do use the bindings.
+pub fn entry_points_with_unused_bindings(
+    spirv: &Spirv,
+    include_unused_bindings: bool,
+) -> impl Iterator<Item = (Id, EntryPointInfo)> + '_ {
     let interface_variables = interface_variables(spirv);
 
     spirv.iter_entry_point().filter_map(move |instruction| {
@@ -42,15 +73,20 @@ pub fn entry_points(spirv: &Spirv) -> impl Iterator<Item = (Id, EntryPointInfo)>
             _ => return None,
         };
 
-        let stage = ShaderStage::from(execution_model);
+        let stage = ShaderStage::try_from_execution_model(execution_model).ok()?;
 
         let descriptor_binding_requirements = inspect_entry_point(
             &interface_variables.descriptor_binding,
             spirv,
             stage,
             function_id,
+            include_unused_bindings,
         );
         let push_constant_requirements = push_constant_requirements(spirv, stage);
+        let used_ids = used_ids(spirv, function_id, interface);
+        let push_constant_members = block_members(spirv, &used_ids, StorageClass::PushConstant);
+        let shader_record_members =
+            block_members(spirv, &used_ids, StorageClass::ShaderRecordBufferKHR);
         let input_interface = shader_interface(
             spirv,
             interface,
@@ -68,6 +104,68 @@ pub fn entry_points(spirv: &Spirv) -> impl Iterator<Item = (Id, EntryPointInfo)>
             StorageClass::Output,
             matches!(execution_model, ExecutionModel::TessellationControl),
         );
+        let local_size = local_size(spirv, execution_model, function_id);
+        let output_vertices = output_vertices(spirv, execution_model, function_id);
+        let workgroup_shared_size = workgroup_shared_size(spirv, execution_model, &used_ids);
+        let uses_fragment_shading_rate = interface
+            .iter()
+            .any(|&id| is_fragment_shading_rate_builtin(spirv, id));
+        // Fragment shaders read the distances written by the previous stage, the other stages
+        // write them.
+        let clip_cull_storage_class = if execution_model == ExecutionModel::Fragment {
+            StorageClass::Input
+        } else {
+            StorageClass::Output
+        };
+        let clip_distance_count = builtin_array_length(
+            spirv,
+            interface,
+            clip_cull_storage_class,
+            BuiltIn::ClipDistance,
+        );
+        let cull_distance_count = builtin_array_length(
+            spirv,
+            interface,
+            clip_cull_storage_class,
+            BuiltIn::CullDistance,
+        );
+        let uses_debug_printf = uses_debug_printf(spirv, function_id);
+        let subgroup_operations = subgroup_operations(spirv, function_id);
+        let uses_ray_query = uses_ray_query(spirv, function_id);
+        let uses_no_contraction = uses_no_contraction(spirv, function_id);
+        let uses_dual_source_blend = execution_model == ExecutionModel::Fragment
+            && (output_interface.elements().iter()).any(|element| element.index == 1);
+        let uses_sample_rate_shading = execution_model == ExecutionModel::Fragment
+            && uses_sample_rate_shading(spirv, interface);
+        let may_discard =
+            execution_model == ExecutionModel::Fragment && may_discard(spirv, function_id);
+        let fragment_origin = fragment_origin(spirv, execution_model, function_id);
+        let exports_stencil_ref = has_fragment_execution_mode(
+            spirv,
+            execution_model,
+            function_id,
+            &[ExecutionMode::StencilRefReplacingEXT],
+        );
+        let uses_frag_coord = execution_model == ExecutionModel::Fragment
+            && declares_builtin(spirv, interface, StorageClass::Input, BuiltIn::FragCoord);
+        let pixel_center_integer = has_fragment_execution_mode(
+            spirv,
+            execution_model,
+            function_id,
+            &[ExecutionMode::PixelCenterInteger],
+        );
+        let sample_interlock = has_fragment_execution_mode(
+            spirv,
+            execution_model,
+            function_id,
+            &[
+                ExecutionMode::SampleInterlockOrderedEXT,
+                ExecutionMode::SampleInterlockUnorderedEXT,
+            ],
+        );
+        let writes_layer = declares_builtin(spirv, interface, StorageClass::Output, BuiltIn::Layer);
+        let position_invariant = position_invariant(spirv, interface);
+        let cooperative_matrix_types = cooperative_matrix_types(spirv, function_id);
 
         Some((
             function_id,
@@ -76,13 +174,59 @@ pub fn entry_points(spirv: &Spirv) -> impl Iterator<Item = (Id, EntryPointInfo)>
                 execution_model,
                 descriptor_binding_requirements,
                 push_constant_requirements,
+                push_constant_members,
+                shader_record_members,
                 input_interface,
                 output_interface,
+                local_size,
+                output_vertices,
+                workgroup_shared_size,
+                clip_distance_count,
+                cull_distance_count,
+                uses_fragment_shading_rate,
+                uses_debug_printf,
+                uses_dual_source_blend,
+                uses_sample_rate_shading,
+                may_discard,
+                fragment_origin,
+                uses_frag_coord,
+                pixel_center_integer,
+                sample_interlock,
+                exports_stencil_ref,
+                writes_layer,
+                position_invariant,
+                cooperative_matrix_types,
+                subgroup_operations,
+                uses_ray_query,
+                uses_no_contraction,
             },
         ))
     })
 }
 
+/// Returns an iterator over all entry points in `spirv`, like [`entry_points`], but with the
+/// descriptor types of some bindings given by `hints`.
+///
+/// The SPIR-V code doesn't always determine the descriptor type of a binding. For example, a
+/// uniform buffer, a dynamic uniform buffer and an inline uniform block all look the same. For
+/// each `(set, binding)` in `hints` that an entry point uses, `descriptor_types` is replaced with
+/// only the given descriptor type. Hints for bindings that an entry point doesn't use are
+/// ignored.
+pub fn entry_points_with_hints<'a>(
+    spirv: &'a Spirv,
+    hints: &'a HashMap<(u32, u32), DescriptorType>,
+) -> impl Iterator<Item = (Id, EntryPointInfo)> + 'a {
+    entry_points(spirv).map(move |(id, mut info)| {
+        for (binding, reqs) in &mut info.descriptor_binding_requirements {
+            if let Some(&descriptor_type) = hints.get(binding) {
+                reqs.descriptor_types = vec![descriptor_type];
+            }
+        }
+
+        (id, info)
+    })
+}
+
 #[derive(Clone, Debug, Default)]
 struct InterfaceVariables {
     descriptor_binding: HashMap<Id, DescriptorBindingVariable>,
@@ -94,6 +238,40 @@ struct DescriptorBindingVariable {
     set: u32,
     binding: u32,
     reqs: DescriptorBindingRequirements,
+    // Whether the entry point uses the variable in a way that requires a sampled image or a
+    // storage image. Used to resolve images with a `Sampled` operand of 0.
+    used_as_sampled: bool,
+    used_as_storage: bool,
+    // Whether the variable, or a member of the block it points to, is decorated with `Coherent`
+    // or `Volatile`.
+    coherent: bool,
+    volatile: bool,
+}
+
+impl DescriptorBindingVariable {
+    /// If the variable is an image with a `Sampled` operand of 0, narrows its descriptor types
+    /// down to either the sampled or the storage variants, depending on how the entry point uses
+    /// it. If the usage is ambiguous, both are kept.
+    fn resolve_unknown_sampled(&mut self) {
+        let descriptor_types = &mut self.reqs.descriptor_types;
+        let is_unknown_sampled = descriptor_types
+            == &[DescriptorType::SampledImage, DescriptorType::StorageImage]
+            || descriptor_types
+                == &[
+                    DescriptorType::UniformTexelBuffer,
+                    DescriptorType::StorageTexelBuffer,
+                ];
+
+        if is_unknown_sampled && self.used_as_sampled != self.used_as_storage {
+            let used_as_storage = self.used_as_storage;
+            descriptor_types.retain(|&descriptor_type| {
+                matches!(
+                    descriptor_type,
+                    DescriptorType::StorageImage | DescriptorType::StorageTexelBuffer
+                ) == used_as_storage
+            });
+        }
+    }
 }
 
 fn interface_variables(spirv: &Spirv) -> InterfaceVariables {
@@ -129,6 +307,7 @@ fn inspect_entry_point(
     spirv: &Spirv,
     stage: ShaderStage,
     entry_point: Id,
+    include_unused: bool,
 ) -> HashMap<(u32, u32), DescriptorBindingRequirements> {
     struct Context<'a> {
         global: &'a HashMap<Id, DescriptorBindingVariable>,
@@ -159,6 +338,10 @@ fn inspect_entry_point(
                 base, ref indexes, ..
             } = *self.spirv.id(id).instruction()
             {
+                let nonuniform = is_nonuniform(self.spirv, id)
+                    || indexes
+                        .first()
+                        .map_or(false, |&index| is_nonuniform(self.spirv, index));
                 id = base;
 
                 if let Some(variable) = self.global.get(&id) {
@@ -171,6 +354,7 @@ fn inspect_entry_point(
                     };
                     let variable = self.result.entry(id).or_insert_with(|| variable.clone());
                     variable.reqs.stages = self.stage.into();
+                    variable.reqs.nonuniform_indexing |= nonuniform;
                     return Some((variable, index));
                 }
             }
@@ -373,6 +557,7 @@ fn inspect_entry_point(
                         ) {
                             desc_reqs.memory_read = stage.into();
                             desc_reqs.sampler_no_unnormalized_coordinates = true;
+                            desc_reqs.sampler_uses_implicit_lod = true;
 
                             if image_operands.as_ref().map_or(false, |image_operands| {
                                 image_operands.const_offset.is_some()
@@ -433,6 +618,7 @@ fn inspect_entry_point(
                             desc_reqs.memory_read = stage.into();
                             desc_reqs.sampler_no_unnormalized_coordinates = true;
                             desc_reqs.sampler_compare = true;
+                            desc_reqs.sampler_uses_implicit_lod = true;
 
                             if image_operands.as_ref().map_or(false, |image_operands| {
                                 image_operands.const_offset.is_some()
@@ -509,22 +695,72 @@ fn inspect_entry_point(
                     }
 
                     Instruction::ImageTexelPointer { image, .. } => {
-                        self.instruction_chain([], image);
+                        if let Some((variable, _)) = self.instruction_chain([], image) {
+                            variable.used_as_storage = true;
+                        }
                     }
 
-                    Instruction::ImageRead { image, .. } => {
-                        if let Some(desc_reqs) =
-                            desc_reqs(self.instruction_chain([inst_load], image))
+                    Instruction::ImageFetch { image, .. }
+                    | Instruction::ImageSparseFetch { image, .. } => {
+                        if let Some((variable, _)) = self.instruction_chain([inst_load], image) {
+                            variable.used_as_sampled = true;
+                        }
+                    }
+
+                    Instruction::ImageRead {
+                        result_type_id,
+                        image,
+                        ..
+                    } => {
+                        let scalar_type = texel_scalar_type(self.spirv, result_type_id);
+
+                        if let Some((variable, index)) = self.instruction_chain([inst_load], image)
                         {
-                            desc_reqs.memory_read = stage.into();
+                            variable.used_as_storage = true;
+
+                            if scalar_type.is_some() {
+                                variable.reqs.image_scalar_type = scalar_type;
+                            }
+
+                            // Reading a subpass input does not need a format.
+                            if variable.reqs.image_format.is_none()
+                                && !variable
+                                    .reqs
+                                    .descriptor_types
+                                    .contains(&DescriptorType::InputAttachment)
+                            {
+                                variable.reqs.storage_read_without_format = true;
+                            }
+
+                            if let Some(desc_reqs) = desc_reqs(Some((variable, index))) {
+                                desc_reqs.memory_read = stage.into();
+                            }
                         }
                     }
 
-                    Instruction::ImageWrite { image, .. } => {
-                        if let Some(desc_reqs) =
-                            desc_reqs(self.instruction_chain([inst_load], image))
+                    Instruction::ImageWrite { image, texel, .. } => {
+                        let scalar_type = self
+                            .spirv
+                            .id(texel)
+                            .instruction()
+                            .result_type_id()
+                            .and_then(|type_id| texel_scalar_type(self.spirv, type_id));
+
+                        if let Some((variable, index)) = self.instruction_chain([inst_load], image)
                         {
-                            desc_reqs.memory_write = stage.into();
+                            variable.used_as_storage = true;
+
+                            if scalar_type.is_some() {
+                                variable.reqs.image_scalar_type = scalar_type;
+                            }
+
+                            if variable.reqs.image_format.is_none() {
+                                variable.reqs.storage_write_without_format = true;
+                            }
+
+                            if let Some(desc_reqs) = desc_reqs(Some((variable, index))) {
+                                desc_reqs.memory_write = stage.into();
+                            }
                         }
                     }
 
@@ -554,12 +790,19 @@ fn inspect_entry_point(
 
                     Instruction::SampledImage { image, sampler, .. } => {
                         let identifier = match self.instruction_chain([inst_load], image) {
-                            Some((variable, Some(index))) => DescriptorIdentifier {
-                                set: variable.set,
-                                binding: variable.binding,
-                                index,
-                            },
-                            _ => continue,
+                            Some((variable, index)) => {
+                                variable.used_as_sampled = true;
+
+                                match index {
+                                    Some(index) => DescriptorIdentifier {
+                                        set: variable.set,
+                                        binding: variable.binding,
+                                        index,
+                                    },
+                                    None => continue,
+                                }
+                            }
+                            None => continue,
                         };
 
                         if let Some(desc_reqs) =
@@ -591,11 +834,91 @@ fn inspect_entry_point(
     };
     context.inspect_entry_point_r(entry_point);
 
-    context
-        .result
-        .into_values()
-        .map(|variable| ((variable.set, variable.binding), variable.reqs))
-        .collect()
+    if include_unused {
+        for (&id, variable) in global {
+            context.result.entry(id).or_insert_with(|| {
+                let mut variable = variable.clone();
+                variable.reqs.stages = stage.into();
+                variable
+            });
+        }
+    }
+
+    let mut result: HashMap<(u32, u32), DescriptorBindingRequirements> = HashMap::default();
+
+    for mut variable in context.result.into_values() {
+        variable.resolve_unknown_sampled();
+
+        for desc_reqs in variable.reqs.descriptors.values_mut() {
+            desc_reqs.image_format = variable.reqs.image_format;
+            desc_reqs.coherent = variable.coherent;
+            desc_reqs.volatile = variable.volatile;
+        }
+
+        match result.entry((variable.set, variable.binding)) {
+            Entry::Vacant(entry) => {
+                entry.insert(variable.reqs);
+            }
+            Entry::Occupied(entry) => {
+                // Several variables can alias the same binding, for example to access different
+                // array elements with different formats.
+                merge_aliased_requirements(entry.into_mut(), variable.reqs);
+            }
+        }
+    }
+
+    result
+}
+
+/// Merges the requirements of a variable into those of another variable that is bound to the same
+/// binding. This is also used to merge the requirements of different entry points in
+/// [`Spirv::reflect_module`].
+///
+/// Unlike [`DescriptorBindingRequirements::merge`], this doesn't fail if the requirements
+/// conflict. Instead, requirements that conflict are relaxed to what both variables allow: the
+/// descriptor types of both variables are allowed, and a format, scalar type, view type or input
+/// attachment index that differs between them is no longer required. If only one of the
+/// variables is multisampled, the merged requirements are those of the one that is not.
+pub(super) fn merge_aliased_requirements(
+    reqs: &mut DescriptorBindingRequirements,
+    mut other: DescriptorBindingRequirements,
+) {
+    if !(reqs.descriptor_types.iter()).any(|ty| other.descriptor_types.contains(ty)) {
+        reqs.descriptor_types.append(&mut other.descriptor_types);
+        other.descriptor_types.clone_from(&reqs.descriptor_types);
+    }
+
+    for (index, desc_reqs) in &mut other.descriptors {
+        if let Some(first) = reqs.descriptors.get_mut(index) {
+            if first.image_format != desc_reqs.image_format {
+                first.image_format = None;
+                desc_reqs.image_format = None;
+            }
+        }
+    }
+
+    fn relax<T: PartialEq>(first: &mut Option<T>, second: &mut Option<T>) {
+        if first.is_some() && second.is_some() && first != second {
+            *first = None;
+            *second = None;
+        }
+    }
+
+    relax(&mut reqs.image_scalar_type, &mut other.image_scalar_type);
+    relax(&mut reqs.image_scalar_width, &mut other.image_scalar_width);
+    relax(&mut reqs.image_view_type, &mut other.image_view_type);
+    relax(
+        &mut reqs.input_attachment_index,
+        &mut other.input_attachment_index,
+    );
+
+    if reqs.image_multisampled != other.image_multisampled {
+        reqs.image_multisampled = false;
+        other.image_multisampled = false;
+    }
+
+    reqs.merge(&other)
+        .expect("conflicting requirements should have been relaxed");
 }
 
 /// Returns a `DescriptorBindingRequirements` value for the pointed type.
@@ -630,7 +953,9 @@ fn descriptor_binding_requirements_of(spirv: &Spirv, variable_id: Id) -> Descrip
         let id_info = spirv.id(id);
 
         next_type_id = match *id_info.instruction() {
-            Instruction::TypeStruct { .. } => {
+            Instruction::TypeStruct {
+                ref member_types, ..
+            } => {
                 let decoration_block = id_info.iter_decoration().any(|instruction| {
                     matches!(
                         instruction,
@@ -662,6 +987,9 @@ fn descriptor_binding_requirements_of(spirv: &Spirv, variable_id: Id) -> Descrip
                         DescriptorType::StorageBuffer,
                         DescriptorType::StorageBufferDynamic,
                     ];
+                    reqs.runtime_array_stride = member_types
+                        .last()
+                        .and_then(|&member_type| runtime_array_stride(spirv, member_type));
                 } else {
                     reqs.descriptor_types = vec![
                         DescriptorType::UniformBuffer,
@@ -676,36 +1004,29 @@ fn descriptor_binding_requirements_of(spirv: &Spirv, variable_id: Id) -> Descrip
             Instruction::TypeImage {
                 sampled_type,
                 dim,
+                depth,
                 arrayed,
                 ms,
                 sampled,
                 image_format,
                 ..
             } => {
-                assert!(
-                    sampled != 0,
-                    "Vulkan requires that variables of type OpTypeImage have a Sampled operand of \
-                    1 or 2",
-                );
                 reqs.image_format = image_format.into();
                 reqs.image_multisampled = ms != 0;
-                reqs.image_scalar_type = Some(match *spirv.id(sampled_type).instruction() {
+                reqs.image_depth = depth == 1;
+                let (scalar_type, scalar_width) = match *spirv.id(sampled_type).instruction() {
                     Instruction::TypeInt {
                         width, signedness, ..
-                    } => {
-                        assert!(width == 32); // TODO: 64-bit components
-                        match signedness {
-                            0 => NumericType::Uint,
-                            1 => NumericType::Int,
-                            _ => unreachable!(),
-                        }
-                    }
-                    Instruction::TypeFloat { width, .. } => {
-                        assert!(width == 32); // TODO: 64-bit components
-                        NumericType::Float
-                    }
+                    } => match signedness {
+                        0 => (NumericType::Uint, width),
+                        1 => (NumericType::Int, width),
+                        _ => unreachable!(),
+                    },
+                    Instruction::TypeFloat { width, .. } => (NumericType::Float, width),
                     _ => unreachable!(),
-                });
+                };
+                reqs.image_scalar_type = Some(scalar_type);
+                reqs.image_scalar_width = Some(scalar_width);
 
                 match dim {
                     Dim::SubpassData => {
@@ -719,11 +1040,15 @@ fn descriptor_binding_requirements_of(spirv: &Spirv, variable_id: Id) -> Descrip
                         reqs.descriptor_types = vec![DescriptorType::InputAttachment];
                     }
                     Dim::Buffer => {
-                        if sampled == 1 {
-                            reqs.descriptor_types = vec![DescriptorType::UniformTexelBuffer];
-                        } else {
-                            reqs.descriptor_types = vec![DescriptorType::StorageTexelBuffer];
-                        }
+                        reqs.descriptor_types = match sampled {
+                            1 => vec![DescriptorType::UniformTexelBuffer],
+                            2 => vec![DescriptorType::StorageTexelBuffer],
+                            // Only known from how the image is used; resolved later.
+                            _ => vec![
+                                DescriptorType::UniformTexelBuffer,
+                                DescriptorType::StorageTexelBuffer,
+                            ],
+                        };
                     }
                     _ => {
                         reqs.image_view_type = Some(match (dim, arrayed) {
@@ -744,11 +1069,14 @@ fn descriptor_binding_requirements_of(spirv: &Spirv, variable_id: Id) -> Descrip
                         });
 
                         if reqs.descriptor_types.is_empty() {
-                            if sampled == 1 {
-                                reqs.descriptor_types = vec![DescriptorType::SampledImage];
-                            } else {
-                                reqs.descriptor_types = vec![DescriptorType::StorageImage];
-                            }
+                            reqs.descriptor_types = match sampled {
+                                1 => vec![DescriptorType::SampledImage],
+                                2 => vec![DescriptorType::StorageImage],
+                                // Only known from how the image is used; resolved later.
+                                _ => {
+                                    vec![DescriptorType::SampledImage, DescriptorType::StorageImage]
+                                }
+                            };
                         }
                     }
                 }
@@ -768,7 +1096,9 @@ fn descriptor_binding_requirements_of(spirv: &Spirv, variable_id: Id) -> Descrip
                 Some(image_type)
             }
 
-            Instruction::TypeAccelerationStructureKHR { .. } => {
+            // The KHR and NV instructions have the same opcode, so this is parsed as the NV one.
+            Instruction::TypeAccelerationStructureKHR { .. }
+            | Instruction::TypeAccelerationStructureNV { .. } => {
                 reqs.descriptor_types = vec![DescriptorType::AccelerationStructure];
 
                 None
@@ -825,6 +1155,19 @@ fn descriptor_binding_requirements_of(spirv: &Spirv, variable_id: Id) -> Descrip
         };
     }
 
+    reqs.input_attachment_index =
+        variable_id_info
+            .iter_decoration()
+            .find_map(|instruction| match *instruction {
+                Instruction::Decorate {
+                    decoration: Decoration::InputAttachmentIndex { attachment_index },
+                    ..
+                } => Some(attachment_index),
+                _ => None,
+            });
+
+    let (coherent, volatile) = memory_decorations(spirv, variable_id);
+
     DescriptorBindingVariable {
         set: variable_id_info
             .iter_decoration()
@@ -847,10 +1190,80 @@ fn descriptor_binding_requirements_of(spirv: &Spirv, variable_id: Id) -> Descrip
             })
             .unwrap(),
         reqs,
+        used_as_sampled: false,
+        used_as_storage: false,
+        coherent,
+        volatile,
+    }
+}
+
+/// Returns the scalar type of the texels that are read from or written to a storage image, given
+/// the type of the texel value.
+fn texel_scalar_type(spirv: &Spirv, type_id: Id) -> Option<NumericType> {
+    match *spirv.id(type_id).instruction() {
+        Instruction::TypeInt { signedness, .. } => match signedness {
+            0 => Some(NumericType::Uint),
+            1 => Some(NumericType::Int),
+            _ => unreachable!(),
+        },
+        Instruction::TypeFloat { .. } => Some(NumericType::Float),
+        Instruction::TypeVector { component_type, .. } => texel_scalar_type(spirv, component_type),
+        _ => None,
+    }
+}
+
+/// Returns whether a descriptor variable is decorated with `Coherent` and `Volatile`,
+/// respectively. For buffers, the decorations may instead be applied to the members of the
+/// block.
+fn memory_decorations(spirv: &Spirv, variable_id: Id) -> (bool, bool) {
+    let variable_id_info = spirv.id(variable_id);
+    let mut decorations: Vec<&Decoration> = variable_id_info
+        .iter_decoration()
+        .filter_map(|instruction| match instruction {
+            Instruction::Decorate { decoration, .. } => Some(decoration),
+            _ => None,
+        })
+        .collect();
+
+    let mut ty = match *variable_id_info.instruction() {
+        Instruction::Variable { result_type_id, .. } => {
+            match *spirv.id(result_type_id).instruction() {
+                Instruction::TypePointer { ty, .. } => ty,
+                _ => return (false, false),
+            }
+        }
+        _ => return (false, false),
+    };
+
+    while let Instruction::TypeArray { element_type, .. }
+    | Instruction::TypeRuntimeArray { element_type, .. } = *spirv.id(ty).instruction()
+    {
+        ty = element_type;
     }
+
+    decorations.extend(
+        spirv
+            .id(ty)
+            .iter_members()
+            .flat_map(|member_info| member_info.iter_decoration())
+            .filter_map(|instruction| match instruction {
+                Instruction::MemberDecorate { decoration, .. } => Some(decoration),
+                _ => None,
+            }),
+    );
+
+    (
+        decorations
+            .iter()
+            .any(|decoration| matches!(decoration, Decoration::Coherent)),
+        decorations
+            .iter()
+            .any(|decoration| matches!(decoration, Decoration::Volatile)),
+    )
 }
 
-/// Extracts the `PushConstantRange` from `spirv`.
+/// Extracts the push constant range from `spirv`, spanning from the lowest member offset to the
+/// end of the last member of the push constant block.
 fn push_constant_requirements(spirv: &Spirv, stage: ShaderStage) -> Option<PushConstantRange> {
     spirv
         .iter_global()
@@ -909,53 +1322,13 @@ pub(super) fn specialization_constants(spirv: &Spirv) -> HashMap<u32, Specializa
                 result_id,
                 ref value,
             } => get_constant_id(result_id).map(|constant_id| {
-                let value = match *spirv.id(result_type_id).instruction() {
-                    Instruction::TypeInt {
-                        width, signedness, ..
-                    } => {
-                        if width == 64 {
-                            assert!(value.len() == 2);
-                        } else {
-                            assert!(value.len() == 1);
-                        }
-
-                        match (signedness, width) {
-                            (0, 8) => SpecializationConstant::U8(value[0] as u8),
-                            (0, 16) => SpecializationConstant::U16(value[0] as u16),
-                            (0, 32) => SpecializationConstant::U32(value[0]),
-                            (0, 64) => SpecializationConstant::U64(
-                                (value[0] as u64) | ((value[1] as u64) << 32),
-                            ),
-                            (1, 8) => SpecializationConstant::I8(value[0] as i8),
-                            (1, 16) => SpecializationConstant::I16(value[0] as i16),
-                            (1, 32) => SpecializationConstant::I32(value[0] as i32),
-                            (1, 64) => SpecializationConstant::I64(
-                                (value[0] as i64) | ((value[1] as i64) << 32),
-                            ),
-                            _ => unimplemented!(),
-                        }
-                    }
-                    Instruction::TypeFloat { width, .. } => {
-                        if width == 64 {
-                            assert!(value.len() == 2);
-                        } else {
-                            assert!(value.len() == 1);
-                        }
-
-                        match width {
-                            16 => SpecializationConstant::F16(f16::from_bits(value[0] as u16)),
-                            32 => SpecializationConstant::F32(f32::from_bits(value[0])),
-                            64 => SpecializationConstant::F64(f64::from_bits(
-                                (value[0] as u64) | ((value[1] as u64) << 32),
-                            )),
-                            _ => unimplemented!(),
-                        }
-                    }
-                    _ => panic!(
-                        "Specialization constant {} has a non-scalar type",
-                        constant_id
-                    ),
-                };
+                let value =
+                    scalar_constant_value(spirv, result_type_id, value).unwrap_or_else(|| {
+                        panic!(
+                            "Specialization constant {} has a non-scalar type",
+                            constant_id
+                        )
+                    });
 
                 (constant_id, value)
             }),
@@ -964,112 +1337,454 @@ pub(super) fn specialization_constants(spirv: &Spirv) -> HashMap<u32, Specializa
         .collect()
 }
 
-/// Extracts the `ShaderInterface` with the given storage class from `spirv`.
-fn shader_interface(
+/// Returns the value of a scalar constant with the given type and value words, or `None` if the
+/// type is not a numeric scalar type.
+fn scalar_constant_value(
     spirv: &Spirv,
-    interface: &[Id],
-    filter_storage_class: StorageClass,
-    ignore_first_array: bool,
-) -> ShaderInterface {
-    let elements: Vec<_> = interface
-        .iter()
-        .filter_map(|&id| {
-            let (result_type_id, result_id) = match *spirv.id(id).instruction() {
-                Instruction::Variable {
-                    result_type_id,
-                    result_id,
-                    storage_class,
-                    ..
-                } if storage_class == filter_storage_class => (result_type_id, result_id),
-                _ => return None,
-            };
+    result_type_id: Id,
+    value: &[u32],
+) -> Option<SpecializationConstant> {
+    let value = match *spirv.id(result_type_id).instruction() {
+        Instruction::TypeInt {
+            width, signedness, ..
+        } => {
+            if width == 64 {
+                assert!(value.len() == 2);
+            } else {
+                assert!(value.len() == 1);
+            }
 
-            if is_builtin(spirv, result_id) {
-                return None;
+            match (signedness, width) {
+                (0, 8) => SpecializationConstant::U8(value[0] as u8),
+                (0, 16) => SpecializationConstant::U16(value[0] as u16),
+                (0, 32) => SpecializationConstant::U32(value[0]),
+                (0, 64) => {
+                    SpecializationConstant::U64((value[0] as u64) | ((value[1] as u64) << 32))
+                }
+                (1, 8) => SpecializationConstant::I8(value[0] as i8),
+                (1, 16) => SpecializationConstant::I16(value[0] as i16),
+                (1, 32) => SpecializationConstant::I32(value[0] as i32),
+                (1, 64) => {
+                    SpecializationConstant::I64((value[0] as i64) | ((value[1] as i64) << 32))
+                }
+                _ => unimplemented!(),
+            }
+        }
+        Instruction::TypeFloat { width, .. } => {
+            if width == 64 {
+                assert!(value.len() == 2);
+            } else {
+                assert!(value.len() == 1);
             }
 
-            let id_info = spirv.id(result_id);
+            match width {
+                16 => SpecializationConstant::F16(f16::from_bits(value[0] as u16)),
+                32 => SpecializationConstant::F32(f32::from_bits(value[0])),
+                64 => SpecializationConstant::F64(f64::from_bits(
+                    (value[0] as u64) | ((value[1] as u64) << 32),
+                )),
+                _ => unimplemented!(),
+            }
+        }
+        _ => return None,
+    };
 
-            let name = id_info
-                .iter_name()
-                .find_map(|instruction| match *instruction {
-                    Instruction::Name { ref name, .. } => Some(Cow::Owned(name.clone())),
-                    _ => None,
-                });
+    Some(value)
+}
 
-            let location = id_info
-                .iter_decoration()
-                .find_map(|instruction| match *instruction {
-                    Instruction::Decorate {
-                        decoration: Decoration::Location { location },
-                        ..
-                    } => Some(location),
-                    _ => None,
-                })
-                .unwrap_or_else(|| {
-                    panic!(
-                        "Input/output variable with id {} (name {:?}) is missing a location",
-                        result_id, name,
-                    )
-                });
-            let component = id_info
-                .iter_decoration()
-                .find_map(|instruction| match *instruction {
-                    Instruction::Decorate {
-                        decoration: Decoration::Component { component },
-                        ..
-                    } => Some(component),
-                    _ => None,
-                })
-                .unwrap_or(0);
-            let index = id_info
-                .iter_decoration()
-                .find_map(|instruction| match *instruction {
-                    Instruction::Decorate {
-                        decoration: Decoration::Index { index },
+/// Returns the values of the `SpecConstantOp` instructions in `base_spirv` that have a scalar
+/// type, as they were evaluated in `specialized_spirv`, keyed by the result id of the instruction.
+pub(super) fn derived_constants(
+    base_spirv: &Spirv,
+    specialized_spirv: &Spirv,
+) -> HashMap<u32, SpecializationConstant> {
+    base_spirv
+        .iter_global()
+        .filter_map(|instruction| match *instruction {
+            Instruction::SpecConstantOp { result_id, .. } => {
+                let value = match *specialized_spirv.id(result_id).instruction() {
+                    Instruction::ConstantFalse { .. } => Some(SpecializationConstant::Bool(false)),
+                    Instruction::ConstantTrue { .. } => Some(SpecializationConstant::Bool(true)),
+                    Instruction::Constant {
+                        result_type_id,
+                        ref value,
                         ..
-                    } => Some(index),
+                    } => scalar_constant_value(specialized_spirv, result_type_id, value),
                     _ => None,
-                })
-                .unwrap_or(0);
-
-            let ty = shader_interface_type_of(spirv, result_type_id, ignore_first_array);
-            assert!(ty.num_elements >= 1);
+                };
 
-            Some(ShaderInterfaceEntry {
-                location,
-                index,
-                component,
-                ty,
-                name,
-            })
+                value.map(|value| (result_id.as_raw(), value))
+            }
+            _ => None,
         })
-        .collect();
+        .collect()
+}
 
-    // Checking for overlapping elements.
-    for (offset, element1) in elements.iter().enumerate() {
-        for element2 in elements.iter().skip(offset + 1) {
-            if element1.index == element2.index
-                && (element1.location == element2.location
-                    || (element1.location < element2.location
-                        && element1.location + element1.ty.num_locations() > element2.location)
-                    || (element2.location < element1.location
-                        && element2.location + element2.ty.num_locations() > element1.location))
-            {
-                panic!(
-                    "The locations of attributes `{:?}` ({}..{}) and `{:?}` ({}..{}) overlap",
-                    element1.name,
-                    element1.location,
-                    element1.location + element1.ty.num_locations(),
-                    element2.name,
-                    element2.location,
-                    element2.location + element2.ty.num_locations(),
-                );
-            }
+/// Returns the ids that an entry point uses, which include all the global variables that it uses.
+fn used_ids(spirv: &Spirv, function_id: Id, interface: &[Id]) -> HashSet<Id> {
+    // Starting with SPIR-V 1.4, the interface of an entry point lists all global variables that
+    // it uses. Before that, the ids that are referenced by the entry point function and the
+    // functions that it calls are collected.
+    if spirv.version() >= Version::V1_4 {
+        return interface.iter().copied().collect();
+    }
+
+    let mut ids = HashSet::default();
+
+    for function in reachable_functions(spirv, function_id) {
+        for instruction in spirv.function(function).iter_instructions() {
+            instruction.visit_ids(&mut |id| {
+                ids.insert(id);
+            });
         }
     }
 
-    ShaderInterface { elements }
+    ids
+}
+
+/// Returns the flattened members of the block with the given storage class, which is either
+/// `PushConstant` or `ShaderRecordBufferKHR`, sorted by offset. Only the block of a variable in
+/// `used_ids` is considered.
+fn block_members(
+    spirv: &Spirv,
+    used_ids: &HashSet<Id>,
+    block_storage_class: StorageClass,
+) -> Vec<(u32, PushConstantMemberType)> {
+    let mut members = Vec::new();
+
+    let block_type = spirv
+        .iter_global()
+        .find_map(|instruction| match *instruction {
+            Instruction::Variable {
+                result_type_id,
+                result_id,
+                storage_class,
+                ..
+            } if storage_class == block_storage_class && used_ids.contains(&result_id) => {
+                match *spirv.id(result_type_id).instruction() {
+                    Instruction::TypePointer { ty, .. } => Some(ty),
+                    _ => None,
+                }
+            }
+            _ => None,
+        });
+
+    if let Some(ty) = block_type {
+        block_members_of(spirv, ty, 0, &mut members);
+        members.sort_by_key(|&(offset, _)| offset);
+    }
+
+    members
+}
+
+/// Appends the members of the struct `id`, located at `base_offset`, to `members`. Members whose
+/// offset or type can't be determined are skipped.
+fn block_members_of(
+    spirv: &Spirv,
+    id: Id,
+    base_offset: u32,
+    members: &mut Vec<(u32, PushConstantMemberType)>,
+) {
+    let id_info = spirv.id(id);
+    let member_types = match id_info.instruction() {
+        Instruction::TypeStruct { member_types, .. } => member_types,
+        _ => return,
+    };
+
+    for (&member, member_info) in member_types.iter().zip(id_info.iter_members()) {
+        let offset = member_info
+            .iter_decoration()
+            .find_map(|instruction| match *instruction {
+                Instruction::MemberDecorate {
+                    decoration: Decoration::Offset { byte_offset },
+                    ..
+                } => Some(byte_offset),
+                _ => None,
+            });
+
+        if let Some(offset) = offset {
+            block_member_of(spirv, member, base_offset + offset, members);
+        }
+    }
+}
+
+/// Appends the member with type `id`, located at `offset`, to `members`. Structs, and arrays of
+/// structs, are flattened into their members. Members whose type can't be determined are
+/// skipped.
+fn block_member_of(
+    spirv: &Spirv,
+    id: Id,
+    offset: u32,
+    members: &mut Vec<(u32, PushConstantMemberType)>,
+) {
+    let id_info = spirv.id(id);
+
+    match *id_info.instruction() {
+        Instruction::TypeStruct { .. } => block_members_of(spirv, id, offset, members),
+        Instruction::TypeArray {
+            element_type,
+            length,
+            ..
+        } if matches!(
+            spirv.id(element_type).instruction(),
+            Instruction::TypeStruct { .. } | Instruction::TypeArray { .. }
+        ) =>
+        {
+            let stride = id_info
+                .iter_decoration()
+                .find_map(|instruction| match *instruction {
+                    Instruction::Decorate {
+                        decoration: Decoration::ArrayStride { array_stride },
+                        ..
+                    } => Some(array_stride),
+                    _ => None,
+                });
+
+            if let (Some(stride), Some(length)) = (stride, array_length(spirv, length)) {
+                for index in 0..length {
+                    block_member_of(spirv, element_type, offset + index * stride, members);
+                }
+            }
+        }
+        _ => {
+            if let Some(ty) = push_constant_member_type_of(spirv, id) {
+                members.push((offset, ty));
+            }
+        }
+    }
+}
+
+fn push_constant_member_type_of(spirv: &Spirv, id: Id) -> Option<PushConstantMemberType> {
+    match *spirv.id(id).instruction() {
+        Instruction::TypeInt {
+            width, signedness, ..
+        } => Some(PushConstantMemberType {
+            base_type: if signedness == 0 {
+                NumericType::Uint
+            } else {
+                NumericType::Int
+            },
+            width,
+            num_components: 1,
+            num_elements: 1,
+        }),
+        Instruction::TypeFloat { width, .. } => Some(PushConstantMemberType {
+            base_type: NumericType::Float,
+            width,
+            num_components: 1,
+            num_elements: 1,
+        }),
+        // A `PhysicalStorageBuffer` pointer (`buffer_reference` in GLSL) holds a 64-bit device
+        // address.
+        Instruction::TypePointer { .. } => Some(PushConstantMemberType {
+            base_type: NumericType::Uint,
+            width: 64,
+            num_components: 1,
+            num_elements: 1,
+        }),
+        Instruction::TypeVector {
+            component_type,
+            component_count,
+            ..
+        } => Some(PushConstantMemberType {
+            num_components: component_count,
+            ..push_constant_member_type_of(spirv, component_type)?
+        }),
+        Instruction::TypeMatrix {
+            column_type,
+            column_count,
+            ..
+        } => Some(PushConstantMemberType {
+            num_elements: column_count,
+            ..push_constant_member_type_of(spirv, column_type)?
+        }),
+        Instruction::TypeArray {
+            element_type,
+            length,
+            ..
+        } => {
+            let mut ty = push_constant_member_type_of(spirv, element_type)?;
+            ty.num_elements *= array_length(spirv, length)?;
+            Some(ty)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the length of an array type, or `None` if it is not a constant. If the length is not
+/// specialized, the default value of the specialization constant is used.
+fn array_length(spirv: &Spirv, length: Id) -> Option<u32> {
+    match *spirv.id(length).instruction() {
+        Instruction::Constant { ref value, .. } | Instruction::SpecConstant { ref value, .. } => {
+            value.first().copied()
+        }
+        _ => None,
+    }
+}
+
+/// Extracts the `ShaderInterface` with the given storage class from `spirv`.
+fn shader_interface(
+    spirv: &Spirv,
+    interface: &[Id],
+    filter_storage_class: StorageClass,
+    ignore_first_array: bool,
+) -> ShaderInterface {
+    let elements: Vec<_> = interface
+        .iter()
+        .filter_map(|&id| {
+            let (result_type_id, result_id) = match *spirv.id(id).instruction() {
+                Instruction::Variable {
+                    result_type_id,
+                    result_id,
+                    storage_class,
+                    ..
+                } if storage_class == filter_storage_class => (result_type_id, result_id),
+                _ => return None,
+            };
+
+            if is_builtin(spirv, result_id) {
+                return None;
+            }
+
+            let id_info = spirv.id(result_id);
+
+            let name = id_info
+                .iter_name()
+                .find_map(|instruction| match *instruction {
+                    Instruction::Name { ref name, .. } => Some(Cow::Owned(name.clone())),
+                    _ => None,
+                });
+
+            let location = id_info
+                .iter_decoration()
+                .find_map(|instruction| match *instruction {
+                    Instruction::Decorate {
+                        decoration: Decoration::Location { location },
+                        ..
+                    } => Some(location),
+                    _ => None,
+                })
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Input/output variable with id {} (name {:?}) is missing a location",
+                        result_id, name,
+                    )
+                });
+            let component = id_info
+                .iter_decoration()
+                .find_map(|instruction| match *instruction {
+                    Instruction::Decorate {
+                        decoration: Decoration::Component { component },
+                        ..
+                    } => Some(component),
+                    _ => None,
+                })
+                .unwrap_or(0);
+            let index = id_info
+                .iter_decoration()
+                .find_map(|instruction| match *instruction {
+                    Instruction::Decorate {
+                        decoration: Decoration::Index { index },
+                        ..
+                    } => Some(index),
+                    _ => None,
+                })
+                .unwrap_or(0);
+
+            let relaxed_precision = id_info.iter_decoration().any(|instruction| {
+                matches!(
+                    instruction,
+                    Instruction::Decorate {
+                        decoration: Decoration::RelaxedPrecision,
+                        ..
+                    }
+                )
+            });
+
+            let flat = id_info.iter_decoration().any(|instruction| {
+                matches!(
+                    instruction,
+                    Instruction::Decorate {
+                        decoration: Decoration::Flat,
+                        ..
+                    }
+                )
+            });
+
+            let is_patch = id_info.iter_decoration().any(|instruction| {
+                matches!(
+                    instruction,
+                    Instruction::Decorate {
+                        decoration: Decoration::Patch,
+                        ..
+                    }
+                )
+            });
+
+            // Per-patch variables are not arrayed, unlike the per-vertex variables of the same
+            // stage.
+            let ty =
+                shader_interface_type_of(spirv, result_type_id, ignore_first_array && !is_patch);
+            assert!(ty.num_elements >= 1);
+
+            Some(ShaderInterfaceEntry {
+                location,
+                index,
+                component,
+                ty,
+                name,
+                relaxed_precision,
+                flat,
+                is_patch,
+            })
+        })
+        .collect();
+
+    // Checking for overlapping elements.
+    for (offset, element1) in elements.iter().enumerate() {
+        for element2 in elements.iter().skip(offset + 1) {
+            if element1.index == element2.index
+                && element1.is_patch == element2.is_patch
+                && (element1.location == element2.location
+                    || (element1.location < element2.location
+                        && element1.location + element1.ty.num_locations() > element2.location)
+                    || (element2.location < element1.location
+                        && element2.location + element2.ty.num_locations() > element1.location))
+            {
+                panic!(
+                    "The locations of attributes `{:?}` ({}..{}) and `{:?}` ({}..{}) overlap",
+                    element1.name,
+                    element1.location,
+                    element1.location + element1.ty.num_locations(),
+                    element2.name,
+                    element2.location,
+                    element2.location + element2.ty.num_locations(),
+                );
+            }
+        }
+    }
+
+    ShaderInterface { elements }
+}
+
+/// If `id` is a runtime array type, returns its `ArrayStride` decoration.
+fn runtime_array_stride(spirv: &Spirv, id: Id) -> Option<u32> {
+    let id_info = spirv.id(id);
+
+    match *id_info.instruction() {
+        Instruction::TypeRuntimeArray { .. } => {
+            id_info
+                .iter_decoration()
+                .find_map(|instruction| match *instruction {
+                    Instruction::Decorate {
+                        decoration: Decoration::ArrayStride { array_stride },
+                        ..
+                    } => Some(array_stride),
+                    _ => None,
+                })
+        }
+        _ => None,
+    }
 }
 
 /// Returns the size of a type, or `None` if its size cannot be determined.
@@ -1186,43 +1901,202 @@ fn offset_of_struct(spirv: &Spirv, id: Id) -> u32 {
         .unwrap_or(0)
 }
 
-/// If `ignore_first_array` is true, the function expects the outermost instruction to be
-/// `OpTypeArray`. If it's the case, the OpTypeArray will be ignored. If not, the function will
-/// panic.
-fn shader_interface_type_of(
-    spirv: &Spirv,
-    id: Id,
-    ignore_first_array: bool,
-) -> ShaderInterfaceEntryType {
-    match *spirv.id(id).instruction() {
-        Instruction::TypeInt {
-            width, signedness, ..
-        } => {
-            assert!(!ignore_first_array);
-            ShaderInterfaceEntryType {
-                base_type: match signedness {
-                    0 => NumericType::Uint,
-                    1 => NumericType::Int,
-                    _ => unreachable!(),
-                },
-                num_components: 1,
-                num_elements: 1,
-                is_64bit: match width {
-                    8 | 16 | 32 => false,
-                    64 => true,
-                    _ => unimplemented!(),
-                },
-            }
-        }
-        Instruction::TypeFloat { width, .. } => {
-            assert!(!ignore_first_array);
-            ShaderInterfaceEntryType {
-                base_type: NumericType::Float,
-                num_components: 1,
-                num_elements: 1,
-                is_64bit: match width {
-                    16 | 32 => false,
-                    64 => true,
+/// Checks that the members of the blocks of uniform buffers, storage buffers, push constants and
+/// shader records don't overlap, including the members of structs that are nested in the blocks.
+pub(super) fn validate_block_layouts(spirv: &Spirv) -> Result<(), Box<ValidationError>> {
+    let mut pending_types: Vec<Id> = spirv
+        .iter_global()
+        .filter_map(|instruction| match *instruction {
+            Instruction::Variable {
+                result_type_id,
+                storage_class:
+                    StorageClass::Uniform
+                    | StorageClass::StorageBuffer
+                    | StorageClass::PushConstant
+                    | StorageClass::ShaderRecordBufferKHR,
+                ..
+            } => match *spirv.id(result_type_id).instruction() {
+                Instruction::TypePointer { ty, .. } => Some(ty),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    let mut inspected_structs = HashSet::default();
+
+    while let Some(id) = pending_types.pop() {
+        let id_info = spirv.id(id);
+
+        match *id_info.instruction() {
+            Instruction::TypeArray { element_type, .. }
+            | Instruction::TypeRuntimeArray { element_type, .. } => {
+                pending_types.push(element_type);
+            }
+            Instruction::TypeStruct {
+                ref member_types, ..
+            } => {
+                if !inspected_structs.insert(id) {
+                    continue;
+                }
+
+                pending_types.extend(member_types);
+
+                let mut members: Vec<_> = (member_types.iter().zip(id_info.iter_members()))
+                    .enumerate()
+                    .filter_map(|(index, (&member, member_info))| {
+                        let offset = member_offset(member_info.iter_decoration())?;
+                        Some((index, offset, block_member_size(spirv, member)))
+                    })
+                    .collect();
+                members.sort_unstable_by_key(|&(_, offset, _)| offset);
+
+                for pair in members.windows(2) {
+                    let (index, offset, size) = pair[0];
+                    let (next_index, next_offset, _) = pair[1];
+                    let end = match size {
+                        Some(size) => offset as DeviceSize + size,
+                        None => continue,
+                    };
+
+                    if end > next_offset as DeviceSize {
+                        return Err(Box::new(ValidationError {
+                            problem: format!(
+                                "the struct type {} is used in a buffer or push constant block, \
+                                but member {} extends from offset {} to offset {}, which overlaps \
+                                member {} at offset {}",
+                                id, index, offset, end, next_index, next_offset,
+                            )
+                            .into(),
+                            ..Default::default()
+                        }));
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the value of the `Offset` decoration among `decorations`.
+fn member_offset<'a>(mut decorations: impl Iterator<Item = &'a Instruction>) -> Option<u32> {
+    decorations.find_map(|instruction| match *instruction {
+        Instruction::MemberDecorate {
+            decoration: Decoration::Offset { byte_offset },
+            ..
+        } => Some(byte_offset),
+        _ => None,
+    })
+}
+
+/// Returns the number of bytes that a member of type `id` of a block occupies, from its offset to
+/// the end of its last scalar. Padding that follows the last element of an array or the last
+/// member of a struct is not included. Returns `None` if the size can't be determined.
+fn block_member_size(spirv: &Spirv, id: Id) -> Option<DeviceSize> {
+    let id_info = spirv.id(id);
+
+    match *id_info.instruction() {
+        Instruction::TypeInt { width, .. } | Instruction::TypeFloat { width, .. } => {
+            Some(width as DeviceSize / 8)
+        }
+        Instruction::TypePointer { .. } => Some(8),
+        Instruction::TypeVector {
+            component_type,
+            component_count,
+            ..
+        } => block_member_size(spirv, component_type)
+            .map(|component_size| component_size * component_count as DeviceSize),
+        // The columns of a matrix may be further apart than their size, so this is only a lower
+        // bound.
+        Instruction::TypeMatrix {
+            column_type,
+            column_count,
+            ..
+        } => block_member_size(spirv, column_type)
+            .map(|column_size| column_size * column_count as DeviceSize),
+        Instruction::TypeArray {
+            element_type,
+            length,
+            ..
+        } => {
+            let stride = id_info
+                .iter_decoration()
+                .find_map(|instruction| match *instruction {
+                    Instruction::Decorate {
+                        decoration: Decoration::ArrayStride { array_stride },
+                        ..
+                    } => Some(array_stride),
+                    _ => None,
+                })?;
+            // The length of an array that is sized by a specialization constant is not known.
+            let length = match *spirv.id(length).instruction() {
+                Instruction::Constant { ref value, .. } => value[0],
+                _ => return None,
+            };
+
+            if length == 0 {
+                return Some(0);
+            }
+
+            block_member_size(spirv, element_type).map(|element_size| {
+                stride as DeviceSize * (length - 1) as DeviceSize + element_size
+            })
+        }
+        Instruction::TypeStruct {
+            ref member_types, ..
+        } => {
+            let mut end_of_struct = 0;
+
+            for (&member, member_info) in member_types.iter().zip(id_info.iter_members()) {
+                let offset = member_offset(member_info.iter_decoration())?;
+                let size = block_member_size(spirv, member)?;
+                end_of_struct = end_of_struct.max(offset as DeviceSize + size);
+            }
+
+            Some(end_of_struct)
+        }
+        _ => None,
+    }
+}
+
+/// If `ignore_first_array` is true, the function expects the outermost instruction to be
+/// `OpTypeArray`. If it's the case, the OpTypeArray will be ignored. If not, the function will
+/// panic.
+fn shader_interface_type_of(
+    spirv: &Spirv,
+    id: Id,
+    ignore_first_array: bool,
+) -> ShaderInterfaceEntryType {
+    match *spirv.id(id).instruction() {
+        Instruction::TypeInt {
+            width, signedness, ..
+        } => {
+            assert!(!ignore_first_array);
+            ShaderInterfaceEntryType {
+                base_type: match signedness {
+                    0 => NumericType::Uint,
+                    1 => NumericType::Int,
+                    _ => unreachable!(),
+                },
+                num_components: 1,
+                num_elements: 1,
+                is_64bit: match width {
+                    8 | 16 | 32 => false,
+                    64 => true,
+                    _ => unimplemented!(),
+                },
+            }
+        }
+        Instruction::TypeFloat { width, .. } => {
+            assert!(!ignore_first_array);
+            ShaderInterfaceEntryType {
+                base_type: NumericType::Float,
+                num_components: 1,
+                num_elements: 1,
+                is_64bit: match width {
+                    16 | 32 => false,
+                    64 => true,
                     _ => unimplemented!(),
                 },
             }
@@ -1284,6 +2158,726 @@ fn shader_interface_type_of(
     }
 }
 
+/// Extracts the output patch size of a tessellation entry point.
+fn output_vertices(spirv: &Spirv, execution_model: ExecutionModel, function_id: Id) -> Option<u32> {
+    if !matches!(
+        execution_model,
+        ExecutionModel::TessellationControl | ExecutionModel::TessellationEvaluation
+    ) {
+        return None;
+    }
+
+    spirv
+        .function(function_id)
+        .iter_execution_mode()
+        .find_map(|instruction| match *instruction {
+            Instruction::ExecutionMode {
+                mode: ExecutionMode::OutputVertices { vertex_count },
+                ..
+            } => Some(vertex_count),
+            _ => None,
+        })
+}
+
+/// Extracts the origin of the fragment coordinates of a fragment entry point.
+fn fragment_origin(
+    spirv: &Spirv,
+    execution_model: ExecutionModel,
+    function_id: Id,
+) -> Option<FragmentOrigin> {
+    if execution_model != ExecutionModel::Fragment {
+        return None;
+    }
+
+    spirv
+        .function(function_id)
+        .iter_execution_mode()
+        .find_map(|instruction| match *instruction {
+            Instruction::ExecutionMode {
+                mode: ExecutionMode::OriginUpperLeft,
+                ..
+            } => Some(FragmentOrigin::UpperLeft),
+            Instruction::ExecutionMode {
+                mode: ExecutionMode::OriginLowerLeft,
+                ..
+            } => Some(FragmentOrigin::LowerLeft),
+            _ => None,
+        })
+}
+
+/// Returns true if the entry point is a fragment shader with one of `modes` as an execution mode.
+fn has_fragment_execution_mode(
+    spirv: &Spirv,
+    execution_model: ExecutionModel,
+    function_id: Id,
+    modes: &[ExecutionMode],
+) -> bool {
+    execution_model == ExecutionModel::Fragment
+        && spirv
+            .function(function_id)
+            .iter_execution_mode()
+            .any(|instruction| match instruction {
+                Instruction::ExecutionMode { mode, .. } => modes.contains(mode),
+                _ => false,
+            })
+}
+
+/// Extracts the local workgroup size of an entry point.
+fn local_size(spirv: &Spirv, execution_model: ExecutionModel, function_id: Id) -> Option<[u32; 3]> {
+    if !matches!(
+        execution_model,
+        ExecutionModel::GLCompute
+            | ExecutionModel::TaskNV
+            | ExecutionModel::TaskEXT
+            | ExecutionModel::MeshNV
+            | ExecutionModel::MeshEXT
+    ) {
+        return None;
+    }
+
+    // If the sizes are not specialized, the default value of specialization constants is used.
+    let constant_value = |id| match *spirv.id(id).instruction() {
+        Instruction::Constant { ref value, .. } | Instruction::SpecConstant { ref value, .. } => {
+            value.first().copied()
+        }
+        _ => None,
+    };
+    let constant_values =
+        |[x, y, z]: [Id; 3]| Some([constant_value(x)?, constant_value(y)?, constant_value(z)?]);
+    let function_info = spirv.function(function_id);
+
+    // A constant decorated with the `WorkgroupSize` builtin overrides the `LocalSize` and
+    // `LocalSizeId` execution modes. `khr_maintenance4` deprecates the builtin, but doesn't
+    // change its meaning.
+    let workgroup_size = spirv
+        .iter_decoration()
+        .find_map(|instruction| match *instruction {
+            Instruction::Decorate {
+                target,
+                decoration:
+                    Decoration::BuiltIn {
+                        built_in: BuiltIn::WorkgroupSize,
+                    },
+            } => match *spirv.id(target).instruction() {
+                Instruction::ConstantComposite {
+                    ref constituents, ..
+                }
+                | Instruction::SpecConstantComposite {
+                    ref constituents, ..
+                } => <[Id; 3]>::try_from(constituents.as_slice()).ok(),
+                _ => None,
+            },
+            _ => None,
+        });
+
+    if let Some(ids) = workgroup_size {
+        return constant_values(ids);
+    }
+
+    let local_size_id =
+        function_info
+            .iter_execution_mode()
+            .find_map(|instruction| match *instruction {
+                Instruction::ExecutionModeId {
+                    mode:
+                        ExecutionMode::LocalSizeId {
+                            x_size,
+                            y_size,
+                            z_size,
+                        },
+                    ..
+                } => Some([x_size, y_size, z_size]),
+                _ => None,
+            });
+
+    if let Some(ids) = local_size_id {
+        return constant_values(ids);
+    }
+
+    function_info
+        .iter_execution_mode()
+        .find_map(|instruction| match *instruction {
+            Instruction::ExecutionMode {
+                mode:
+                    ExecutionMode::LocalSize {
+                        x_size,
+                        y_size,
+                        z_size,
+                    },
+                ..
+            } => Some([x_size, y_size, z_size]),
+            _ => None,
+        })
+}
+
+/// Returns the total size in bytes of the `Workgroup` variables that an entry point uses, or
+/// `None` if the size of one of them is not known.
+fn workgroup_shared_size(
+    spirv: &Spirv,
+    execution_model: ExecutionModel,
+    used_ids: &HashSet<Id>,
+) -> Option<u32> {
+    if !matches!(
+        execution_model,
+        ExecutionModel::GLCompute
+            | ExecutionModel::TaskNV
+            | ExecutionModel::TaskEXT
+            | ExecutionModel::MeshNV
+            | ExecutionModel::MeshEXT
+    ) {
+        return None;
+    }
+
+    let mut size = 0;
+
+    for instruction in spirv.iter_global() {
+        if let Instruction::Variable {
+            result_type_id,
+            result_id,
+            storage_class: StorageClass::Workgroup,
+            ..
+        } = *instruction
+        {
+            if !used_ids.contains(&result_id) {
+                continue;
+            }
+
+            let pointee_type_id = match *spirv.id(result_type_id).instruction() {
+                Instruction::TypePointer { ty, .. } => ty,
+                _ => unreachable!(),
+            };
+
+            size += workgroup_size_of_type(spirv, pointee_type_id)?;
+        }
+    }
+
+    Some(size)
+}
+
+/// Returns the size in bytes of a type in `Workgroup` storage. As is done by the validation
+/// layers, the members of structs and the elements of arrays are counted without padding.
+fn workgroup_size_of_type(spirv: &Spirv, id: Id) -> Option<u32> {
+    match *spirv.id(id).instruction() {
+        // Booleans don't have a defined size, count them as 32-bit integers.
+        Instruction::TypeBool { .. } => Some(4),
+        Instruction::TypeInt { width, .. } | Instruction::TypeFloat { width, .. } => {
+            Some(width / 8)
+        }
+        Instruction::TypeVector {
+            component_type,
+            component_count,
+            ..
+        } => workgroup_size_of_type(spirv, component_type)
+            .map(|component_size| component_size * component_count),
+        Instruction::TypeMatrix {
+            column_type,
+            column_count,
+            ..
+        } => {
+            workgroup_size_of_type(spirv, column_type).map(|column_size| column_size * column_count)
+        }
+        Instruction::TypeArray {
+            element_type,
+            length,
+            ..
+        } => {
+            // If the length is not specialized, the default value of the specialization constant
+            // is used.
+            let length = match *spirv.id(length).instruction() {
+                Instruction::Constant { ref value, .. }
+                | Instruction::SpecConstant { ref value, .. } => value.first().copied(),
+                _ => None,
+            }?;
+
+            workgroup_size_of_type(spirv, element_type).map(|element_size| element_size * length)
+        }
+        Instruction::TypeStruct {
+            ref member_types, ..
+        } => member_types
+            .iter()
+            .map(|&member| workgroup_size_of_type(spirv, member))
+            .sum(),
+        _ => None,
+    }
+}
+
+/// Returns true if the function, or any function that it calls, uses an instruction from the
+/// `NonSemantic.DebugPrintf` extended instruction set.
+fn uses_debug_printf(spirv: &Spirv, function: Id) -> bool {
+    let debug_printf_sets: HashSet<Id> = spirv
+        .iter_ext_inst_import()
+        .filter_map(|instruction| match instruction {
+            Instruction::ExtInstImport { result_id, name } if name == "NonSemantic.DebugPrintf" => {
+                Some(*result_id)
+            }
+            _ => None,
+        })
+        .collect();
+
+    if debug_printf_sets.is_empty() {
+        return false;
+    }
+
+    reachable_functions(spirv, function)
+        .into_iter()
+        .flat_map(|function| spirv.function(function).iter_instructions())
+        .any(|instruction| {
+            matches!(
+                *instruction,
+                Instruction::ExtInst { set, .. } if debug_printf_sets.contains(&set)
+            )
+        })
+}
+
+/// Returns true if the function, or a function that it calls, discards the fragment.
+fn may_discard(spirv: &Spirv, function: Id) -> bool {
+    reachable_functions(spirv, function)
+        .into_iter()
+        .flat_map(|function| spirv.function(function).iter_instructions())
+        .any(|instruction| {
+            matches!(
+                instruction,
+                Instruction::Kill
+                    | Instruction::TerminateInvocation
+                    | Instruction::DemoteToHelperInvocation
+                    | Instruction::DemoteToHelperInvocationEXT
+            )
+        })
+}
+
+/// Returns true if the function, or a function that it calls, initializes a ray query.
+fn uses_ray_query(spirv: &Spirv, function: Id) -> bool {
+    reachable_functions(spirv, function)
+        .into_iter()
+        .flat_map(|function| spirv.function(function).iter_instructions())
+        .any(|instruction| matches!(instruction, Instruction::RayQueryInitializeKHR { .. }))
+}
+
+/// Returns true if the function, or a function that it calls, has an instruction whose result is
+/// decorated with `NoContraction`.
+fn uses_no_contraction(spirv: &Spirv, function: Id) -> bool {
+    reachable_functions(spirv, function)
+        .into_iter()
+        .flat_map(|function| spirv.function(function).iter_instructions())
+        .filter_map(Instruction::result_id)
+        .any(|result_id| {
+            spirv.id(result_id).iter_decoration().any(|instruction| {
+                matches!(
+                    instruction,
+                    Instruction::Decorate {
+                        decoration: Decoration::NoContraction,
+                        ..
+                    }
+                )
+            })
+        })
+}
+
+/// Returns the function and all the functions that it calls, directly or indirectly, in the order
+/// in which they are first reached.
+pub(super) fn reachable_functions(spirv: &Spirv, function: Id) -> Vec<Id> {
+    let mut reachable_functions = vec![function];
+    let mut inspected_functions: HashSet<Id> = [function].into_iter().collect();
+    let mut index = 0;
+
+    while let Some(&function) = reachable_functions.get(index) {
+        index += 1;
+
+        for instruction in spirv.function(function).iter_instructions() {
+            if let Instruction::FunctionCall { function, .. } = *instruction {
+                if inspected_functions.insert(function) {
+                    reachable_functions.push(function);
+                }
+            }
+        }
+    }
+
+    reachable_functions
+}
+
+/// Returns the categories of subgroup operations that the function, or a function that it calls,
+/// uses.
+fn subgroup_operations(spirv: &Spirv, function: Id) -> SubgroupFeatures {
+    let mut operations = SubgroupFeatures::empty();
+
+    for function in reachable_functions(spirv, function) {
+        for instruction in spirv.function(function).iter_instructions() {
+            operations |= match *instruction {
+                Instruction::GroupNonUniformElect { .. } => SubgroupFeatures::BASIC,
+                Instruction::GroupNonUniformAll { .. }
+                | Instruction::GroupNonUniformAny { .. }
+                | Instruction::GroupNonUniformAllEqual { .. } => SubgroupFeatures::VOTE,
+                Instruction::GroupNonUniformBroadcast { .. }
+                | Instruction::GroupNonUniformBroadcastFirst { .. }
+                | Instruction::GroupNonUniformBallot { .. }
+                | Instruction::GroupNonUniformInverseBallot { .. }
+                | Instruction::GroupNonUniformBallotBitExtract { .. }
+                | Instruction::GroupNonUniformBallotBitCount { .. }
+                | Instruction::GroupNonUniformBallotFindLSB { .. }
+                | Instruction::GroupNonUniformBallotFindMSB { .. } => SubgroupFeatures::BALLOT,
+                Instruction::GroupNonUniformShuffle { .. }
+                | Instruction::GroupNonUniformShuffleXor { .. } => SubgroupFeatures::SHUFFLE,
+                Instruction::GroupNonUniformShuffleUp { .. }
+                | Instruction::GroupNonUniformShuffleDown { .. } => {
+                    SubgroupFeatures::SHUFFLE_RELATIVE
+                }
+                Instruction::GroupNonUniformIAdd { operation, .. }
+                | Instruction::GroupNonUniformFAdd { operation, .. }
+                | Instruction::GroupNonUniformIMul { operation, .. }
+                | Instruction::GroupNonUniformFMul { operation, .. }
+                | Instruction::GroupNonUniformSMin { operation, .. }
+                | Instruction::GroupNonUniformUMin { operation, .. }
+                | Instruction::GroupNonUniformFMin { operation, .. }
+                | Instruction::GroupNonUniformSMax { operation, .. }
+                | Instruction::GroupNonUniformUMax { operation, .. }
+                | Instruction::GroupNonUniformFMax { operation, .. }
+                | Instruction::GroupNonUniformBitwiseAnd { operation, .. }
+                | Instruction::GroupNonUniformBitwiseOr { operation, .. }
+                | Instruction::GroupNonUniformBitwiseXor { operation, .. }
+                | Instruction::GroupNonUniformLogicalAnd { operation, .. }
+                | Instruction::GroupNonUniformLogicalOr { operation, .. }
+                | Instruction::GroupNonUniformLogicalXor { operation, .. } => match operation {
+                    GroupOperation::Reduce
+                    | GroupOperation::InclusiveScan
+                    | GroupOperation::ExclusiveScan => SubgroupFeatures::ARITHMETIC,
+                    GroupOperation::ClusteredReduce => SubgroupFeatures::CLUSTERED,
+                    GroupOperation::PartitionedReduceNV
+                    | GroupOperation::PartitionedInclusiveScanNV
+                    | GroupOperation::PartitionedExclusiveScanNV => SubgroupFeatures::PARTITIONED,
+                },
+                Instruction::GroupNonUniformQuadBroadcast { .. }
+                | Instruction::GroupNonUniformQuadSwap { .. } => SubgroupFeatures::QUAD,
+                Instruction::GroupNonUniformPartitionNV { .. } => SubgroupFeatures::PARTITIONED,
+                _ => continue,
+            };
+        }
+    }
+
+    // Every subgroup operation requires the basic subgroup operations to be supported.
+    if !operations.is_empty() {
+        operations |= SubgroupFeatures::BASIC;
+    }
+
+    operations
+}
+
+/// Returns true if a `NonUniform` decorator is applied on an id.
+fn is_nonuniform(spirv: &Spirv, id: Id) -> bool {
+    spirv.id(id).iter_decoration().any(|instruction| {
+        matches!(
+            instruction,
+            Instruction::Decorate {
+                decoration: Decoration::NonUniform,
+                ..
+            }
+        )
+    })
+}
+
+/// Returns true if the id is decorated as the `PrimitiveShadingRateKHR` or `ShadingRateKHR`
+/// builtin.
+fn is_fragment_shading_rate_builtin(spirv: &Spirv, id: Id) -> bool {
+    spirv.id(id).iter_decoration().any(|instruction| {
+        matches!(
+            instruction,
+            Instruction::Decorate {
+                decoration: Decoration::BuiltIn {
+                    built_in: BuiltIn::PrimitiveShadingRateKHR | BuiltIn::ShadingRateKHR,
+                },
+                ..
+            }
+        )
+    })
+}
+
+/// Returns the cooperative matrix types that the function, or a function that it calls, uses.
+fn cooperative_matrix_types(spirv: &Spirv, function: Id) -> Vec<CooperativeMatrixType> {
+    // A type is used if it's referenced by an instruction of the functions, or by an id that they
+    // reference in turn, such as the type of a variable or the pointee type of a pointer type.
+    let mut used_ids = HashSet::default();
+    let mut pending_ids = Vec::new();
+
+    for function in reachable_functions(spirv, function) {
+        for instruction in spirv.function(function).iter_instructions() {
+            instruction.visit_ids(&mut |id| pending_ids.push(id));
+        }
+    }
+
+    while let Some(id) = pending_ids.pop() {
+        if used_ids.insert(id) {
+            (spirv.id(id).instruction()).visit_ids(&mut |id| pending_ids.push(id));
+        }
+    }
+
+    let constant_value = |id| match *spirv.id(id).instruction() {
+        Instruction::Constant { ref value, .. } => value.first().copied(),
+        _ => None,
+    };
+
+    spirv
+        .iter_global()
+        .filter_map(|instruction| match *instruction {
+            Instruction::TypeCooperativeMatrixKHR {
+                result_id,
+                component_type,
+                scope,
+                rows,
+                columns,
+                usage,
+            } if used_ids.contains(&result_id) => {
+                let (component_type, component_width) =
+                    match *spirv.id(component_type).instruction() {
+                        Instruction::TypeInt {
+                            width, signedness, ..
+                        } => match signedness {
+                            0 => (NumericType::Uint, width),
+                            1 => (NumericType::Int, width),
+                            _ => unreachable!(),
+                        },
+                        Instruction::TypeFloat { width, .. } => (NumericType::Float, width),
+                        _ => return None,
+                    };
+
+                Some(CooperativeMatrixType {
+                    component_type,
+                    component_width,
+                    scope: constant_value(scope).and_then(Scope::from_value),
+                    rows: constant_value(rows),
+                    columns: constant_value(columns),
+                    usage: constant_value(usage).and_then(CooperativeMatrixUse::from_value),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns true if any of the input variables in `interface` enables sample shading: either the
+/// `SampleId` or `SamplePosition` builtins, or a variable or block member that is decorated with
+/// `Sample`.
+fn uses_sample_rate_shading(spirv: &Spirv, interface: &[Id]) -> bool {
+    if declares_builtin(spirv, interface, StorageClass::Input, BuiltIn::SampleId)
+        || declares_builtin(
+            spirv,
+            interface,
+            StorageClass::Input,
+            BuiltIn::SamplePosition,
+        )
+    {
+        return true;
+    }
+
+    interface.iter().any(|&id| {
+        let id_info = spirv.id(id);
+        let mut ty = match *id_info.instruction() {
+            Instruction::Variable {
+                result_type_id,
+                storage_class: StorageClass::Input,
+                ..
+            } => match *spirv.id(result_type_id).instruction() {
+                Instruction::TypePointer { ty, .. } => ty,
+                _ => return false,
+            },
+            _ => return false,
+        };
+
+        if id_info.iter_decoration().any(|instruction| {
+            matches!(
+                instruction,
+                Instruction::Decorate {
+                    decoration: Decoration::Sample,
+                    ..
+                }
+            )
+        }) {
+            return true;
+        }
+
+        while let Instruction::TypeArray { element_type, .. } = *spirv.id(ty).instruction() {
+            ty = element_type;
+        }
+
+        spirv.id(ty).iter_members().any(|member_info| {
+            member_info.iter_decoration().any(|instruction| {
+                matches!(
+                    instruction,
+                    Instruction::MemberDecorate {
+                        decoration: Decoration::Sample,
+                        ..
+                    }
+                )
+            })
+        })
+    })
+}
+
+/// Returns true if any of the variables in `interface` with the given storage class is, or is a
+/// block that contains, the `built_in` builtin.
+fn declares_builtin(
+    spirv: &Spirv,
+    interface: &[Id],
+    storage_class: StorageClass,
+    built_in: BuiltIn,
+) -> bool {
+    builtin_decorations(spirv, interface, storage_class, built_in).is_some()
+}
+
+/// Returns whether the `Position` builtin output in `interface` is decorated with `Invariant`.
+fn position_invariant(spirv: &Spirv, interface: &[Id]) -> bool {
+    let decorations =
+        builtin_decorations(spirv, interface, StorageClass::Output, BuiltIn::Position);
+
+    decorations.map_or(false, |decorations| {
+        decorations
+            .iter()
+            .any(|decoration| matches!(decoration, Decoration::Invariant))
+    })
+}
+
+/// Returns the decorations of the variable or block member in `interface` that is decorated
+/// with `built_in`, or `None` if there is no such variable.
+fn builtin_decorations<'a>(
+    spirv: &'a Spirv,
+    interface: &[Id],
+    storage_class: StorageClass,
+    built_in: BuiltIn,
+) -> Option<Vec<&'a Decoration>> {
+    let is_built_in = |decoration: &Decoration| matches!(*decoration, Decoration::BuiltIn { built_in: b } if b == built_in);
+
+    interface.iter().find_map(|&id| {
+        let id_info = spirv.id(id);
+        let mut ty = match *id_info.instruction() {
+            Instruction::Variable {
+                result_type_id,
+                storage_class: variable_storage_class,
+                ..
+            } if variable_storage_class == storage_class => {
+                match *spirv.id(result_type_id).instruction() {
+                    Instruction::TypePointer { ty, .. } => ty,
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+
+        let decorations: Vec<_> = id_info
+            .iter_decoration()
+            .filter_map(|instruction| match instruction {
+                Instruction::Decorate { decoration, .. } => Some(decoration),
+                _ => None,
+            })
+            .collect();
+
+        if decorations.iter().any(|decoration| is_built_in(decoration)) {
+            return Some(decorations);
+        }
+
+        // Strip the outer array of arrayed interfaces to get to the block.
+        while let Instruction::TypeArray { element_type, .. } = *spirv.id(ty).instruction() {
+            ty = element_type;
+        }
+
+        spirv.id(ty).iter_members().find_map(|member_info| {
+            let decorations: Vec<_> = member_info
+                .iter_decoration()
+                .filter_map(|instruction| match instruction {
+                    Instruction::MemberDecorate { decoration, .. } => Some(decoration),
+                    _ => None,
+                })
+                .collect();
+
+            decorations
+                .iter()
+                .any(|decoration| is_built_in(decoration))
+                .then_some(decorations)
+        })
+    })
+}
+
+/// Returns the array size of the `built_in` variable, or of the `built_in` member of a block, in
+/// the interface variables with the given storage class. Returns 0 if there is no such builtin.
+///
+/// For arrayed interfaces, such as the inputs of a geometry shader, the size of the innermost
+/// array is returned.
+fn builtin_array_length(
+    spirv: &Spirv,
+    interface: &[Id],
+    storage_class: StorageClass,
+    built_in: BuiltIn,
+) -> u32 {
+    let is_built_in = |decoration: &Decoration| matches!(*decoration, Decoration::BuiltIn { built_in: b } if b == built_in);
+    let innermost_array_length = |mut ty: Id| {
+        let mut length = None;
+
+        while let Instruction::TypeArray {
+            element_type,
+            length: length_id,
+            ..
+        } = *spirv.id(ty).instruction()
+        {
+            length = match *spirv.id(length_id).instruction() {
+                Instruction::Constant { ref value, .. }
+                | Instruction::SpecConstant { ref value, .. } => value.first().copied(),
+                _ => None,
+            };
+            ty = element_type;
+        }
+
+        length
+    };
+
+    interface
+        .iter()
+        .filter_map(|&id| {
+            let id_info = spirv.id(id);
+            let mut ty = match *id_info.instruction() {
+                Instruction::Variable {
+                    result_type_id,
+                    storage_class: variable_storage_class,
+                    ..
+                } if variable_storage_class == storage_class => {
+                    match *spirv.id(result_type_id).instruction() {
+                        Instruction::TypePointer { ty, .. } => ty,
+                        _ => return None,
+                    }
+                }
+                _ => return None,
+            };
+
+            if id_info.iter_decoration().any(|instruction| {
+                matches!(instruction, Instruction::Decorate { decoration, .. } if is_built_in(decoration))
+            }) {
+                return innermost_array_length(ty);
+            }
+
+            // Strip the outer array of arrayed interfaces to get to the block.
+            while let Instruction::TypeArray { element_type, .. } = *spirv.id(ty).instruction() {
+                ty = element_type;
+            }
+
+            let ty_info = spirv.id(ty);
+            let member_types = match ty_info.instruction() {
+                Instruction::TypeStruct { member_types, .. } => member_types,
+                _ => return None,
+            };
+
+            member_types
+                .iter()
+                .zip(ty_info.iter_members())
+                .find(|(_, member_info)| {
+                    member_info.iter_decoration().any(|instruction| {
+                        matches!(
+                            instruction,
+                            Instruction::MemberDecorate { decoration, .. } if is_built_in(decoration)
+                        )
+                    })
+                })
+                .and_then(|(&member_type, _)| innermost_array_length(member_type))
+        })
+        .max()
+        .unwrap_or(0)
+}
+
 /// Returns true if a `BuiltIn` decorator is applied on an id.
 fn is_builtin(spirv: &Spirv, id: Id) -> bool {
     let id_info = spirv.id(id);
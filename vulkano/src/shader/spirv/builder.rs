@@ -0,0 +1,354 @@
+// Copyright (c) 2024 The Vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Programmatic construction of minimal SPIR-V modules.
+//!
+//! This is intended for tests of code that inspects SPIR-V modules, so that they don't need to
+//! compile a shader or contain hand-written SPIR-V words. It is only available with the
+//! `spirv_builder` feature.
+
+use super::{
+    AddressingModel, Capability, Decoration, ExecutionMode, ExecutionModel, FunctionControl, Id,
+    Instruction, MemoryModel, StorageClass,
+};
+use crate::Version;
+
+/// Builds a SPIR-V module with a single entry point.
+///
+/// The module has the `Shader` capability and the `Logical GLSL450` memory model. The entry point
+/// function takes no parameters and returns nothing. Its body consists of the instructions added
+/// with [`instruction`](Self::instruction), followed by `OpReturn`.
+///
+/// The builder does not validate the instructions that are added to it.
+#[derive(Clone, Debug)]
+pub struct SpirvBuilder {
+    version: Version,
+    bound: u32,
+    execution_model: ExecutionModel,
+    entry_point: Id,
+    name: String,
+    capabilities: Vec<Capability>,
+    execution_modes: Vec<ExecutionMode>,
+    interface: Vec<Id>,
+    decorations: Vec<Instruction>,
+    globals: Vec<Instruction>,
+    body: Vec<Instruction>,
+}
+
+impl SpirvBuilder {
+    /// Returns a builder for a SPIR-V 1.0 module, with an entry point of `execution_model` named
+    /// `name`.
+    pub fn new(execution_model: ExecutionModel, name: impl Into<String>) -> Self {
+        SpirvBuilder {
+            version: Version::V1_0,
+            bound: 2,
+            execution_model,
+            entry_point: Id(1),
+            name: name.into(),
+            capabilities: vec![Capability::Shader],
+            execution_modes: Vec::new(),
+            interface: Vec::new(),
+            decorations: Vec::new(),
+            globals: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// Sets the SPIR-V version of the module.
+    ///
+    /// The patch version is ignored, as the SPIR-V header only holds the major and minor version.
+    pub fn set_version(&mut self, version: Version) {
+        self.version = version;
+    }
+
+    /// Returns the `Id` of the entry point function.
+    pub fn entry_point(&self) -> Id {
+        self.entry_point
+    }
+
+    /// Allocates a new `Id`, to be used as the result of an instruction.
+    pub fn new_id(&mut self) -> Id {
+        let id = Id(self.bound);
+        self.bound += 1;
+        id
+    }
+
+    /// Adds a capability to the module, if it is not already added.
+    pub fn capability(&mut self, capability: Capability) {
+        if !self.capabilities.contains(&capability) {
+            self.capabilities.push(capability);
+        }
+    }
+
+    /// Adds an execution mode to the entry point.
+    pub fn execution_mode(&mut self, mode: ExecutionMode) {
+        self.execution_modes.push(mode);
+    }
+
+    /// Adds a variable to the interface of the entry point.
+    ///
+    /// Variables in the `Input` and `Output` storage classes that are created with
+    /// [`variable`](Self::variable) are added automatically.
+    pub fn interface(&mut self, variable: Id) {
+        self.interface.push(variable);
+    }
+
+    /// Decorates `target` with `decoration`.
+    pub fn decorate(&mut self, target: Id, decoration: Decoration) {
+        self.decorations
+            .push(Instruction::Decorate { target, decoration });
+    }
+
+    /// Decorates member `member` of the struct type `structure_type` with `decoration`.
+    pub fn member_decorate(&mut self, structure_type: Id, member: u32, decoration: Decoration) {
+        self.decorations.push(Instruction::MemberDecorate {
+            structure_type,
+            member,
+            decoration,
+        });
+    }
+
+    /// Adds a type, constant or global variable declaration to the module.
+    pub fn global(&mut self, instruction: Instruction) {
+        self.globals.push(instruction);
+    }
+
+    /// Adds an instruction to the body of the entry point function.
+    pub fn instruction(&mut self, instruction: Instruction) {
+        self.body.push(instruction);
+    }
+
+    /// Returns the `Id` of a `void` type.
+    pub fn type_void(&mut self) -> Id {
+        self.type_declaration(|result_id| Instruction::TypeVoid { result_id })
+    }
+
+    /// Returns the `Id` of an integer type.
+    pub fn type_int(&mut self, width: u32, signed: bool) -> Id {
+        self.type_declaration(|result_id| Instruction::TypeInt {
+            result_id,
+            width,
+            signedness: signed as u32,
+        })
+    }
+
+    /// Returns the `Id` of a floating-point type.
+    pub fn type_float(&mut self, width: u32) -> Id {
+        self.type_declaration(|result_id| Instruction::TypeFloat { result_id, width })
+    }
+
+    /// Returns the `Id` of a vector type.
+    pub fn type_vector(&mut self, component_type: Id, component_count: u32) -> Id {
+        self.type_declaration(|result_id| Instruction::TypeVector {
+            result_id,
+            component_type,
+            component_count,
+        })
+    }
+
+    /// Returns the `Id` of a pointer type.
+    pub fn type_pointer(&mut self, storage_class: StorageClass, ty: Id) -> Id {
+        self.type_declaration(|result_id| Instruction::TypePointer {
+            result_id,
+            storage_class,
+            ty,
+        })
+    }
+
+    /// Declares a new struct type, and returns its `Id`.
+    ///
+    /// Unlike the other types, a new struct type is declared every time, so that each one can be
+    /// decorated separately.
+    pub fn type_struct(&mut self, member_types: Vec<Id>) -> Id {
+        let result_id = self.new_id();
+        self.globals.push(Instruction::TypeStruct {
+            result_id,
+            member_types,
+        });
+        result_id
+    }
+
+    /// Declares a global variable of type `ty` in `storage_class`, and returns its `Id`.
+    pub fn variable(&mut self, storage_class: StorageClass, ty: Id) -> Id {
+        let result_type_id = self.type_pointer(storage_class, ty);
+        let result_id = self.new_id();
+        self.globals.push(Instruction::Variable {
+            result_type_id,
+            result_id,
+            storage_class,
+            initializer: None,
+        });
+
+        if matches!(storage_class, StorageClass::Input | StorageClass::Output) {
+            self.interface.push(result_id);
+        }
+
+        result_id
+    }
+
+    /// Returns the `Id` of an existing type declaration that is equal to the one returned by
+    /// `declaration`, or adds a new one. SPIR-V doesn't allow declaring the same non-aggregate
+    /// type twice.
+    fn type_declaration(&mut self, declaration: impl Fn(Id) -> Instruction) -> Id {
+        let existing = self.globals.iter().find_map(|instruction| {
+            let result_id = instruction.result_id()?;
+            (*instruction == declaration(result_id)).then_some(result_id)
+        });
+
+        existing.unwrap_or_else(|| {
+            let result_id = self.new_id();
+            self.globals.push(declaration(result_id));
+            result_id
+        })
+    }
+
+    /// Encodes the module into SPIR-V words.
+    pub fn build(&self) -> Vec<u32> {
+        let mut builder = self.clone();
+        let void = builder.type_void();
+        let function_type = builder.new_id();
+        builder.globals.push(Instruction::TypeFunction {
+            result_id: function_type,
+            return_type: void,
+            parameter_types: Vec::new(),
+        });
+        let label = builder.new_id();
+
+        let SpirvBuilder {
+            version,
+            bound,
+            execution_model,
+            entry_point,
+            name,
+            capabilities,
+            execution_modes,
+            interface,
+            decorations,
+            globals,
+            body,
+        } = builder;
+
+        let mut words = vec![
+            0x07230203,
+            version.major << 16 | version.minor << 8,
+            0, // Generator
+            bound,
+            0, // Schema
+        ];
+
+        let instructions = (capabilities.into_iter())
+            .map(|capability| Instruction::Capability { capability })
+            .chain([
+                Instruction::MemoryModel {
+                    addressing_model: AddressingModel::Logical,
+                    memory_model: MemoryModel::GLSL450,
+                },
+                Instruction::EntryPoint {
+                    execution_model,
+                    entry_point,
+                    name,
+                    interface,
+                },
+            ])
+            .chain(
+                (execution_modes.into_iter())
+                    .map(|mode| Instruction::ExecutionMode { entry_point, mode }),
+            )
+            .chain(decorations)
+            .chain(globals)
+            .chain([
+                Instruction::Function {
+                    result_type_id: void,
+                    result_id: entry_point,
+                    function_control: FunctionControl {
+                        inline: false,
+                        dont_inline: false,
+                        pure: false,
+                        constant: false,
+                        opt_none_intel: false,
+                    },
+                    function_type,
+                },
+                Instruction::Label { result_id: label },
+            ])
+            .chain(body)
+            .chain([Instruction::Return, Instruction::FunctionEnd]);
+
+        for instruction in instructions {
+            instruction.write_words(&mut words);
+        }
+
+        words
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpirvBuilder;
+    use crate::{
+        shader::{
+            reflect,
+            spirv::{Decoration, ExecutionMode, ExecutionModel, Instruction, Spirv, StorageClass},
+        },
+        Version,
+    };
+
+    #[test]
+    fn build_and_reparse() {
+        let mut builder = SpirvBuilder::new(ExecutionModel::GLCompute, "main");
+        builder.execution_mode(ExecutionMode::LocalSize {
+            x_size: 8,
+            y_size: 4,
+            z_size: 1,
+        });
+
+        let float = builder.type_float(32);
+        let vec4 = builder.type_vector(float, 4);
+        let block = builder.type_struct(vec![vec4]);
+        builder.decorate(block, Decoration::Block);
+        builder.member_decorate(block, 0, Decoration::Offset { byte_offset: 0 });
+        let uniforms = builder.variable(StorageClass::Uniform, block);
+        builder.decorate(uniforms, Decoration::DescriptorSet { descriptor_set: 0 });
+        builder.decorate(uniforms, Decoration::Binding { binding_point: 1 });
+
+        let result_id = builder.new_id();
+        builder.instruction(Instruction::Load {
+            result_type_id: block,
+            result_id,
+            pointer: uniforms,
+            memory_access: None,
+        });
+
+        // Types that are already declared are reused.
+        assert_eq!(builder.type_float(32), float);
+
+        let spirv = Spirv::new(&builder.build()).unwrap();
+        let (id, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert_eq!(id, builder.entry_point());
+        assert_eq!(info.name, "main");
+        assert_eq!(info.execution_model, ExecutionModel::GLCompute);
+        assert_eq!(info.local_size, Some([8, 4, 1]));
+        assert!(info.descriptor_binding_requirements.contains_key(&(0, 1)));
+    }
+
+    #[test]
+    fn version_ignores_patch() {
+        let mut builder = SpirvBuilder::new(ExecutionModel::GLCompute, "main");
+        builder.set_version(Version {
+            major: 1,
+            minor: 3,
+            patch: 7,
+        });
+
+        let words = builder.build();
+        assert_eq!(words[1], 0x00010300);
+        assert_eq!(Spirv::new(&words).unwrap().version(), Version::V1_3);
+    }
+}
@@ -15,16 +15,26 @@
 //! For more information about SPIR-V modules, instructions and types, see the
 //! [SPIR-V specification](https://registry.khronos.org/SPIR-V/specs/unified1/SPIRV.html).
 
-use crate::{shader::SpecializationConstant, Version};
-use ahash::HashMap;
+use crate::{
+    descriptor_set::layout::DescriptorType,
+    shader::{
+        reflect, DescriptorBindingRequirements, ModuleReflection, ShaderStage, ShaderStages,
+        SpecializationConstant,
+    },
+    ValidationError, Version,
+};
+use ahash::{HashMap, HashSet};
 use smallvec::{smallvec, SmallVec};
 use std::{
     borrow::Cow,
+    collections::hash_map::Entry,
     error::Error,
     fmt::{Display, Error as FmtError, Formatter},
     string::FromUtf8Error,
 };
 
+#[cfg(any(test, feature = "spirv_builder"))]
+pub mod builder;
 mod specialization;
 
 // Generated by build.rs
@@ -48,6 +58,20 @@ pub struct Spirv {
     instructions_decoration: Vec<Instruction>,
     instructions_global: Vec<Instruction>,
     functions: HashMap<Id, FunctionInfo>,
+
+    // The language and version from the first `OpSource` instruction, if there is one.
+    source_language: Option<(SourceLanguage, u32)>,
+
+    // The `OpLine` context at each instruction index where it changes. `None` if there is no
+    // line information from that index on.
+    lines: Vec<(usize, Option<SourceLine>)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct SourceLine {
+    file: Id,
+    line: u32,
+    column: u32,
 }
 
 impl Spirv {
@@ -84,9 +108,32 @@ impl Spirv {
         let mut functions = HashMap::default();
         let mut current_function: Option<&mut Vec<Instruction>> = None;
 
-        for instruction in iter_instructions(&words[5..]) {
+        let mut lines = Vec::new();
+        let mut current_line = None;
+
+        let mut source_language = None;
+
+        for (index, instruction) in iter_instructions(&words[5..]).enumerate() {
             let instruction = instruction?;
 
+            // The scope of an `OpLine` instruction ends at the end of the block it is in.
+            match instruction {
+                Instruction::Line { file, line, column } => {
+                    current_line = Some(SourceLine { file, line, column });
+                }
+                Instruction::NoLine | Instruction::Label { .. } | Instruction::FunctionEnd => {
+                    current_line = None
+                }
+                _ => (),
+            }
+
+            if lines
+                .last()
+                .map_or(current_line.is_some(), |&(_, line)| line != current_line)
+            {
+                lines.push((index, current_line));
+            }
+
             if let Some(id) = instruction.result_id() {
                 bound = bound.max(u32::from(id) + 1);
 
@@ -164,6 +211,14 @@ impl Spirv {
                         });
                         current_function.insert(&mut function.instructions)
                     }
+                    Instruction::Source {
+                        source_language: language,
+                        version,
+                        ..
+                    } => {
+                        source_language = source_language.or(Some((language, version)));
+                        continue;
+                    }
                     Instruction::Capability { .. } => &mut instructions_capability,
                     Instruction::Extension { .. } => &mut instructions_extension,
                     Instruction::ExtInstImport { .. } => &mut instructions_ext_inst_import,
@@ -207,7 +262,9 @@ impl Spirv {
                     | Instruction::TypePipeStorage { .. }
                     | Instruction::TypeNamedBarrier { .. }
                     | Instruction::TypeRayQueryKHR { .. }
+                    | Instruction::TypeAccelerationStructureNV { .. }
                     | Instruction::TypeAccelerationStructureKHR { .. }
+                    | Instruction::TypeCooperativeMatrixKHR { .. }
                     | Instruction::TypeCooperativeMatrixNV { .. }
                     | Instruction::TypeVmeImageINTEL { .. }
                     | Instruction::TypeAvcImePayloadINTEL { .. }
@@ -408,9 +465,37 @@ impl Spirv {
             instructions_decoration,
             instructions_global,
             functions,
+            source_language,
+            lines,
         })
     }
 
+    /// Parses a SPIR-V document from a list of words, ignoring any zero words that follow the
+    /// last instruction.
+    ///
+    /// Some tools pad the module with zeroes after its logical end, which [`Spirv::new`] would
+    /// reject. Trailing words are only ignored if they are all zero.
+    pub fn new_lenient(words: &[u32]) -> Result<Spirv, SpirvError> {
+        let mut end = words.len().min(5);
+
+        while end < words.len() {
+            if words[end..].iter().all(|&word| word == 0) {
+                break;
+            }
+
+            let word_count = (words[end] >> 16) as usize;
+
+            if word_count == 0 {
+                end = words.len();
+                break;
+            }
+
+            end = (end + word_count).min(words.len());
+        }
+
+        Self::new(&words[..end])
+    }
+
     /// Returns the SPIR-V version that the module is compiled for.
     #[inline]
     pub fn version(&self) -> Version {
@@ -439,6 +524,38 @@ impl Spirv {
         &self.functions[&id]
     }
 
+    /// Returns the source location of the instruction at `instruction_index`, as given by the
+    /// `OpLine` instruction that applies to it. The location consists of the file name, which is
+    /// taken from the `OpString` instruction that the `OpLine` refers to, the line and the column.
+    ///
+    /// `instruction_index` is the index of the instruction in the code that the module was parsed
+    /// from, not counting the header. This is the same index that is reported in [`ParseError`].
+    /// Returns `None` if no `OpLine` applies to the instruction.
+    ///
+    /// Modules returned by [`retain_entry_points`](Self::retain_entry_points) don't contain any
+    /// line information.
+    pub fn line_info(&self, instruction_index: usize) -> Option<(String, u32, u32)> {
+        let end = self
+            .lines
+            .partition_point(|&(index, _)| index <= instruction_index);
+        let SourceLine { file, line, column } = self.lines[..end].last()?.1?;
+
+        match self.ids.get(&file)?.instruction() {
+            Instruction::String { string, .. } => Some((string.clone(), line, column)),
+            _ => None,
+        }
+    }
+
+    /// Returns the source language that the module was compiled from and its version, as
+    /// declared by the `OpSource` instruction, or `None` if the module doesn't declare it.
+    ///
+    /// For GLSL, the version is the number in the `#version` directive, such as 450. ESSL is the
+    /// GLSL ES dialect, such as version 310 for `#version 310 es`.
+    #[inline]
+    pub fn source_language(&self) -> Option<(SourceLanguage, u32)> {
+        self.source_language
+    }
+
     /// Returns an iterator over all `Capability` instructions.
     #[inline]
     pub fn iter_capability(&self) -> impl ExactSizeIterator<Item = &Instruction> {
@@ -457,6 +574,19 @@ impl Spirv {
         self.instructions_ext_inst_import.iter()
     }
 
+    /// Returns the names of the extended instruction sets that are imported by `ExtInstImport`
+    /// instructions, such as `GLSL.std.450` or `NonSemantic.DebugPrintf`.
+    #[inline]
+    pub fn ext_inst_imports(&self) -> Vec<&str> {
+        self.instructions_ext_inst_import
+            .iter()
+            .map(|instruction| match instruction {
+                Instruction::ExtInstImport { name, .. } => name.as_str(),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
     /// Returns the `MemoryModel` instruction.
     #[inline]
     pub fn memory_model(&self) -> &Instruction {
@@ -500,6 +630,631 @@ impl Spirv {
         self.functions.values()
     }
 
+    /// Returns a summary of the reflection information of all entry points in the module.
+    pub fn reflect_module(&self) -> ModuleReflection {
+        let entry_points: Vec<_> = reflect::entry_points(self).map(|(_, info)| info).collect();
+        let mut descriptor_binding_requirements: HashMap<
+            u32,
+            HashMap<u32, DescriptorBindingRequirements>,
+        > = HashMap::default();
+        let mut conflicting_bindings = Vec::new();
+
+        for info in &entry_points {
+            for (&(set_num, binding_num), reqs) in &info.descriptor_binding_requirements {
+                match descriptor_binding_requirements
+                    .entry(set_num)
+                    .or_default()
+                    .entry(binding_num)
+                {
+                    Entry::Occupied(entry) => {
+                        let merged = entry.into_mut();
+
+                        if merged.merge(reqs).is_err() {
+                            reflect::merge_aliased_requirements(merged, reqs.clone());
+
+                            if !conflicting_bindings.contains(&(set_num, binding_num)) {
+                                conflicting_bindings.push((set_num, binding_num));
+                            }
+                        }
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(reqs.clone());
+                    }
+                }
+            }
+        }
+
+        conflicting_bindings.sort_unstable();
+
+        let storage_image_formats = descriptor_binding_requirements
+            .values()
+            .flat_map(HashMap::values)
+            .filter(|reqs| {
+                reqs.descriptor_types
+                    .contains(&DescriptorType::StorageImage)
+            })
+            .flat_map(|reqs| {
+                (reqs.descriptors.values())
+                    .map(|desc_reqs| desc_reqs.image_format)
+                    .chain([reqs.image_format])
+            })
+            .flatten()
+            .collect();
+
+        ModuleReflection {
+            version: self.version,
+            capabilities: self
+                .instructions_capability
+                .iter()
+                .map(|instruction| match *instruction {
+                    Instruction::Capability { capability } => capability,
+                    _ => unreachable!(),
+                })
+                .collect(),
+            extensions: self
+                .instructions_extension
+                .iter()
+                .map(|instruction| match instruction {
+                    Instruction::Extension { name } => name.clone(),
+                    _ => unreachable!(),
+                })
+                .collect(),
+            source_language: self.source_language,
+            uses_storage_buffer_class: self
+                .storage_classes()
+                .contains(&StorageClass::StorageBuffer),
+            entry_points,
+            descriptor_binding_requirements,
+            conflicting_bindings,
+            storage_image_formats,
+        }
+    }
+
+    /// Returns the shader stages of all entry points in the module. Entry points with the
+    /// `Kernel` execution model are ignored.
+    pub fn stage_mask(&self) -> ShaderStages {
+        self.instructions_entry_point
+            .iter()
+            .filter_map(|instruction| match *instruction {
+                Instruction::EntryPoint {
+                    execution_model, ..
+                } => ShaderStage::try_from_execution_model(execution_model).ok(),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    /// Returns the name and execution model of each entry point that can't be used with Vulkan,
+    /// which are the entry points with the `Kernel` execution model.
+    ///
+    /// These entry points are skipped by [`reflect::entry_points`] and [`stage_mask`], so this
+    /// can be used to report them.
+    ///
+    /// [`stage_mask`]: Self::stage_mask
+    pub fn unsupported_entry_points(&self) -> Vec<(String, ExecutionModel)> {
+        self.instructions_entry_point
+            .iter()
+            .filter_map(|instruction| match instruction {
+                Instruction::EntryPoint {
+                    execution_model,
+                    name,
+                    ..
+                } => ShaderStage::try_from_execution_model(*execution_model)
+                    .is_err()
+                    .then(|| (name.clone(), *execution_model)),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    /// Returns whether the module has entry points, and they are all compute entry points.
+    #[inline]
+    pub fn is_compute_only(&self) -> bool {
+        self.stage_mask() == ShaderStages::COMPUTE
+    }
+
+    /// Returns whether the module has entry points, and they are all graphics entry points.
+    /// Task and mesh entry points are not included.
+    #[inline]
+    pub fn is_graphics_only(&self) -> bool {
+        let stage_mask = self.stage_mask();
+        !stage_mask.is_empty() && ShaderStages::all_graphics().contains(stage_mask)
+    }
+
+    /// Returns whether the module has an entry point with the given name. If `execution` is
+    /// `Some`, the entry point must also have that execution model.
+    pub fn has_entry_point(&self, name: &str, execution: Option<ExecutionModel>) -> bool {
+        self.instructions_entry_point
+            .iter()
+            .any(|instruction| match instruction {
+                Instruction::EntryPoint {
+                    execution_model,
+                    name: entry_point_name,
+                    ..
+                } => {
+                    entry_point_name == name
+                        && execution.map_or(true, |execution| execution == *execution_model)
+                }
+                _ => unreachable!(),
+            })
+    }
+
+    /// Returns the name and execution model of each entry point that is declared more than once
+    /// with the same name and execution model. Such a module is not valid, and
+    /// [`ShaderModule::entry_point_with_execution`] can't tell the entry points apart, so it
+    /// returns `None` for them.
+    ///
+    /// [`ShaderModule::entry_point_with_execution`]: crate::shader::ShaderModule::entry_point_with_execution
+    pub fn duplicate_entry_points(&self) -> Vec<(String, ExecutionModel)> {
+        let mut seen: HashSet<(&str, ExecutionModel)> = HashSet::default();
+        let mut duplicates: Vec<(String, ExecutionModel)> = Vec::new();
+
+        for instruction in &self.instructions_entry_point {
+            let (name, execution_model) = match instruction {
+                Instruction::EntryPoint {
+                    execution_model,
+                    name,
+                    ..
+                } => (name.as_str(), *execution_model),
+                _ => unreachable!(),
+            };
+
+            if !seen.insert((name, execution_model))
+                && !duplicates
+                    .iter()
+                    .any(|(duplicate_name, duplicate_execution_model)| {
+                        duplicate_name == name && *duplicate_execution_model == execution_model
+                    })
+            {
+                duplicates.push((name.to_owned(), execution_model));
+            }
+        }
+
+        duplicates
+    }
+
+    /// Returns the storage classes of all global variables and pointer types in the module.
+    ///
+    /// This can be used to check whether a module uses, for example, `PhysicalStorageBuffer`
+    /// pointers or push constants. Variables inside functions always have the `Function` storage
+    /// class, which is included through their pointer type.
+    pub fn storage_classes(&self) -> HashSet<StorageClass> {
+        self.instructions_global
+            .iter()
+            .filter_map(|instruction| match *instruction {
+                Instruction::Variable { storage_class, .. }
+                | Instruction::TypePointer { storage_class, .. }
+                | Instruction::TypeForwardPointer { storage_class, .. } => Some(storage_class),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Checks that every capability declared by the module is available in the SPIR-V version of
+    /// the module, either in core or through an extension that the module declares.
+    pub fn validate_version_against_capabilities(&self) -> Result<(), Box<ValidationError>> {
+        let spirv_version = Version {
+            patch: 0, // Ignore the patch version
+            ..self.version
+        };
+        let extensions: Vec<&str> = self
+            .instructions_extension
+            .iter()
+            .map(|instruction| match instruction {
+                Instruction::Extension { name } => name.as_str(),
+                _ => unreachable!(),
+            })
+            .collect();
+
+        for instruction in &self.instructions_capability {
+            let capability = match *instruction {
+                Instruction::Capability { capability } => capability,
+                _ => unreachable!(),
+            };
+
+            if capability
+                .spirv_version()
+                .map_or(false, |version| spirv_version >= version)
+                || (capability.spirv_extensions().iter()).any(|ext| extensions.contains(ext))
+            {
+                continue;
+            }
+
+            let required_version = match capability.spirv_version() {
+                Some(version) => format!("SPIR-V version {}.{}", version.major, version.minor),
+                None => "an extension".into(),
+            };
+
+            return Err(Box::new(ValidationError {
+                problem: format!(
+                    "declares the `Capability::{:?}` capability, which requires {} or one of the \
+                    extensions {:?}, but the module has SPIR-V version {}.{} and does not \
+                    declare any of these extensions",
+                    capability,
+                    required_version,
+                    capability.spirv_extensions(),
+                    spirv_version.major,
+                    spirv_version.minor,
+                )
+                .into(),
+                vuids: &["VUID-VkShaderModuleCreateInfo-pCode-01379"],
+                ..Default::default()
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the members of the blocks of uniform buffers, storage buffers, push constants
+    /// and shader records don't overlap, according to their `Offset` decorations and their sizes.
+    /// Structs that are nested in these blocks are checked as well.
+    ///
+    /// A module that was compiled from GLSL or HLSL always passes this check, but a hand-written
+    /// or otherwise malformed module might not. The check is not performed when creating a
+    /// shader module, because the Vulkan specification has no valid usage rule for it.
+    ///
+    /// Members that follow an array whose length is a specialization constant are not checked,
+    /// because the length of the array is not known until the constant is specialized.
+    #[inline]
+    pub fn validate_block_layouts(&self) -> Result<(), Box<ValidationError>> {
+        reflect::validate_block_layouts(self)
+    }
+
+    /// Encodes the module back into SPIR-V words.
+    ///
+    /// The words are written from the parsed instructions, so any changes made to the module,
+    /// such as by [`apply_specialization`](Self::apply_specialization), are included. Debug
+    /// instructions other than `OpString`, `OpName` and `OpMemberName` are not preserved,
+    /// decoration groups are written as individual decorations, and the generator magic number
+    /// is set to 0.
+    pub fn to_words(&self) -> Vec<u32> {
+        let mut words = vec![
+            0x07230203,
+            self.version.major << 16 | self.version.minor << 8 | self.version.patch,
+            0, // Generator
+            self.bound,
+            0, // Schema
+        ];
+
+        let mut strings: Vec<_> = (self.ids.values())
+            .map(IdInfo::instruction)
+            .filter(|instruction| matches!(instruction, Instruction::String { .. }))
+            .collect();
+        strings.sort_unstable_by_key(|instruction| instruction.result_id().map(Id::as_raw));
+
+        // Function declarations must come before function definitions.
+        let mut functions: Vec<_> = self.functions.iter().collect();
+        functions.sort_unstable_by_key(|&(&id, function_info)| {
+            let is_definition = (function_info.instructions.iter())
+                .any(|instruction| matches!(instruction, Instruction::Label { .. }));
+            (is_definition, id.as_raw())
+        });
+
+        for instruction in (self.instructions_capability.iter())
+            .chain(&self.instructions_extension)
+            .chain(&self.instructions_ext_inst_import)
+            .chain([&self.instruction_memory_model])
+            .chain(&self.instructions_entry_point)
+            .chain(&self.instructions_execution_mode)
+            .chain(strings)
+            .chain(&self.instructions_name)
+            .chain(&self.instructions_decoration)
+            .chain(&self.instructions_global)
+            .chain(
+                functions
+                    .into_iter()
+                    .flat_map(|(_, function_info)| function_info.instructions.iter()),
+            )
+        {
+            instruction.write_words(&mut words);
+        }
+
+        words
+    }
+
+    /// Returns a copy of the module that contains only the `EntryPoint` instructions whose name is
+    /// in `names`, together with their execution modes and everything that they transitively
+    /// reference. Functions, types, constants and global variables that are only used by the
+    /// other entry points are removed, along with their names and decorations.
+    #[inline]
+    pub fn retain_entry_points(&self, names: &[&str]) -> Spirv {
+        self.retain_entry_points_filter(|name, _| names.contains(&name))
+    }
+
+    fn retain_entry_points_filter(&self, filter: impl Fn(&str, ExecutionModel) -> bool) -> Spirv {
+        let instructions_entry_point: Vec<_> = self
+            .instructions_entry_point
+            .iter()
+            .filter(|instruction| match instruction {
+                Instruction::EntryPoint {
+                    execution_model,
+                    name,
+                    ..
+                } => filter(name, *execution_model),
+                _ => unreachable!(),
+            })
+            .cloned()
+            .collect();
+        let entry_point_ids: HashSet<Id> = instructions_entry_point
+            .iter()
+            .map(|instruction| match *instruction {
+                Instruction::EntryPoint { entry_point, .. } => entry_point,
+                _ => unreachable!(),
+            })
+            .collect();
+        let instructions_execution_mode: Vec<_> = self
+            .instructions_execution_mode
+            .iter()
+            .filter(|instruction| match **instruction {
+                Instruction::ExecutionMode { entry_point, .. }
+                | Instruction::ExecutionModeId { entry_point, .. } => {
+                    entry_point_ids.contains(&entry_point)
+                }
+                _ => unreachable!(),
+            })
+            .cloned()
+            .collect();
+
+        // Find all ids that are transitively referenced by the retained entry points.
+        let mut live_ids: HashSet<Id> = HashSet::default();
+        let mut pending_ids: Vec<Id> = Vec::new();
+        let mut push_id = |id| pending_ids.push(id);
+
+        for instruction in instructions_entry_point
+            .iter()
+            .chain(&instructions_execution_mode)
+        {
+            instruction.visit_ids(&mut push_id);
+        }
+
+        while let Some(id) = pending_ids.pop() {
+            if !live_ids.insert(id) {
+                continue;
+            }
+
+            let mut push_id = |id| pending_ids.push(id);
+
+            if let Some(function_info) = self.functions.get(&id) {
+                for instruction in &function_info.instructions {
+                    if let Some(result_id) = instruction.result_id() {
+                        push_id(result_id);
+                    }
+
+                    instruction.visit_ids(&mut push_id);
+                }
+            }
+
+            if let Some(id_info) = self.ids.get(&id) {
+                id_info.instruction.visit_ids(&mut push_id);
+
+                for instruction in id_info.decorations.iter().chain(
+                    (id_info.members.iter()).flat_map(|member_info| &member_info.decorations),
+                ) {
+                    instruction.visit_ids(&mut push_id);
+                }
+            }
+        }
+
+        let ids = (self.ids.iter())
+            .filter(|(id, _)| live_ids.contains(id))
+            .map(|(&id, id_info)| (id, id_info.clone()))
+            .collect();
+        let instructions_name = (self.instructions_name.iter())
+            .filter(|instruction| match **instruction {
+                Instruction::Name { target, .. } => live_ids.contains(&target),
+                Instruction::MemberName { ty, .. } => live_ids.contains(&ty),
+                _ => unreachable!(),
+            })
+            .cloned()
+            .collect();
+        let instructions_decoration = (self.instructions_decoration.iter())
+            .filter(|instruction| match **instruction {
+                Instruction::Decorate { target, .. }
+                | Instruction::DecorateId { target, .. }
+                | Instruction::DecorateString { target, .. }
+                | Instruction::MemberDecorate {
+                    structure_type: target,
+                    ..
+                }
+                | Instruction::MemberDecorateString {
+                    struct_type: target,
+                    ..
+                } => live_ids.contains(&target),
+                _ => unreachable!(),
+            })
+            .cloned()
+            .collect();
+        let instructions_global = (self.instructions_global.iter())
+            .filter(|instruction| match **instruction {
+                Instruction::TypeForwardPointer { pointer_type, .. } => {
+                    live_ids.contains(&pointer_type)
+                }
+                _ => instruction
+                    .result_id()
+                    .map_or(false, |result_id| live_ids.contains(&result_id)),
+            })
+            .cloned()
+            .collect();
+        let functions = (self.functions.iter())
+            .filter(|(id, _)| live_ids.contains(id))
+            .map(|(&id, function_info)| {
+                let mut function_info = function_info.clone();
+
+                // The function may be called by a retained entry point, while being an entry point
+                // itself that was removed.
+                if !entry_point_ids.contains(&id) {
+                    function_info.entry_point = None;
+                    function_info.execution_modes.clear();
+                }
+
+                (id, function_info)
+            })
+            .collect();
+
+        Spirv {
+            version: self.version,
+            bound: self.bound,
+            ids,
+            instructions_capability: self.instructions_capability.clone(),
+            instructions_extension: self.instructions_extension.clone(),
+            instructions_ext_inst_import: self.instructions_ext_inst_import.clone(),
+            instruction_memory_model: self.instruction_memory_model.clone(),
+            instructions_entry_point,
+            instructions_execution_mode,
+            instructions_name,
+            instructions_decoration,
+            instructions_global,
+            functions,
+            source_language: self.source_language,
+            lines: Vec::new(),
+        }
+    }
+
+    /// Returns a copy of the module that contains only the entry point named `name`, and
+    /// everything that it transitively references. This is like
+    /// [`retain_entry_points`](Self::retain_entry_points), but returns an error if there is not
+    /// exactly one entry point named `name`. Entry points with different execution models can
+    /// have the same name; use
+    /// [`extract_entry_point_with_execution`](Self::extract_entry_point_with_execution) to
+    /// extract one of them.
+    ///
+    /// The capabilities, extensions and memory model of the original module are kept as-is.
+    #[inline]
+    pub fn extract_entry_point(&self, name: &str) -> Result<Spirv, Box<ValidationError>> {
+        self.extract_entry_point_filter(name, None)
+    }
+
+    /// Returns a copy of the module that contains only the entry point named `name` with the
+    /// execution model `execution`, and everything that it transitively references. Returns an
+    /// error if there is not exactly one such entry point.
+    ///
+    /// The capabilities, extensions and memory model of the original module are kept as-is.
+    #[inline]
+    pub fn extract_entry_point_with_execution(
+        &self,
+        name: &str,
+        execution: ExecutionModel,
+    ) -> Result<Spirv, Box<ValidationError>> {
+        self.extract_entry_point_filter(name, Some(execution))
+    }
+
+    fn extract_entry_point_filter(
+        &self,
+        name: &str,
+        execution: Option<ExecutionModel>,
+    ) -> Result<Spirv, Box<ValidationError>> {
+        let is_match = |entry_point_name: &str, execution_model| {
+            entry_point_name == name
+                && execution.map_or(true, |execution| execution == execution_model)
+        };
+        let count = self
+            .instructions_entry_point
+            .iter()
+            .filter(|instruction| match instruction {
+                Instruction::EntryPoint {
+                    execution_model,
+                    name,
+                    ..
+                } => is_match(name, *execution_model),
+                _ => unreachable!(),
+            })
+            .count();
+
+        if count != 1 {
+            let entry_point = match execution {
+                Some(execution) => format!("`{:?}` entry point named `{}`", execution, name),
+                None => format!("entry point named `{}`", name),
+            };
+
+            return Err(Box::new(ValidationError {
+                context: "name".into(),
+                problem: if count == 0 {
+                    format!("the module has no {}", entry_point)
+                } else {
+                    format!("the module has more than one {}", entry_point)
+                }
+                .into(),
+                ..Default::default()
+            }));
+        }
+
+        Ok(self.retain_entry_points_filter(is_match))
+    }
+
+    /// Renames the entry points named `old` to `new`. `OpName` instructions on the entry point
+    /// functions that have the old name are updated as well.
+    ///
+    /// An error is returned if there is no entry point named `old`, or if there already is an
+    /// entry point named `new`.
+    pub fn rename_entry_point(&mut self, old: &str, new: &str) -> Result<(), Box<ValidationError>> {
+        let has_entry_point = |name: &str| {
+            self.instructions_entry_point.iter().any(|instruction| {
+                matches!(instruction, Instruction::EntryPoint { name: entry_point_name, .. }
+                    if entry_point_name == name)
+            })
+        };
+
+        if !has_entry_point(old) {
+            return Err(Box::new(ValidationError {
+                context: "old".into(),
+                problem: format!("the module has no entry point named `{}`", old).into(),
+                ..Default::default()
+            }));
+        }
+
+        if old == new {
+            return Ok(());
+        }
+
+        if has_entry_point(new) {
+            return Err(Box::new(ValidationError {
+                context: "new".into(),
+                problem: format!("the module already has an entry point named `{}`", new).into(),
+                ..Default::default()
+            }));
+        }
+
+        let rename = |instruction: &mut Instruction| match instruction {
+            Instruction::EntryPoint { name, .. } | Instruction::Name { name, .. }
+                if name == old =>
+            {
+                *name = new.to_owned();
+            }
+            _ => (),
+        };
+        let mut renamed_functions: HashSet<Id> = HashSet::default();
+
+        for instruction in &mut self.instructions_entry_point {
+            if let Instruction::EntryPoint {
+                entry_point, name, ..
+            } = instruction
+            {
+                if name == old {
+                    *name = new.to_owned();
+                    renamed_functions.insert(*entry_point);
+                }
+            }
+        }
+
+        for function_id in &renamed_functions {
+            let function_info = self.functions.get_mut(function_id).unwrap();
+            function_info.entry_point.iter_mut().for_each(rename);
+
+            let id_info = self.ids.get_mut(function_id).unwrap();
+            id_info.names.iter_mut().for_each(rename);
+        }
+
+        for instruction in &mut self.instructions_name {
+            if let Instruction::Name { target, .. } = *instruction {
+                if renamed_functions.contains(&target) {
+                    rename(instruction);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn apply_specialization(
         &mut self,
         specialization_info: &HashMap<u32, SpecializationConstant>,
@@ -646,12 +1401,56 @@ pub struct FunctionInfo {
 }
 
 impl FunctionInfo {
+    /// Returns the `Id` of the function, which is the result of its `Function` instruction.
+    #[inline]
+    pub fn id(&self) -> Id {
+        self.instructions[0].result_id().unwrap()
+    }
+
     /// Returns an iterator over all instructions in the function.
     #[inline]
     pub fn iter_instructions(&self) -> impl ExactSizeIterator<Item = &Instruction> {
         self.instructions.iter()
     }
 
+    /// Returns an iterator over the `FunctionParameter` instructions of the function.
+    #[inline]
+    pub fn iter_parameters(&self) -> impl Iterator<Item = &Instruction> {
+        self.instructions[1..]
+            .iter()
+            .take_while(|instruction| matches!(instruction, Instruction::FunctionParameter { .. }))
+    }
+
+    /// Returns an iterator over the basic blocks of the function. Each block is given as a slice
+    /// of instructions, starting with its `Label` instruction and ending with its terminator.
+    ///
+    /// The blocks are returned in the order that they appear in the function, which is not
+    /// necessarily the order in which they are executed. The first block is the entry block of
+    /// the function. A function that is only declared, and not defined, has no blocks.
+    pub fn iter_blocks(&self) -> impl Iterator<Item = &[Instruction]> {
+        let mut rest = match self
+            .instructions
+            .iter()
+            .position(|instruction| matches!(instruction, Instruction::Label { .. }))
+        {
+            // Exclude the `FunctionEnd` instruction.
+            Some(start) => &self.instructions[start..self.instructions.len() - 1],
+            None => &[],
+        };
+
+        std::iter::from_fn(move || {
+            let (_, tail) = rest.split_first()?;
+            let len = tail
+                .iter()
+                .position(|instruction| matches!(instruction, Instruction::Label { .. }))
+                .map_or(rest.len(), |index| index + 1);
+            let (block, remaining) = rest.split_at(len);
+            rest = remaining;
+
+            Some(block)
+        })
+    }
+
     /// Returns the `EntryPoint` instruction that targets this function, if there is one.
     #[inline]
     pub fn entry_point(&self) -> Option<&Instruction> {
@@ -675,7 +1474,15 @@ fn iter_instructions(
         }
 
         let word_count = (words[0] >> 16) as usize;
-        assert!(word_count >= 1);
+
+        if word_count == 0 {
+            return Some(Err(ParseError {
+                instruction: index,
+                word: 0,
+                error: ParseErrors::InvalidWordCount,
+                words: words.to_owned(),
+            }));
+        }
 
         if words.len() < word_count {
             return Some(Err(ParseError {
@@ -704,6 +1511,22 @@ fn iter_instructions(
     std::iter::from_fn(next)
 }
 
+impl Scope {
+    /// Returns the scope with the given value, as it appears in a constant that is used as a
+    /// `Scope` operand.
+    pub(crate) fn from_value(value: u32) -> Option<Self> {
+        Self::parse(&mut InstructionReader::new(&[value], 0)).ok()
+    }
+}
+
+impl CooperativeMatrixUse {
+    /// Returns the use with the given value, as it appears in a constant that is used as the
+    /// `Use` operand of `OpTypeCooperativeMatrixKHR`.
+    pub(crate) fn from_value(value: u32) -> Option<Self> {
+        Self::parse(&mut InstructionReader::new(&[value], 0)).ok()
+    }
+}
+
 /// Helper type for parsing the words of an instruction.
 #[derive(Debug)]
 struct InstructionReader<'a> {
@@ -785,6 +1608,17 @@ impl<'a> InstructionReader<'a> {
     }
 }
 
+/// Writes a nul-terminated string to `words`, padded to a multiple of 4 bytes.
+fn write_string(words: &mut Vec<u32>, string: &str) {
+    let mut bytes = string.as_bytes().to_vec();
+    bytes.resize((bytes.len() / 4 + 1) * 4, 0);
+    words.extend(
+        bytes
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap())),
+    );
+}
+
 /// Error that can happen when reading a SPIR-V module.
 #[derive(Clone, Debug)]
 pub enum SpirvError {
@@ -812,12 +1646,66 @@ impl Error for SpirvError {
     }
 }
 
+impl SpirvError {
+    /// Returns the kind of failure that caused the error, for handling it programmatically.
+    pub fn kind(&self) -> SpirvErrorKind {
+        match self {
+            Self::DuplicateId { .. } => SpirvErrorKind::Other,
+            Self::InvalidHeader => SpirvErrorKind::InvalidHeader,
+            Self::ParseError(err) => match err.error {
+                ParseErrors::InvalidWordCount => SpirvErrorKind::InvalidWordCount,
+                ParseErrors::UnexpectedEOF => SpirvErrorKind::UnexpectedEndOfStream,
+                ParseErrors::UnknownOpcode(opcode) => SpirvErrorKind::UnknownOpcode(opcode),
+                ParseErrors::FromUtf8Error(_)
+                | ParseErrors::LeftoverOperands
+                | ParseErrors::MissingOperands
+                | ParseErrors::UnknownEnumerant(..)
+                | ParseErrors::UnknownSpecConstantOpcode(_) => SpirvErrorKind::Other,
+            },
+        }
+    }
+}
+
 impl From<ParseError> for SpirvError {
     fn from(err: ParseError) -> Self {
         Self::ParseError(err)
     }
 }
 
+/// The kind of failure that caused a SPIR-V module to fail to parse, as returned by
+/// [`SpirvError::kind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SpirvErrorKind {
+    /// The module does not start with the SPIR-V magic number, or is too short to contain a
+    /// complete header.
+    InvalidHeader,
+
+    /// The module ends in the middle of an instruction.
+    UnexpectedEndOfStream,
+
+    /// An instruction has an opcode that is not known.
+    UnknownOpcode(u16),
+
+    /// An instruction has a word count of zero.
+    InvalidWordCount,
+
+    /// Another error, such as an invalid operand or an id that is assigned more than once.
+    Other,
+}
+
+impl Display for SpirvErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::InvalidHeader => write!(f, "the SPIR-V module header is invalid"),
+            Self::UnexpectedEndOfStream => write!(f, "encountered unexpected end of file"),
+            Self::UnknownOpcode(opcode) => write!(f, "invalid instruction opcode {}", opcode),
+            Self::InvalidWordCount => write!(f, "an instruction has a word count of zero"),
+            Self::Other => write!(f, "the SPIR-V module is invalid"),
+        }
+    }
+}
+
 /// Error that can happen when parsing SPIR-V instructions into Rust data structures.
 #[derive(Clone, Debug)]
 pub struct ParseError {
@@ -847,6 +1735,7 @@ impl Error for ParseError {}
 #[derive(Clone, Debug)]
 pub enum ParseErrors {
     FromUtf8Error(FromUtf8Error),
+    InvalidWordCount,
     LeftoverOperands,
     MissingOperands,
     UnexpectedEOF,
@@ -859,6 +1748,7 @@ impl Display for ParseErrors {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         match self {
             Self::FromUtf8Error(_) => write!(f, "invalid UTF-8 in string literal"),
+            Self::InvalidWordCount => write!(f, "the instruction has a word count of zero"),
             Self::LeftoverOperands => write!(f, "unparsed operands remaining"),
             Self::MissingOperands => write!(
                 f,
@@ -910,3 +1800,1202 @@ impl Display for SpirvBytesNotMultipleOf4 {
         write!(f, "the length of the provided slice is not a multiple of 4")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        builder::SpirvBuilder, BuiltIn, Capability, Decoration, Dim, ExecutionMode, ExecutionModel,
+        Id, ImageFormat, Instruction, SpecConstantInstruction, Spirv, SpirvErrorKind, StorageClass,
+    };
+    use crate::{
+        descriptor_set::layout::DescriptorType,
+        format::NumericType,
+        shader::{reflect, ShaderModule, ShaderModuleCreateInfo, ShaderStage, ShaderStages},
+        Version,
+    };
+
+    /*
+    OpCapability Shader
+    OpMemoryModel Logical GLSL450
+    OpEntryPoint GLCompute %main_a "main_a"
+    OpEntryPoint GLCompute %main_b "main_b"
+    OpExecutionMode %main_a LocalSize 1 1 1
+    OpExecutionMode %main_b LocalSize 64 1 1
+    OpName %main_a "main_a"
+    OpName %main_b "main_b"
+    OpName %helper "helper"
+    OpName %data_a "data_a"
+    OpName %data_b "data_b"
+    OpDecorate %block_a Block
+    OpMemberDecorate %block_a 0 Offset 0
+    OpDecorate %data_a DescriptorSet 0
+    OpDecorate %data_a Binding 0
+    OpDecorate %block_b Block
+    OpMemberDecorate %block_b 0 Offset 0
+    OpDecorate %data_b DescriptorSet 0
+    OpDecorate %data_b Binding 1
+    %void = OpTypeVoid
+    %fn = OpTypeFunction %void
+    %uint = OpTypeInt 32 0
+    %float = OpTypeFloat 32
+    %block_a = OpTypeStruct %uint
+    %block_b = OpTypeStruct %float
+    %ptr_block_a = OpTypePointer StorageBuffer %block_a
+    %ptr_block_b = OpTypePointer StorageBuffer %block_b
+    %ptr_uint = OpTypePointer StorageBuffer %uint
+    %ptr_float = OpTypePointer StorageBuffer %float
+    %data_a = OpVariable %ptr_block_a StorageBuffer
+    %data_b = OpVariable %ptr_block_b StorageBuffer
+    %uint_0 = OpConstant %uint 0
+    %uint_1 = OpConstant %uint 1
+    %float_1 = OpConstant %float 1
+    %main_a = OpFunction %void None %fn
+    %label_a = OpLabel
+    %call = OpFunctionCall %void %helper
+    OpReturn
+    OpFunctionEnd
+    %helper = OpFunction %void None %fn
+    %label_helper = OpLabel
+    %pa = OpAccessChain %ptr_uint %data_a %uint_0
+    OpStore %pa %uint_1
+    OpReturn
+    OpFunctionEnd
+    %main_b = OpFunction %void None %fn
+    %label_b = OpLabel
+    %pb = OpAccessChain %ptr_float %data_b %uint_0
+    OpStore %pb %float_1
+    OpReturn
+    OpFunctionEnd
+    */
+    const MULTIPLE_ENTRY_POINTS: [u32; 185] = [
+        119734787, 66304, 0, 25, 0, 131089, 1, 196622, 0, 1, 327695, 5, 1, 1852399981, 24927,
+        327695, 5, 2, 1852399981, 25183, 393232, 1, 17, 1, 1, 1, 393232, 2, 17, 64, 1, 1, 262149,
+        1, 1852399981, 24927, 262149, 2, 1852399981, 25183, 262149, 3, 1886152040, 29285, 262149,
+        4, 1635017060, 24927, 262149, 5, 1635017060, 25183, 196679, 6, 2, 327752, 6, 0, 35, 0,
+        262215, 4, 34, 0, 262215, 4, 33, 0, 196679, 7, 2, 327752, 7, 0, 35, 0, 262215, 5, 34, 0,
+        262215, 5, 33, 1, 131091, 8, 196641, 9, 8, 262165, 10, 32, 0, 196630, 11, 32, 196638, 6,
+        10, 196638, 7, 11, 262176, 12, 12, 6, 262176, 13, 12, 7, 262176, 14, 12, 10, 262176, 15,
+        12, 11, 262203, 12, 4, 12, 262203, 13, 5, 12, 262187, 10, 16, 0, 262187, 10, 17, 1, 262187,
+        11, 18, 1, 327734, 8, 1, 0, 9, 131320, 19, 262201, 8, 20, 3, 65789, 65592, 327734, 8, 3, 0,
+        9, 131320, 21, 327745, 14, 22, 4, 16, 196670, 22, 17, 65789, 65592, 327734, 8, 2, 0, 9,
+        131320, 23, 327745, 15, 24, 5, 16, 196670, 24, 18, 65789, 65592,
+    ];
+
+    #[test]
+    fn retain_entry_points() {
+        let spirv = Spirv::new(&MULTIPLE_ENTRY_POINTS).unwrap();
+        assert_eq!(reflect::entry_points(&spirv).count(), 2);
+
+        let trimmed = spirv.retain_entry_points(&["main_a"]);
+        let entry_points: Vec<_> = reflect::entry_points(&trimmed).collect();
+        assert_eq!(entry_points.len(), 1);
+
+        let (_, info) = &entry_points[0];
+        assert_eq!(info.name, "main_a");
+        assert!(info.descriptor_binding_requirements.contains_key(&(0, 0)));
+        assert!(!info.descriptor_binding_requirements.contains_key(&(0, 1)));
+
+        // `main_a` and `helper` are kept, `main_b` and everything only it uses are removed.
+        assert_eq!(trimmed.iter_functions().count(), 2);
+        assert_eq!(trimmed.iter_execution_mode().count(), 1);
+        assert_eq!(trimmed.iter_name().count(), 3);
+        assert!(!trimmed
+            .iter_global()
+            .any(|instruction| matches!(instruction, super::Instruction::TypeFloat { .. })));
+
+        let trimmed = spirv.retain_entry_points(&["main_b"]);
+        let entry_points: Vec<_> = reflect::entry_points(&trimmed).collect();
+        assert_eq!(entry_points.len(), 1);
+        assert_eq!(trimmed.iter_functions().count(), 1);
+
+        let (_, info) = &entry_points[0];
+        assert!(info.descriptor_binding_requirements.contains_key(&(0, 1)));
+    }
+
+    #[test]
+    fn to_words_round_trip() {
+        let spirv = Spirv::new(&MULTIPLE_ENTRY_POINTS).unwrap();
+        let words = spirv.to_words();
+        assert_eq!(words.len(), MULTIPLE_ENTRY_POINTS.len());
+        assert_eq!(words[..5], [0x07230203, 0x00010300, 0, spirv.bound, 0]);
+
+        let reparsed = Spirv::new(&words).unwrap();
+        assert_eq!(reparsed.to_words(), words);
+        assert_eq!(
+            reflect::entry_points(&reparsed).count(),
+            reflect::entry_points(&spirv).count(),
+        );
+    }
+
+    #[test]
+    fn rename_entry_point() {
+        /*
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint Vertex %main "main"
+        OpName %main "main"
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %main = OpFunction %void None %fn
+        %l = OpLabel
+        OpReturn
+        OpFunctionEnd
+        */
+        const VERTEX_MAIN: &[u32] = &[
+            119734787, 65536, 0, 5, 0, 131089, 1, 196622, 0, 1, 327695, 0, 1, 1852399981, 0,
+            262149, 1, 1852399981, 0, 131091, 2, 196641, 3, 2, 327734, 2, 1, 0, 3, 131320, 4,
+            65789, 65592,
+        ];
+
+        let mut spirv = Spirv::new(VERTEX_MAIN).unwrap();
+        spirv.rename_entry_point("main", "vs_main").unwrap();
+
+        let (id, info) = reflect::entry_points(&spirv).next().unwrap();
+        assert_eq!(info.name, "vs_main");
+        assert!(spirv.id(id).iter_name().all(|instruction| matches!(
+            instruction,
+            Instruction::Name { name, .. } if name == "vs_main"
+        )));
+
+        // The new name is kept when the module is encoded.
+        let reparsed = Spirv::new(&spirv.to_words()).unwrap();
+        let (_, info) = reflect::entry_points(&reparsed).next().unwrap();
+        assert_eq!(info.name, "vs_main");
+
+        assert!(spirv.rename_entry_point("main", "fs_main").is_err());
+
+        let mut spirv = Spirv::new(&MULTIPLE_ENTRY_POINTS).unwrap();
+        assert!(spirv.rename_entry_point("main_a", "main_b").is_err());
+    }
+
+    #[test]
+    fn stage_mask() {
+        /*
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %cs "cs_main"
+        OpEntryPoint Vertex %vs "vs_main"
+        OpExecutionMode %cs LocalSize 1 1 1
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %cs = OpFunction %void None %fn
+        %l1 = OpLabel
+        OpReturn
+        OpFunctionEnd
+        %vs = OpFunction %void None %fn
+        %l2 = OpLabel
+        OpReturn
+        OpFunctionEnd
+        */
+        const MIXED: &[u32] = &[
+            119734787, 65536, 0, 7, 0, 131089, 1, 196622, 0, 1, 327695, 5, 1, 1834972003, 7235937,
+            327695, 0, 2, 1834972022, 7235937, 393232, 1, 17, 1, 1, 1, 131091, 3, 196641, 4, 3,
+            327734, 3, 1, 0, 4, 131320, 5, 65789, 65592, 327734, 3, 2, 0, 4, 131320, 6, 65789,
+            65592,
+        ];
+
+        let spirv = Spirv::new(MIXED).unwrap();
+        let stage_mask = spirv.stage_mask();
+        assert!(stage_mask.contains(ShaderStages::COMPUTE | ShaderStages::VERTEX));
+        assert!(!spirv.is_compute_only());
+        assert!(!spirv.is_graphics_only());
+
+        let compute = spirv.retain_entry_points(&["cs_main"]);
+        assert_eq!(compute.stage_mask(), ShaderStages::COMPUTE);
+        assert!(compute.is_compute_only());
+
+        let vertex = spirv.retain_entry_points(&["vs_main"]);
+        assert!(vertex.is_graphics_only());
+    }
+
+    #[test]
+    fn has_entry_point() {
+        let spirv = Spirv::new(&MULTIPLE_ENTRY_POINTS).unwrap();
+        assert!(spirv.has_entry_point("main_a", None));
+        assert!(spirv.has_entry_point("main_b", Some(ExecutionModel::GLCompute)));
+        assert!(!spirv.has_entry_point("main_a", Some(ExecutionModel::Vertex)));
+        assert!(!spirv.has_entry_point("main_c", None));
+        assert!(!spirv.has_entry_point("helper", None));
+    }
+
+    /*
+    ; SPIR-V 1.0
+    OpCapability Shader
+    OpMemoryModel Logical GLSL450
+    OpEntryPoint GLCompute %main_a "main"
+    OpEntryPoint GLCompute %main_b "main"
+    OpEntryPoint Vertex %main_c "main"
+    OpExecutionMode %main_a LocalSize 1 1 1
+    OpExecutionMode %main_b LocalSize 1 1 1
+    %void = OpTypeVoid
+    %fn = OpTypeFunction %void
+    %main_a = OpFunction %void None %fn
+    %label_a = OpLabel
+    OpReturn
+    OpFunctionEnd
+    %main_b = OpFunction %void None %fn
+    %label_b = OpLabel
+    OpReturn
+    OpFunctionEnd
+    %main_c = OpFunction %void None %fn
+    %label_c = OpLabel
+    OpReturn
+    OpFunctionEnd
+    */
+    const DUPLICATE_ENTRY_POINTS: [u32; 69] = [
+        119734787, 65536, 0, 9, 0, 131089, 1, 196622, 0, 1, 327695, 5, 1, 1852399981, 0, 327695, 5,
+        2, 1852399981, 0, 327695, 0, 3, 1852399981, 0, 393232, 1, 17, 1, 1, 1, 393232, 2, 17, 1, 1,
+        1, 131091, 4, 196641, 5, 4, 327734, 4, 1, 0, 5, 131320, 6, 65789, 65592, 327734, 4, 2, 0,
+        5, 131320, 7, 65789, 65592, 327734, 4, 3, 0, 5, 131320, 8, 65789, 65592,
+    ];
+
+    #[test]
+    fn duplicate_entry_points() {
+        let spirv = Spirv::new(&DUPLICATE_ENTRY_POINTS).unwrap();
+        assert_eq!(
+            spirv.duplicate_entry_points(),
+            [("main".to_owned(), ExecutionModel::GLCompute)],
+        );
+
+        let spirv = Spirv::new(&MULTIPLE_ENTRY_POINTS).unwrap();
+        assert!(spirv.duplicate_entry_points().is_empty());
+    }
+
+    #[test]
+    fn parse_error_kind() {
+        let kind = |words: &[u32]| Spirv::new(words).unwrap_err().kind();
+        let header = [0x07230203, 0x00010000, 0, 1, 0];
+        let with_header = |instruction: &[u32]| [&header[..], instruction].concat();
+
+        assert_eq!(
+            kind(&[0x03022307, 0x00010000, 0, 1, 0]),
+            SpirvErrorKind::InvalidHeader,
+        );
+        assert_eq!(kind(&header[..3]), SpirvErrorKind::InvalidHeader);
+        // OpCapability with a word count of 2, but no operand.
+        assert_eq!(
+            kind(&with_header(&[0x00020011])),
+            SpirvErrorKind::UnexpectedEndOfStream,
+        );
+        assert_eq!(
+            kind(&with_header(&[0x0001ffff])),
+            SpirvErrorKind::UnknownOpcode(0xffff),
+        );
+        assert_eq!(
+            kind(&with_header(&[0x00000011])),
+            SpirvErrorKind::InvalidWordCount,
+        );
+        // OpCapability with an extra operand.
+        assert_eq!(
+            kind(&with_header(&[0x00030011, 1, 0])),
+            SpirvErrorKind::Other,
+        );
+    }
+
+    #[test]
+    fn shader_module_parse_error() {
+        use crate::{shader::CreateShaderModuleError, Validated};
+
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        match unsafe {
+            ShaderModule::new(
+                device,
+                ShaderModuleCreateInfo::new(&[0x07230203, 0x00010000]),
+            )
+        } {
+            Err(Validated::Error(CreateShaderModuleError::SpirvError(err))) => {
+                assert_eq!(err.kind(), SpirvErrorKind::InvalidHeader);
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn extract_entry_point() {
+        let spirv = Spirv::new(&MULTIPLE_ENTRY_POINTS).unwrap();
+        assert!(spirv.extract_entry_point("main_c").is_err());
+
+        let extracted = spirv.extract_entry_point("main_b").unwrap();
+        let entry_points: Vec<_> = reflect::entry_points(&extracted).collect();
+        assert_eq!(entry_points.len(), 1);
+        assert_eq!(entry_points[0].1.name, "main_b");
+        assert_eq!(extracted.iter_functions().count(), 1);
+
+        let (device, _queue) = gfx_dev_and_queue!();
+        let module = unsafe {
+            ShaderModule::new(device, ShaderModuleCreateInfo::new(&extracted.to_words())).unwrap()
+        };
+        assert!(module.entry_point("main_b").is_some());
+        assert!(module.entry_point("main_a").is_none());
+    }
+
+    #[test]
+    fn extract_entry_point_with_execution() {
+        /*
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint Vertex %vs "main"
+        OpEntryPoint Fragment %fs "main"
+        OpExecutionMode %fs OriginUpperLeft
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %vs = OpFunction %void None %fn
+        %l1 = OpLabel
+        OpReturn
+        OpFunctionEnd
+        %fs = OpFunction %void None %fn
+        %l2 = OpLabel
+        OpReturn
+        OpFunctionEnd
+        */
+        const SAME_NAME: [u32; 46] = [
+            119734787, 65536, 0, 7, 0, 131089, 1, 196622, 0, 1, 327695, 0, 1, 1852399981, 0,
+            327695, 4, 2, 1852399981, 0, 196624, 2, 7, 131091, 3, 196641, 4, 3, 327734, 3, 1, 0, 4,
+            131320, 5, 65789, 65592, 327734, 3, 2, 0, 4, 131320, 6, 65789, 65592,
+        ];
+
+        let spirv = Spirv::new(&SAME_NAME).unwrap();
+
+        // The name alone doesn't identify a single entry point.
+        assert!(spirv.extract_entry_point("main").is_err());
+        assert!(spirv
+            .extract_entry_point_with_execution("main", ExecutionModel::GLCompute)
+            .is_err());
+
+        let extracted = spirv
+            .extract_entry_point_with_execution("main", ExecutionModel::Fragment)
+            .unwrap();
+        assert_eq!(extracted.stage_mask(), ShaderStages::FRAGMENT);
+        assert_eq!(extracted.iter_functions().count(), 1);
+    }
+
+    #[test]
+    fn function_blocks() {
+        /*
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+        %void = OpTypeVoid
+        %bool = OpTypeBool
+        %uint = OpTypeInt 32 0
+        %fn = OpTypeFunction %void
+        %fn_select = OpTypeFunction %uint %bool %uint
+        %true = OpConstantTrue %bool
+        %uint_1 = OpConstant %uint 1
+        %main = OpFunction %void None %fn
+        %main_entry = OpLabel
+        %result = OpFunctionCall %uint %select %true %uint_1
+        OpReturn
+        OpFunctionEnd
+        %select = OpFunction %uint None %fn_select
+        %condition = OpFunctionParameter %bool
+        %value = OpFunctionParameter %uint
+        %select_entry = OpLabel
+        OpSelectionMerge %merge None
+        OpBranchConditional %condition %then %merge
+        %then = OpLabel
+        OpReturnValue %value
+        %merge = OpLabel
+        OpReturnValue %uint_1
+        OpFunctionEnd
+        */
+        const FUNCTIONS: &[u32] = &[
+            119734787, 65536, 0, 17, 0, 131089, 1, 196622, 0, 1, 327695, 5, 1, 1852399981, 0,
+            393232, 1, 17, 1, 1, 1, 131091, 2, 131092, 3, 262165, 4, 32, 0, 196641, 5, 2, 327713,
+            6, 4, 3, 4, 196649, 3, 7, 262187, 4, 8, 1, 327734, 2, 1, 0, 5, 131320, 9, 393273, 4,
+            10, 11, 7, 8, 65789, 65592, 327734, 4, 11, 0, 6, 196663, 3, 12, 196663, 4, 13, 131320,
+            14, 196855, 15, 0, 262394, 12, 16, 15, 131320, 16, 131326, 13, 131320, 15, 131326, 8,
+            65592,
+        ];
+
+        let spirv = Spirv::new(FUNCTIONS).unwrap();
+        assert_eq!(spirv.iter_functions().count(), 2);
+
+        let main = spirv.function(Id(1));
+        assert_eq!(main.id(), Id(1));
+        assert_eq!(main.iter_parameters().count(), 0);
+        assert_eq!(main.iter_blocks().count(), 1);
+
+        let select = spirv.function(Id(11));
+        assert_eq!(select.id(), Id(11));
+        assert_eq!(select.iter_parameters().count(), 2);
+
+        let blocks: Vec<_> = select.iter_blocks().collect();
+        assert_eq!(blocks.len(), 3);
+        assert!(matches!(blocks[0][0], Instruction::Label { result_id } if result_id == Id(14)));
+        assert_eq!(blocks[0].len(), 3);
+        assert!(matches!(
+            blocks[2].last(),
+            Some(Instruction::ReturnValue { .. })
+        ));
+    }
+
+    #[test]
+    fn line_info() {
+        /*
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+        %file = OpString "shader.comp"
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %uint = OpTypeInt 32 0
+        %ptr = OpTypePointer Function %uint
+        %uint_1 = OpConstant %uint 1
+        %main = OpFunction %void None %fn
+        %label = OpLabel
+        OpLine %file 7 5
+        %var = OpVariable %ptr Function
+        OpLine %file 8 9
+        OpStore %var %uint_1
+        OpNoLine
+        OpReturn
+        OpFunctionEnd
+        */
+        const LINES: &[u32] = &[
+            119734787, 65536, 0, 10, 0, 131089, 1, 196622, 0, 1, 327695, 5, 1, 1852399981, 0,
+            393232, 1, 17, 1, 1, 1, 327687, 2, 1684105331, 1663988325, 7368047, 131091, 3, 196641,
+            4, 3, 262165, 5, 32, 0, 262176, 6, 7, 5, 262187, 5, 7, 1, 327734, 3, 1, 0, 4, 131320,
+            8, 262152, 2, 7, 5, 262203, 6, 9, 7, 262152, 2, 8, 9, 196670, 9, 7, 65853, 65789,
+            65592,
+        ];
+
+        let spirv = Spirv::new(LINES).unwrap();
+
+        // OpVariable
+        assert_eq!(spirv.line_info(13), Some(("shader.comp".to_owned(), 7, 5)));
+        // OpStore
+        assert_eq!(spirv.line_info(15), Some(("shader.comp".to_owned(), 8, 9)));
+        // OpTypeVoid, OpLabel and OpReturn
+        assert_eq!(spirv.line_info(5), None);
+        assert_eq!(spirv.line_info(11), None);
+        assert_eq!(spirv.line_info(17), None);
+    }
+
+    #[test]
+    fn kernel_entry_point() {
+        /*
+        OpCapability Addresses
+        OpCapability Kernel
+        OpCapability Shader
+        OpMemoryModel Physical32 OpenCL
+        OpEntryPoint Kernel %kernel "kernel_main"
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %kernel = OpFunction %void None %fn
+        %l1 = OpLabel
+        OpReturn
+        OpFunctionEnd
+        %main = OpFunction %void None %fn
+        %l2 = OpLabel
+        OpReturn
+        OpFunctionEnd
+        */
+        const KERNEL: &[u32] = &[
+            119734787, 65536, 0, 7, 0, 131089, 4, 131089, 6, 131089, 1, 196622, 1, 2, 393231, 6, 1,
+            1852990827, 1834970213, 7235937, 327695, 5, 2, 1852399981, 0, 393232, 2, 17, 1, 1, 1,
+            131091, 3, 196641, 4, 3, 327734, 3, 1, 0, 4, 131320, 5, 65789, 65592, 327734, 3, 2, 0,
+            4, 131320, 6, 65789, 65592,
+        ];
+
+        let spirv = Spirv::new(KERNEL).unwrap();
+
+        // The kernel entry point is skipped.
+        let names: Vec<_> = reflect::entry_points(&spirv)
+            .map(|(_, info)| info.name)
+            .collect();
+        assert_eq!(names, ["main"]);
+        assert_eq!(spirv.stage_mask(), ShaderStages::COMPUTE);
+        assert_eq!(
+            spirv.unsupported_entry_points(),
+            [("kernel_main".to_owned(), ExecutionModel::Kernel)],
+        );
+
+        assert!(ShaderStage::try_from_execution_model(ExecutionModel::Kernel).is_err());
+        assert_eq!(
+            ShaderStage::try_from_execution_model(ExecutionModel::GLCompute).unwrap(),
+            ShaderStage::Compute,
+        );
+    }
+
+    #[test]
+    fn reflect_module() {
+        /*
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint Vertex %vs "vs_main"
+        OpEntryPoint Fragment %fs "fs_main"
+        OpExecutionMode %fs OriginUpperLeft
+        OpDecorate %ubo_t Block
+        OpMemberDecorate %ubo_t 0 Offset 0
+        OpDecorate %ubo DescriptorSet 0
+        OpDecorate %ubo Binding 0
+        OpDecorate %ssbo_t BufferBlock
+        OpMemberDecorate %ssbo_t 0 Offset 0
+        OpDecorate %ssbo DescriptorSet 1
+        OpDecorate %ssbo Binding 0
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %float = OpTypeFloat 32
+        %int = OpTypeInt 32 1
+        %ubo_t = OpTypeStruct %float
+        %ssbo_t = OpTypeStruct %float
+        %ptr_ubo = OpTypePointer Uniform %ubo_t
+        %ptr_ssbo = OpTypePointer Uniform %ssbo_t
+        %ptr_float = OpTypePointer Uniform %float
+        %ubo = OpVariable %ptr_ubo Uniform
+        %ssbo = OpVariable %ptr_ssbo Uniform
+        %int_0 = OpConstant %int 0
+        %vs = OpFunction %void None %fn
+        %l1 = OpLabel
+        %p1 = OpAccessChain %ptr_float %ubo %int_0
+        %v1 = OpLoad %float %p1
+        OpReturn
+        OpFunctionEnd
+        %fs = OpFunction %void None %fn
+        %l2 = OpLabel
+        %p2 = OpAccessChain %ptr_float %ubo %int_0
+        %v2 = OpLoad %float %p2
+        %p3 = OpAccessChain %ptr_float %ssbo %int_0
+        OpStore %p3 %v2
+        OpReturn
+        OpFunctionEnd
+        */
+        const VS_FS: &[u32] = &[
+            119734787, 66304, 0, 22, 0, 131089, 1, 196622, 0, 1, 327695, 0, 1, 1834972022, 7235937,
+            327695, 4, 2, 1834972006, 7235937, 196624, 2, 7, 196679, 3, 2, 327752, 3, 0, 35, 0,
+            262215, 4, 34, 0, 262215, 4, 33, 0, 196679, 5, 3, 327752, 5, 0, 35, 0, 262215, 6, 34,
+            1, 262215, 6, 33, 0, 131091, 7, 196641, 8, 7, 196630, 9, 32, 262165, 10, 32, 1, 196638,
+            3, 9, 196638, 5, 9, 262176, 11, 2, 3, 262176, 12, 2, 5, 262176, 13, 2, 9, 262203, 11,
+            4, 2, 262203, 12, 6, 2, 262187, 10, 14, 0, 327734, 7, 1, 0, 8, 131320, 15, 327745, 13,
+            16, 4, 14, 262205, 9, 17, 16, 65789, 65592, 327734, 7, 2, 0, 8, 131320, 18, 327745, 13,
+            19, 4, 14, 262205, 9, 20, 19, 327745, 13, 21, 6, 14, 196670, 21, 20, 65789, 65592,
+        ];
+
+        let spirv = Spirv::new(VS_FS).unwrap();
+        let reflection = spirv.reflect_module();
+
+        assert_eq!(reflection.version, Version::V1_3);
+        assert_eq!(reflection.capabilities, [Capability::Shader]);
+        assert!(reflection.extensions.is_empty());
+        assert!(!reflection.uses_storage_buffer_class);
+        assert_eq!(reflection.entry_points.len(), 2);
+        assert!(reflection.conflicting_bindings.is_empty());
+
+        let set_0 = &reflection.descriptor_binding_requirements[&0];
+        assert_eq!(set_0.len(), 1);
+        assert_eq!(
+            set_0[&0].stages,
+            ShaderStages::VERTEX | ShaderStages::FRAGMENT
+        );
+        assert!(set_0[&0]
+            .descriptor_types
+            .contains(&DescriptorType::UniformBuffer));
+
+        let set_1 = &reflection.descriptor_binding_requirements[&1];
+        assert_eq!(set_1.len(), 1);
+        assert_eq!(set_1[&0].stages, ShaderStages::FRAGMENT);
+        assert!(set_1[&0]
+            .descriptor_types
+            .contains(&DescriptorType::StorageBuffer));
+    }
+
+    #[test]
+    fn reflect_module_conflicting_bindings() {
+        /*
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint Vertex %vs "vs_main"
+        OpEntryPoint Fragment %fs "fs_main"
+        OpExecutionMode %fs OriginUpperLeft
+        OpDecorate %ubo_t Block
+        OpMemberDecorate %ubo_t 0 Offset 0
+        OpDecorate %ubo DescriptorSet 0
+        OpDecorate %ubo Binding 0
+        OpDecorate %ssbo_t BufferBlock
+        OpMemberDecorate %ssbo_t 0 Offset 0
+        OpDecorate %ssbo DescriptorSet 0
+        OpDecorate %ssbo Binding 0
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %float = OpTypeFloat 32
+        %int = OpTypeInt 32 1
+        %ubo_t = OpTypeStruct %float
+        %ssbo_t = OpTypeStruct %float
+        %ptr_ubo = OpTypePointer Uniform %ubo_t
+        %ptr_ssbo = OpTypePointer Uniform %ssbo_t
+        %ptr_float = OpTypePointer Uniform %float
+        %ubo = OpVariable %ptr_ubo Uniform
+        %ssbo = OpVariable %ptr_ssbo Uniform
+        %int_0 = OpConstant %int 0
+        %float_0 = OpConstant %float 0
+        %vs = OpFunction %void None %fn
+        %l1 = OpLabel
+        %p1 = OpAccessChain %ptr_float %ubo %int_0
+        %v1 = OpLoad %float %p1
+        OpReturn
+        OpFunctionEnd
+        %fs = OpFunction %void None %fn
+        %l2 = OpLabel
+        %p2 = OpAccessChain %ptr_float %ssbo %int_0
+        OpStore %p2 %float_0
+        OpReturn
+        OpFunctionEnd
+        */
+        const VS_FS: &[u32] = &[
+            119734787, 66304, 0, 21, 0, 131089, 1, 196622, 0, 1, 327695, 0, 1, 1834972022, 7235937,
+            327695, 4, 2, 1834972006, 7235937, 196624, 2, 7, 196679, 3, 2, 327752, 3, 0, 35, 0,
+            262215, 4, 34, 0, 262215, 4, 33, 0, 196679, 5, 3, 327752, 5, 0, 35, 0, 262215, 6, 34,
+            0, 262215, 6, 33, 0, 131091, 7, 196641, 8, 7, 196630, 9, 32, 262165, 10, 32, 1, 196638,
+            3, 9, 196638, 5, 9, 262176, 11, 2, 3, 262176, 12, 2, 5, 262176, 13, 2, 9, 262203, 11,
+            4, 2, 262203, 12, 6, 2, 262187, 10, 14, 0, 262187, 9, 15, 0, 327734, 7, 1, 0, 8,
+            131320, 16, 327745, 13, 17, 4, 14, 262205, 9, 18, 17, 65789, 65592, 327734, 7, 2, 0, 8,
+            131320, 19, 327745, 13, 20, 6, 14, 196670, 20, 15, 65789, 65592,
+        ];
+
+        // The vertex shader uses a uniform buffer, and the fragment shader a storage buffer, at
+        // the same binding.
+        let spirv = Spirv::new(VS_FS).unwrap();
+        let reflection = spirv.reflect_module();
+        assert_eq!(reflection.conflicting_bindings, [(0, 0)]);
+
+        let reqs = &reflection.descriptor_binding_requirements[&0][&0];
+        assert_eq!(reqs.stages, ShaderStages::VERTEX | ShaderStages::FRAGMENT);
+        assert!(reqs
+            .descriptor_types
+            .contains(&DescriptorType::UniformBuffer));
+        assert!(reqs
+            .descriptor_types
+            .contains(&DescriptorType::StorageBuffer));
+    }
+
+    #[test]
+    fn validate_version_against_capabilities() {
+        /*
+        OpCapability Shader
+        OpCapability VulkanMemoryModel
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %main = OpFunction %void None %fn
+        %l = OpLabel
+        OpReturn
+        OpFunctionEnd
+        */
+        const SPIRV_1_0: &[u32] = &[
+            119734787, 65536, 0, 5, 0, 131089, 1, 131089, 5345, 196622, 0, 1, 327695, 5, 1,
+            1852399981, 0, 393232, 1, 17, 1, 1, 1, 131091, 2, 196641, 3, 2, 327734, 2, 1, 0, 3,
+            131320, 4, 65789, 65592,
+        ];
+
+        // Same module, but with `OpExtension "SPV_KHR_vulkan_memory_model"`.
+        const SPIRV_1_0_WITH_EXTENSION: &[u32] = &[
+            119734787, 65536, 0, 5, 0, 131089, 1, 524298, 1599492179, 1599227979, 1802270070,
+            1834970721, 1919905125, 1869438841, 7103844, 131089, 5345, 196622, 0, 1, 327695, 5, 1,
+            1852399981, 0, 393232, 1, 17, 1, 1, 1, 131091, 2, 196641, 3, 2, 327734, 2, 1, 0, 3,
+            131320, 4, 65789, 65592,
+        ];
+
+        // `VulkanMemoryModel` is only core in SPIR-V 1.5.
+        let spirv = Spirv::new(SPIRV_1_0).unwrap();
+        let err = spirv.validate_version_against_capabilities().unwrap_err();
+        assert_eq!(err.vuids, &["VUID-VkShaderModuleCreateInfo-pCode-01379"]);
+
+        let spirv = Spirv::new(SPIRV_1_0_WITH_EXTENSION).unwrap();
+        assert!(spirv.validate_version_against_capabilities().is_ok());
+    }
+
+    #[test]
+    fn validate_block_layouts() {
+        // A push constant block with two `vec4` members.
+        let push_constants = |second_offset| {
+            let mut builder = SpirvBuilder::new(ExecutionModel::GLCompute, "main");
+            builder.execution_mode(ExecutionMode::LocalSize {
+                x_size: 1,
+                y_size: 1,
+                z_size: 1,
+            });
+            let float = builder.type_float(32);
+            let vec4 = builder.type_vector(float, 4);
+            let block = builder.type_struct(vec![vec4, vec4]);
+            builder.decorate(block, Decoration::Block);
+            builder.member_decorate(block, 0, Decoration::Offset { byte_offset: 0 });
+            builder.member_decorate(
+                block,
+                1,
+                Decoration::Offset {
+                    byte_offset: second_offset,
+                },
+            );
+            builder.variable(StorageClass::PushConstant, block);
+
+            Spirv::new(&builder.build()).unwrap()
+        };
+
+        assert!(push_constants(16).validate_block_layouts().is_ok());
+        assert!(push_constants(8).validate_block_layouts().is_err());
+
+        // A push constant block with an array whose length is a specialization constant, followed
+        // by a member that would overlap the array at the constant's default value.
+        let mut builder = SpirvBuilder::new(ExecutionModel::GLCompute, "main");
+        builder.execution_mode(ExecutionMode::LocalSize {
+            x_size: 1,
+            y_size: 1,
+            z_size: 1,
+        });
+        let uint = builder.type_int(32, false);
+        let length = builder.new_id();
+        builder.global(Instruction::SpecConstant {
+            result_type_id: uint,
+            result_id: length,
+            value: vec![4],
+        });
+        let array = builder.new_id();
+        builder.global(Instruction::TypeArray {
+            result_id: array,
+            element_type: uint,
+            length,
+        });
+        builder.decorate(array, Decoration::ArrayStride { array_stride: 4 });
+        let block = builder.type_struct(vec![array, uint]);
+        builder.decorate(block, Decoration::Block);
+        builder.member_decorate(block, 0, Decoration::Offset { byte_offset: 0 });
+        builder.member_decorate(block, 1, Decoration::Offset { byte_offset: 4 });
+        builder.variable(StorageClass::PushConstant, block);
+
+        let spirv = Spirv::new(&builder.build()).unwrap();
+        assert!(spirv.validate_block_layouts().is_ok());
+    }
+
+    #[test]
+    fn image_scalar_width() {
+        // A fragment shader that loads a `f16sampler2D` from `SPV_AMD_gpu_shader_half_float_fetch`.
+        let mut builder = SpirvBuilder::new(ExecutionModel::Fragment, "main");
+        builder.capability(Capability::Float16ImageAMD);
+        builder.execution_mode(ExecutionMode::OriginUpperLeft);
+        let half = builder.type_float(16);
+        let image = builder.new_id();
+        builder.global(Instruction::TypeImage {
+            result_id: image,
+            sampled_type: half,
+            dim: Dim::Dim2D,
+            depth: 0,
+            arrayed: 0,
+            ms: 0,
+            sampled: 1,
+            image_format: ImageFormat::Unknown,
+            access_qualifier: None,
+        });
+        let sampled_image = builder.new_id();
+        builder.global(Instruction::TypeSampledImage {
+            result_id: sampled_image,
+            image_type: image,
+        });
+        let texture = builder.variable(StorageClass::UniformConstant, sampled_image);
+        builder.decorate(texture, Decoration::DescriptorSet { descriptor_set: 0 });
+        builder.decorate(texture, Decoration::Binding { binding_point: 0 });
+        let result_id = builder.new_id();
+        builder.instruction(Instruction::Load {
+            result_type_id: sampled_image,
+            result_id,
+            pointer: texture,
+            memory_access: None,
+        });
+
+        let spirv = Spirv::new(&builder.build()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+        let reqs = &info.descriptor_binding_requirements[&(0, 0)];
+
+        assert_eq!(
+            reqs.descriptor_types,
+            [DescriptorType::CombinedImageSampler]
+        );
+        assert_eq!(reqs.image_scalar_type, Some(NumericType::Float));
+        assert_eq!(reqs.image_scalar_width, Some(16));
+    }
+
+    #[test]
+    fn workgroup_shared_size() {
+        // Before SPIR-V 1.4, only the variables that the entry point references are counted.
+        let mut builder = SpirvBuilder::new(ExecutionModel::GLCompute, "main");
+        builder.execution_mode(ExecutionMode::LocalSize {
+            x_size: 64,
+            y_size: 1,
+            z_size: 1,
+        });
+        let uint = builder.type_int(32, false);
+        let vec4 = builder.type_vector(uint, 4);
+        let used = builder.variable(StorageClass::Workgroup, vec4);
+        builder.variable(StorageClass::Workgroup, uint);
+        let result_id = builder.new_id();
+        builder.instruction(Instruction::Load {
+            result_type_id: vec4,
+            result_id,
+            pointer: used,
+            memory_access: None,
+        });
+
+        let spirv = Spirv::new(&builder.build()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+        assert_eq!(info.workgroup_shared_size, Some(16));
+
+        // The length of an array that is a specialization constant operation is not known.
+        let length = builder.new_id();
+        let spec_length = builder.new_id();
+        let two = builder.new_id();
+        builder.global(Instruction::SpecConstant {
+            result_type_id: uint,
+            result_id: spec_length,
+            value: vec![4],
+        });
+        builder.global(Instruction::Constant {
+            result_type_id: uint,
+            result_id: two,
+            value: vec![2],
+        });
+        builder.global(Instruction::SpecConstantOp {
+            result_type_id: uint,
+            result_id: length,
+            opcode: SpecConstantInstruction::IMul {
+                operand1: spec_length,
+                operand2: two,
+            },
+        });
+        let array = builder.new_id();
+        builder.global(Instruction::TypeArray {
+            result_id: array,
+            element_type: uint,
+            length,
+        });
+        let array_variable = builder.variable(StorageClass::Workgroup, array);
+        let result_id = builder.new_id();
+        builder.instruction(Instruction::Load {
+            result_type_id: array,
+            result_id,
+            pointer: array_variable,
+            memory_access: None,
+        });
+
+        let spirv = Spirv::new(&builder.build()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+        assert_eq!(info.workgroup_shared_size, None);
+    }
+
+    #[test]
+    fn push_constant_pointer_member() {
+        // A `buffer_reference` in a push constant block is a 64-bit device address.
+        let mut builder = SpirvBuilder::new(ExecutionModel::GLCompute, "main");
+        builder.set_version(Version::V1_5);
+        builder.capability(Capability::PhysicalStorageBufferAddresses);
+        builder.execution_mode(ExecutionMode::LocalSize {
+            x_size: 64,
+            y_size: 1,
+            z_size: 1,
+        });
+        let uint = builder.type_int(32, false);
+        let buffer = builder.type_struct(vec![uint]);
+        builder.decorate(buffer, Decoration::Block);
+        builder.member_decorate(buffer, 0, Decoration::Offset { byte_offset: 0 });
+        let buffer_pointer = builder.type_pointer(StorageClass::PhysicalStorageBuffer, buffer);
+        let block = builder.type_struct(vec![buffer_pointer, uint]);
+        builder.decorate(block, Decoration::Block);
+        builder.member_decorate(block, 0, Decoration::Offset { byte_offset: 0 });
+        builder.member_decorate(block, 1, Decoration::Offset { byte_offset: 8 });
+
+        // A push constant block that the entry point doesn't use is ignored.
+        let unused_block = builder.type_struct(vec![uint]);
+        builder.decorate(unused_block, Decoration::Block);
+        builder.member_decorate(unused_block, 0, Decoration::Offset { byte_offset: 0 });
+        builder.variable(StorageClass::PushConstant, unused_block);
+        let variable = builder.variable(StorageClass::PushConstant, block);
+        builder.interface(variable);
+
+        let spirv = Spirv::new(&builder.build()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+        let members: Vec<_> = info
+            .push_constant_members
+            .iter()
+            .map(|&(offset, ty)| (offset, ty.base_type, ty.width))
+            .collect();
+        assert_eq!(
+            members,
+            [(0, NumericType::Uint, 64), (8, NumericType::Uint, 32)],
+        );
+    }
+
+    #[test]
+    fn shader_record_pointer_member() {
+        let mut builder = SpirvBuilder::new(ExecutionModel::ClosestHitKHR, "main");
+        builder.set_version(Version::V1_5);
+        builder.capability(Capability::RayTracingKHR);
+        builder.capability(Capability::PhysicalStorageBufferAddresses);
+        let uint = builder.type_int(32, false);
+        let buffer = builder.type_struct(vec![uint]);
+        builder.decorate(buffer, Decoration::Block);
+        builder.member_decorate(buffer, 0, Decoration::Offset { byte_offset: 0 });
+        let buffer_pointer = builder.type_pointer(StorageClass::PhysicalStorageBuffer, buffer);
+        let record = builder.type_struct(vec![buffer_pointer]);
+        builder.decorate(record, Decoration::Block);
+        builder.member_decorate(record, 0, Decoration::Offset { byte_offset: 0 });
+        let variable = builder.variable(StorageClass::ShaderRecordBufferKHR, record);
+        builder.interface(variable);
+
+        let spirv = Spirv::new(&builder.build()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+        let members: Vec<_> = info
+            .shader_record_members
+            .iter()
+            .map(|&(offset, ty)| (offset, ty.base_type, ty.width))
+            .collect();
+        assert_eq!(members, [(0, NumericType::Uint, 64)]);
+    }
+
+    #[test]
+    fn shader_record_members_per_entry_point() {
+        /*
+        OpCapability RayTracingKHR
+        OpCapability PhysicalStorageBufferAddresses
+        OpExtension "SPV_KHR_ray_tracing"
+        OpMemoryModel PhysicalStorageBuffer64 GLSL450
+        OpEntryPoint ClosestHitKHR %chit "chit" %record_a
+        OpEntryPoint MissKHR %miss "miss" %record_b
+        OpDecorate %Buf Block
+        OpMemberDecorate %Buf 0 Offset 0
+        OpDecorate %RecordA Block
+        OpMemberDecorate %RecordA 0 Offset 0
+        OpMemberDecorate %RecordA 1 Offset 8
+        OpDecorate %RecordB Block
+        OpMemberDecorate %RecordB 0 Offset 0
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %uint = OpTypeInt 32 0
+        %float = OpTypeFloat 32
+        %v4 = OpTypeVector %float 4
+        OpTypeForwardPointer %pbuf PhysicalStorageBuffer
+        %Buf = OpTypeStruct %uint
+        %pbuf = OpTypePointer PhysicalStorageBuffer %Buf
+        %RecordA = OpTypeStruct %pbuf %uint
+        %RecordB = OpTypeStruct %v4
+        %pa = OpTypePointer ShaderRecordBufferKHR %RecordA
+        %pb = OpTypePointer ShaderRecordBufferKHR %RecordB
+        %record_a = OpVariable %pa ShaderRecordBufferKHR
+        %record_b = OpVariable %pb ShaderRecordBufferKHR
+        %chit = OpFunction %void None %fn
+        %l1 = OpLabel
+        OpReturn
+        OpFunctionEnd
+        %miss = OpFunction %void None %fn
+        %l2 = OpLabel
+        OpReturn
+        OpFunctionEnd
+        */
+        const TWO_RECORDS: &[u32] = &[
+            119734787, 66816, 0, 18, 0, 131089, 4479, 131089, 5347, 393226, 1599492179, 1599227979,
+            1601790322, 1667330676, 6778473, 196622, 5348, 1, 393231, 5316, 1, 1953065059, 0, 2,
+            393231, 5317, 3, 1936943469, 0, 4, 196679, 5, 2, 327752, 5, 0, 35, 0, 196679, 6, 2,
+            327752, 6, 0, 35, 0, 327752, 6, 1, 35, 8, 196679, 7, 2, 327752, 7, 0, 35, 0, 131091, 8,
+            196641, 9, 8, 262165, 10, 32, 0, 196630, 11, 32, 262167, 12, 11, 4, 196647, 13, 5349,
+            196638, 5, 10, 262176, 13, 5349, 5, 262174, 6, 13, 10, 196638, 7, 12, 262176, 14, 5343,
+            6, 262176, 15, 5343, 7, 262203, 14, 2, 5343, 262203, 15, 4, 5343, 327734, 8, 1, 0, 9,
+            131320, 16, 65789, 65592, 327734, 8, 3, 0, 9, 131320, 17, 65789, 65592,
+        ];
+
+        let spirv = Spirv::new(TWO_RECORDS).unwrap();
+        let members: Vec<_> = reflect::entry_points(&spirv)
+            .map(|(_, info)| {
+                let members: Vec<_> = info
+                    .shader_record_members
+                    .iter()
+                    .map(|&(offset, ty)| (offset, ty.base_type, ty.width, ty.num_components))
+                    .collect();
+                (info.name, members)
+            })
+            .collect();
+        assert_eq!(
+            members,
+            [
+                (
+                    "chit".to_owned(),
+                    vec![(0, NumericType::Uint, 64, 1), (8, NumericType::Uint, 32, 1)],
+                ),
+                ("miss".to_owned(), vec![(0, NumericType::Float, 32, 4)]),
+            ],
+        );
+    }
+
+    #[test]
+    fn aliased_binding_conflicting_requirements() {
+        // A `texture2D` and an `itexture2D` that are both bound to set 0, binding 0.
+        let mut builder = SpirvBuilder::new(ExecutionModel::Fragment, "main");
+        builder.execution_mode(ExecutionMode::OriginUpperLeft);
+        let float = builder.type_float(32);
+        let int = builder.type_int(32, true);
+
+        for sampled_type in [float, int] {
+            let image = builder.new_id();
+            builder.global(Instruction::TypeImage {
+                result_id: image,
+                sampled_type,
+                dim: Dim::Dim2D,
+                depth: 0,
+                arrayed: 0,
+                ms: 0,
+                sampled: 1,
+                image_format: ImageFormat::Unknown,
+                access_qualifier: None,
+            });
+            let texture = builder.variable(StorageClass::UniformConstant, image);
+            builder.decorate(texture, Decoration::DescriptorSet { descriptor_set: 0 });
+            builder.decorate(texture, Decoration::Binding { binding_point: 0 });
+            let result_id = builder.new_id();
+            builder.instruction(Instruction::Load {
+                result_type_id: image,
+                result_id,
+                pointer: texture,
+                memory_access: None,
+            });
+        }
+
+        let spirv = Spirv::new(&builder.build()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+        let reqs = &info.descriptor_binding_requirements[&(0, 0)];
+
+        // Neither scalar type is required, rather than only that of the first variable.
+        assert_eq!(reqs.descriptor_types, [DescriptorType::SampledImage]);
+        assert_eq!(reqs.image_scalar_type, None);
+        assert_eq!(reqs.image_scalar_width, Some(32));
+    }
+
+    #[test]
+    fn frag_coord_pixel_center_integer() {
+        let mut builder = SpirvBuilder::new(ExecutionModel::Fragment, "main");
+        builder.execution_mode(ExecutionMode::OriginUpperLeft);
+        builder.execution_mode(ExecutionMode::PixelCenterInteger);
+        let float = builder.type_float(32);
+        let vec4 = builder.type_vector(float, 4);
+        let frag_coord = builder.variable(StorageClass::Input, vec4);
+        builder.decorate(
+            frag_coord,
+            Decoration::BuiltIn {
+                built_in: BuiltIn::FragCoord,
+            },
+        );
+
+        let spirv = Spirv::new(&builder.build()).unwrap();
+        let (_, info) = reflect::entry_points(&spirv).next().unwrap();
+
+        assert!(info.uses_frag_coord);
+        assert!(info.pixel_center_integer);
+        assert!(!info.sample_interlock);
+    }
+
+    /*
+    OpCapability Shader
+    OpMemoryModel Logical GLSL450
+    OpEntryPoint GLCompute %main "main"
+    OpExecutionMode %main LocalSize 1 1 1
+    OpDecorate %ubo Block
+    OpMemberDecorate %ubo 0 Offset 0
+    OpDecorate %u DescriptorSet 0
+    OpDecorate %u Binding 0
+    OpDecorate %pcb Block
+    OpMemberDecorate %pcb 0 Offset 0
+    %void = OpTypeVoid
+    %fn = OpTypeFunction %void
+    %float = OpTypeFloat 32
+    %ubo = OpTypeStruct %float
+    %pcb = OpTypeStruct %float
+    %ptr_ubo = OpTypePointer Uniform %ubo
+    %ptr_pcb = OpTypePointer PushConstant %pcb
+    %u = OpVariable %ptr_ubo Uniform
+    %pc = OpVariable %ptr_pcb PushConstant
+    %main = OpFunction %void None %fn
+    %label = OpLabel
+    OpReturn
+    OpFunctionEnd
+    */
+    const UNIFORM_AND_PUSH_CONSTANT: [u32; 84] = [
+        119734787, 65536, 0, 12, 0, 131089, 1, 196622, 0, 1, 327695, 5, 1, 1852399981, 0, 393232,
+        1, 17, 1, 1, 1, 196679, 2, 2, 327752, 2, 0, 35, 0, 262215, 3, 34, 0, 262215, 3, 33, 0,
+        196679, 4, 2, 327752, 4, 0, 35, 0, 131091, 5, 196641, 6, 5, 196630, 7, 32, 196638, 2, 7,
+        196638, 4, 7, 262176, 8, 2, 2, 262176, 9, 9, 4, 262203, 8, 3, 2, 262203, 9, 10, 9, 327734,
+        5, 1, 0, 6, 131320, 11, 65789, 65592,
+    ];
+
+    #[test]
+    fn storage_classes() {
+        let spirv = Spirv::new(&UNIFORM_AND_PUSH_CONSTANT).unwrap();
+        let storage_classes = spirv.storage_classes();
+
+        assert_eq!(storage_classes.len(), 2);
+        assert!(storage_classes.contains(&StorageClass::Uniform));
+        assert!(storage_classes.contains(&StorageClass::PushConstant));
+        assert!(!storage_classes.contains(&StorageClass::PhysicalStorageBuffer));
+    }
+
+    #[test]
+    fn uses_storage_buffer_class() {
+        /*
+        OpCapability Shader
+        OpExtension "SPV_KHR_storage_buffer_storage_class"
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+        OpDecorate %ssbo_t Block
+        OpMemberDecorate %ssbo_t 0 Offset 0
+        OpDecorate %ssbo DescriptorSet 0
+        OpDecorate %ssbo Binding 0
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %float = OpTypeFloat 32
+        %ssbo_t = OpTypeStruct %float
+        %ptr_ssbo = OpTypePointer StorageBuffer %ssbo_t
+        %ssbo = OpVariable %ptr_ssbo StorageBuffer
+        %main = OpFunction %void None %fn
+        %label = OpLabel
+        OpReturn
+        OpFunctionEnd
+        */
+        const STORAGE_BUFFER_CLASS: [u32; 76] = [
+            119734787, 65536, 0, 9, 0, 131089, 1, 720906, 1599492179, 1599227979, 1919906931,
+            1600481121, 1717990754, 1935635045, 1634889588, 1667196263, 1936941420, 0, 196622, 0,
+            1, 327695, 5, 1, 1852399981, 0, 393232, 1, 17, 1, 1, 1, 196679, 2, 2, 327752, 2, 0, 35,
+            0, 262215, 3, 34, 0, 262215, 3, 33, 0, 131091, 4, 196641, 5, 4, 196630, 6, 32, 196638,
+            2, 6, 262176, 7, 12, 2, 262203, 7, 3, 12, 327734, 4, 1, 0, 5, 131320, 8, 65789, 65592,
+        ];
+
+        let reflection = Spirv::new(&STORAGE_BUFFER_CLASS).unwrap().reflect_module();
+        assert!(reflection.uses_storage_buffer_class);
+
+        // The `reflect_module` test covers a `BufferBlock` storage buffer in the `Uniform`
+        // storage class.
+        let reflection = Spirv::new(&UNIFORM_AND_PUSH_CONSTANT)
+            .unwrap()
+            .reflect_module();
+        assert!(!reflection.uses_storage_buffer_class);
+    }
+
+    #[test]
+    fn new_lenient_trailing_zeros() {
+        let mut words = MULTIPLE_ENTRY_POINTS.to_vec();
+        words.extend([0, 0]);
+
+        let spirv = Spirv::new_lenient(&words).unwrap();
+        assert_eq!(
+            spirv.to_words(),
+            Spirv::new(&MULTIPLE_ENTRY_POINTS).unwrap().to_words()
+        );
+        assert_eq!(reflect::entry_points(&spirv).count(), 2);
+
+        // Trailing words that aren't zero are still rejected.
+        words.push(1);
+        assert!(Spirv::new_lenient(&words).is_err());
+    }
+}